@@ -1,4 +1,4 @@
-#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_render::fullscreen_vertex_shader::FullscreenVertexOutput
 #ifdef SRGB_TO_LINEAR
 #import bevy_render::color_operations::srgb_to_linear
 #endif
@@ -0,0 +1,32 @@
+//! Benchmarks syncing entities to the render world.
+//!
+//! Run with `--features multi_threaded` to check the added entities in parallel.
+
+use bevy_app::App;
+use bevy_ecs::schedule::ScheduleLabel;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use robin_render::{
+    Render, RenderApp, extract_plugin::ExtractPlugin, sync_world::SyncToRenderWorld,
+};
+
+const ENTITY_COUNT: usize = 50_000;
+
+fn sync_new_entities(c: &mut Criterion) {
+    c.bench_function("sync_50k_new_entities", |b| {
+        b.iter_batched(
+            || {
+                let mut app = App::new();
+                app.add_plugins(ExtractPlugin::default());
+                app.get_sub_app_mut(RenderApp).unwrap().update_schedule = Some(Render.intern());
+                app.world_mut()
+                    .spawn_batch((0..ENTITY_COUNT).map(|_| SyncToRenderWorld));
+                app
+            },
+            |mut app| app.update(),
+            BatchSize::PerIteration,
+        );
+    });
+}
+
+criterion_group!(benches, sync_new_entities);
+criterion_main!(benches);
@@ -0,0 +1,17 @@
+// Copies the depth of a region of a texture to a buffer, for the depth formats whose depth
+// aspect can't be copied to a buffer directly, like `Depth24Plus`.
+//
+// We issue one thread per texel of the region, writing the values row by row.
+
+@group(0) @binding(0) var depth_texture: texture_depth_2d;
+// The origin of the region in `xy` and its size in `zw`.
+@group(0) @binding(1) var<uniform> region: vec4<u32>;
+@group(0) @binding(2) var<storage, read_write> depth_values: array<f32>;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (any(id.xy >= region.zw)) {
+        return;
+    }
+    depth_values[id.y * region.z + id.x] = textureLoad(depth_texture, region.xy + id.xy, 0);
+}
@@ -0,0 +1,67 @@
+use bevy_app::{Plugin, PreUpdate};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::{resource::Resource, system::Res};
+use bevy_platform::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of render entities spawned or despawned by entity sync per frame
+static ENTITIES_SYNCED: DiagnosticPath =
+    DiagnosticPath::const_new("render/extract/entities_synced");
+
+/// Number of components extracted by [`ExtractComponentPlugin`](crate::extract_component::ExtractComponentPlugin)s per frame
+static COMPONENTS_EXTRACTED: DiagnosticPath =
+    DiagnosticPath::const_new("render/extract/components_extracted");
+
+/// Publishes how many entities were synced and how many components were extracted to the
+/// render world every frame.
+pub struct ExtractThroughputDiagnosticPlugin;
+
+impl ExtractThroughputDiagnosticPlugin {
+    /// Get the [`DiagnosticPath`] for synced entities
+    pub fn entities_synced_diagnostic_path() -> &'static DiagnosticPath {
+        &ENTITIES_SYNCED
+    }
+    /// Get the [`DiagnosticPath`] for extracted components
+    pub fn components_extracted_diagnostic_path() -> &'static DiagnosticPath {
+        &COMPONENTS_EXTRACTED
+    }
+}
+
+impl Plugin for ExtractThroughputDiagnosticPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        app.register_diagnostic(Diagnostic::new(ENTITIES_SYNCED.clone()).with_suffix(" entities"))
+            .register_diagnostic(
+                Diagnostic::new(COMPONENTS_EXTRACTED.clone()).with_suffix(" components"),
+            )
+            .init_resource::<ExtractThroughputMeasurements>()
+            .add_systems(PreUpdate, add_extract_throughput_measurement);
+    }
+}
+
+/// Counts of the last extraction, in the main world. Only present if
+/// [`ExtractThroughputDiagnosticPlugin`] was added.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct ExtractThroughputMeasurements {
+    entities_synced: AtomicUsize,
+    components_extracted: AtomicUsize,
+}
+
+impl ExtractThroughputMeasurements {
+    pub(crate) fn record_synced(&self, count: usize) {
+        self.entities_synced.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_extracted(&self, count: usize) {
+        self.components_extracted
+            .fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+fn add_extract_throughput_measurement(
+    mut diagnostics: Diagnostics,
+    measurements: Res<ExtractThroughputMeasurements>,
+) {
+    let entities_synced = measurements.entities_synced.swap(0, Ordering::Relaxed);
+    let components_extracted = measurements.components_extracted.swap(0, Ordering::Relaxed);
+    diagnostics.add_measurement(&ENTITIES_SYNCED, || entities_synced as f64);
+    diagnostics.add_measurement(&COMPONENTS_EXTRACTED, || components_extracted as f64);
+}
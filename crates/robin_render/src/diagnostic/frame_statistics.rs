@@ -0,0 +1,423 @@
+use alloc::{collections::VecDeque, sync::Arc};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::{prelude::*, resource::Resource};
+use bevy_log::warn;
+use bevy_platform::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::Mutex;
+
+use crate::{Render, RenderApp, RenderSystems};
+
+/// Counts of the GPU work recorded during a frame, e.g. to explain on a debug overlay why a scene
+/// is slow.
+///
+/// Render passes created through [`TrackedRenderPass`](crate::render_phase::TrackedRenderPass)
+/// and buffer and texture writes through [`RenderQueue`](crate::renderer::RenderQueue) are counted
+/// automatically. Other work, like compute dispatches, can be counted with
+/// [`FrameStatisticsCounters::add`].
+///
+/// The counters are collected during [`RenderSystems::Cleanup`] and mirrored to the main world at
+/// the start of the next frame.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderFrameStatistics {
+    pub draw_calls: u64,
+    /// The number of instances drawn by non-indirect draw calls.
+    pub instances: u64,
+    pub dispatches: u64,
+    pub pipeline_switches: u64,
+    pub bind_group_sets: u64,
+    /// The number of bind group sets skipped because the bind group was already bound.
+    pub bind_group_sets_elided: u64,
+    pub buffer_writes: u64,
+    pub texture_writes: u64,
+    /// The bytes written to buffers and textures.
+    pub bytes_uploaded: u64,
+    /// The time spent submitting command buffers through
+    /// [`RenderQueue::submit`](crate::renderer::RenderQueue::submit).
+    pub submit_time: Duration,
+    /// The time spent waiting for swap chain textures in
+    /// [`prepare_windows`](crate::view::window::prepare_windows).
+    pub acquire_time: Duration,
+    /// The time spent presenting swap chain textures.
+    pub present_time: Duration,
+}
+
+/// The counters [`RenderFrameStatistics`] are accumulated in during a frame.
+///
+/// This is a render world resource, shared with the [`RenderDevice`](crate::renderer::RenderDevice)
+/// and [`RenderQueue`](crate::renderer::RenderQueue) it was created with, so every renderer counts
+/// its own work. Render passes count into a local [`RenderFrameStatistics`] and merge it here when
+/// they end, so recording stays cheap. Other work, like compute dispatches, can be counted with
+/// [`FrameStatisticsCounters::add`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct FrameStatisticsCounters(Arc<Counters>);
+
+#[derive(Debug, Default)]
+struct Counters {
+    draw_calls: AtomicU64,
+    instances: AtomicU64,
+    dispatches: AtomicU64,
+    pipeline_switches: AtomicU64,
+    bind_group_sets: AtomicU64,
+    bind_group_sets_elided: AtomicU64,
+    buffer_writes: AtomicU64,
+    texture_writes: AtomicU64,
+    bytes_uploaded: AtomicU64,
+    submit_nanos: AtomicU64,
+    acquire_nanos: AtomicU64,
+    present_nanos: AtomicU64,
+}
+
+impl FrameStatisticsCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `statistics` to the counters.
+    pub fn add(&self, statistics: &RenderFrameStatistics) {
+        let add = |counter: &AtomicU64, value: u64| {
+            if value > 0 {
+                counter.fetch_add(value, Ordering::Relaxed);
+            }
+        };
+        let counters = &self.0;
+        add(&counters.draw_calls, statistics.draw_calls);
+        add(&counters.instances, statistics.instances);
+        add(&counters.dispatches, statistics.dispatches);
+        add(&counters.pipeline_switches, statistics.pipeline_switches);
+        add(&counters.bind_group_sets, statistics.bind_group_sets);
+        add(
+            &counters.bind_group_sets_elided,
+            statistics.bind_group_sets_elided,
+        );
+        add(&counters.buffer_writes, statistics.buffer_writes);
+        add(&counters.texture_writes, statistics.texture_writes);
+        add(&counters.bytes_uploaded, statistics.bytes_uploaded);
+        add(
+            &counters.submit_nanos,
+            statistics.submit_time.as_nanos() as u64,
+        );
+        add(
+            &counters.acquire_nanos,
+            statistics.acquire_time.as_nanos() as u64,
+        );
+        add(
+            &counters.present_nanos,
+            statistics.present_time.as_nanos() as u64,
+        );
+    }
+
+    /// Returns the counters and resets them to zero.
+    pub fn take(&self) -> RenderFrameStatistics {
+        let take = |counter: &AtomicU64| counter.swap(0, Ordering::Relaxed);
+        let counters = &self.0;
+        RenderFrameStatistics {
+            draw_calls: take(&counters.draw_calls),
+            instances: take(&counters.instances),
+            dispatches: take(&counters.dispatches),
+            pipeline_switches: take(&counters.pipeline_switches),
+            bind_group_sets: take(&counters.bind_group_sets),
+            bind_group_sets_elided: take(&counters.bind_group_sets_elided),
+            buffer_writes: take(&counters.buffer_writes),
+            texture_writes: take(&counters.texture_writes),
+            bytes_uploaded: take(&counters.bytes_uploaded),
+            submit_time: Duration::from_nanos(take(&counters.submit_nanos)),
+            acquire_time: Duration::from_nanos(take(&counters.acquire_nanos)),
+            present_time: Duration::from_nanos(take(&counters.present_nanos)),
+        }
+    }
+}
+
+/// Runs `f`, returning its result and how long it took.
+pub(crate) fn timed<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = bevy_platform::time::Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// The history of a timed part of the frame, e.g. one of the points where the CPU waits on the
+/// GPU or the driver.
+#[derive(Clone, Debug, Default)]
+pub struct SyncPointTiming {
+    latest: Duration,
+    average: Duration,
+    window: VecDeque<Duration>,
+}
+
+impl SyncPointTiming {
+    /// How strongly a new frame pulls the [`average`](Self::average).
+    const SMOOTHING: f64 = 0.1;
+
+    /// The time of the latest frame.
+    pub fn latest(&self) -> Duration {
+        self.latest
+    }
+
+    /// The exponential moving average of the time per frame.
+    pub fn average(&self) -> Duration {
+        self.average
+    }
+
+    /// The worst time of the frames in the window.
+    pub fn worst(&self) -> Duration {
+        self.window.iter().copied().max().unwrap_or_default()
+    }
+
+    /// The number of frames in the window.
+    pub fn frames(&self) -> usize {
+        self.window.len()
+    }
+
+    /// The mean time of the frames in the window.
+    pub fn window_average(&self) -> Duration {
+        let total: Duration = self.window.iter().sum();
+        total / self.window.len().max(1) as u32
+    }
+
+    pub(crate) fn record(&mut self, time: Duration, window: usize) {
+        self.average = if self.window.is_empty() {
+            time
+        } else {
+            self.average
+                .mul_f64(1.0 - Self::SMOOTHING)
+                .saturating_add(time.mul_f64(Self::SMOOTHING))
+        };
+        self.latest = time;
+        self.window.push_back(time);
+        while self.window.len() > window.max(1) {
+            self.window.pop_front();
+        }
+    }
+}
+
+/// Smoothed timings of the CPU-side sync points of rendering, i.e. submitting command buffers,
+/// acquiring swap chain textures and presenting them.
+///
+/// High times here usually mean the CPU is waiting on the GPU or the compositor. Like
+/// [`RenderFrameStatistics`], this is updated in the render world and mirrored to the main world.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct RenderSyncTimings {
+    pub submit: SyncPointTiming,
+    pub acquire: SyncPointTiming,
+    pub present: SyncPointTiming,
+    warned_slow_submit: bool,
+}
+
+impl RenderSyncTimings {
+    fn record(&mut self, statistics: &RenderFrameStatistics, window: usize) {
+        self.submit.record(statistics.submit_time, window);
+        self.acquire.record(statistics.acquire_time, window);
+        self.present.record(statistics.present_time, window);
+    }
+
+    /// Returns `true` the first time the latest submit time exceeds `threshold`.
+    fn check_slow_submit(&mut self, threshold: Option<Duration>) -> bool {
+        let slow = threshold.is_some_and(|threshold| self.submit.latest > threshold);
+        if slow && !self.warned_slow_submit {
+            self.warned_slow_submit = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Configures [`RenderSyncTimings`], see [`RenderFrameStatisticsPlugin`].
+#[derive(Resource, Clone, Debug)]
+struct RenderSyncTimingSettings {
+    window: usize,
+    submit_warning_threshold: Option<Duration>,
+}
+
+/// Shares the latest [`RenderFrameStatistics`] and [`RenderSyncTimings`] of the render world with
+/// the main world.
+#[derive(Resource, Clone, Default)]
+struct RenderFrameStatisticsMutex(Arc<Mutex<Option<(RenderFrameStatistics, RenderSyncTimings)>>>);
+
+/// Collects [`RenderFrameStatistics`] and [`RenderSyncTimings`] every frame.
+pub struct RenderFrameStatisticsPlugin {
+    /// The number of frames [`SyncPointTiming::worst`] looks back.
+    pub timing_window: usize,
+    /// Logs a warning, once, when submitting the command buffers of a frame takes longer than
+    /// this. `None` disables the warning.
+    pub submit_warning_threshold: Option<Duration>,
+}
+
+impl Default for RenderFrameStatisticsPlugin {
+    fn default() -> Self {
+        Self {
+            timing_window: 120,
+            submit_warning_threshold: Some(Duration::from_millis(4)),
+        }
+    }
+}
+
+impl Plugin for RenderFrameStatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        let mutex = RenderFrameStatisticsMutex::default();
+        app.init_resource::<RenderFrameStatistics>()
+            .init_resource::<RenderSyncTimings>()
+            .insert_resource(mutex.clone())
+            .add_systems(PreUpdate, sync_frame_statistics);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<RenderFrameStatistics>()
+                .init_resource::<RenderSyncTimings>()
+                .insert_resource(RenderSyncTimingSettings {
+                    window: self.timing_window,
+                    submit_warning_threshold: self.submit_warning_threshold,
+                })
+                .insert_resource(mutex)
+                .add_systems(
+                    Render,
+                    collect_frame_statistics.in_set(RenderSystems::Cleanup),
+                );
+        }
+    }
+}
+
+fn collect_frame_statistics(
+    counters: Res<FrameStatisticsCounters>,
+    mut statistics: ResMut<RenderFrameStatistics>,
+    mut timings: ResMut<RenderSyncTimings>,
+    settings: Res<RenderSyncTimingSettings>,
+    mutex: Res<RenderFrameStatisticsMutex>,
+) {
+    *statistics = counters.take();
+    timings.record(&statistics, settings.window);
+    if timings.check_slow_submit(settings.submit_warning_threshold) {
+        warn!(
+            "Submitting command buffers took {:?} this frame, more than the {:?} threshold. \
+            Consider submitting fewer, larger batches of command buffers per frame.",
+            statistics.submit_time,
+            settings.submit_warning_threshold.unwrap_or_default(),
+        );
+    }
+    *mutex.0.lock().unwrap() = Some((*statistics, timings.clone()));
+}
+
+fn sync_frame_statistics(
+    mutex: Res<RenderFrameStatisticsMutex>,
+    mut statistics: ResMut<RenderFrameStatistics>,
+    mut timings: ResMut<RenderSyncTimings>,
+) {
+    if let Some((received, received_timings)) = mutex.0.lock().ok().and_then(|mut v| v.take()) {
+        *statistics = received;
+        *timings = received_timings;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{
+        renderer::{SUBMIT_DELAY, noop_render_resources},
+        settings::RenderResources,
+    };
+
+    #[test]
+    fn counters_merge_and_reset() {
+        let counters = FrameStatisticsCounters::new();
+        let pass = RenderFrameStatistics {
+            draw_calls: 3,
+            instances: 12,
+            pipeline_switches: 2,
+            bind_group_sets: 4,
+            bind_group_sets_elided: 5,
+            ..Default::default()
+        };
+        counters.add(&pass);
+        counters.add(&pass);
+        counters.add(&RenderFrameStatistics {
+            dispatches: 1,
+            buffer_writes: 2,
+            texture_writes: 1,
+            bytes_uploaded: 256,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            counters.take(),
+            RenderFrameStatistics {
+                draw_calls: 6,
+                instances: 24,
+                dispatches: 1,
+                pipeline_switches: 4,
+                bind_group_sets: 8,
+                bind_group_sets_elided: 10,
+                buffer_writes: 2,
+                texture_writes: 1,
+                bytes_uploaded: 256,
+                ..Default::default()
+            }
+        );
+        assert_eq!(counters.take(), RenderFrameStatistics::default());
+    }
+
+    #[test]
+    fn sync_timings_average_and_worst() {
+        let mut timing = SyncPointTiming::default();
+        for millis in [10, 20, 10, 10] {
+            timing.record(Duration::from_millis(millis), 3);
+        }
+        assert_eq!(timing.latest(), Duration::from_millis(10));
+        assert_eq!(timing.worst(), Duration::from_millis(20));
+        assert_eq!(timing.window_average(), Duration::from_nanos(13_333_333));
+        assert!(timing.average() > Duration::from_millis(10));
+        assert!(timing.average() < Duration::from_millis(20));
+
+        // The slow frame falls out of the window.
+        timing.record(Duration::from_millis(10), 3);
+        assert_eq!(timing.worst(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn slow_submit_warns_once() {
+        let counters = FrameStatisticsCounters::new();
+        let ((), submit_time) = timed(|| std::thread::sleep(Duration::from_millis(5)));
+        counters.add(&RenderFrameStatistics {
+            submit_time,
+            ..Default::default()
+        });
+        let statistics = counters.take();
+        assert!(statistics.submit_time >= Duration::from_millis(5));
+
+        let threshold = Some(Duration::from_millis(1));
+        let mut timings = RenderSyncTimings::default();
+        timings.record(&statistics, 120);
+        assert!(timings.check_slow_submit(threshold));
+        timings.record(&statistics, 120);
+        assert!(!timings.check_slow_submit(threshold));
+
+        let mut timings = RenderSyncTimings::default();
+        timings.record(&statistics, 120);
+        assert!(!timings.check_slow_submit(None));
+    }
+
+    #[test]
+    fn slow_submissions_are_collected() {
+        let RenderResources(device, queue, ..) = noop_render_resources(wgpu::Features::empty());
+        let mut world = World::new();
+        world.insert_resource(device.frame_statistics().clone());
+        world.init_resource::<RenderFrameStatistics>();
+        world.init_resource::<RenderSyncTimings>();
+        world.insert_resource(RenderSyncTimingSettings {
+            window: 120,
+            submit_warning_threshold: Some(Duration::from_millis(1)),
+        });
+        world.init_resource::<RenderFrameStatisticsMutex>();
+
+        SUBMIT_DELAY.set(Duration::from_millis(5));
+        queue.submit([]);
+        SUBMIT_DELAY.set(Duration::ZERO);
+        world.run_system_once(collect_frame_statistics).unwrap();
+
+        let statistics = world.resource::<RenderFrameStatistics>();
+        assert!(statistics.submit_time >= Duration::from_millis(5));
+        let timings = world.resource::<RenderSyncTimings>();
+        assert!(timings.submit.latest() >= Duration::from_millis(5));
+        assert!(timings.warned_slow_submit);
+    }
+}
@@ -27,6 +27,14 @@ const MAX_PIPELINE_STATISTICS: u32 = 128;
 const TIMESTAMP_SIZE: u64 = 8;
 const PIPELINE_STATISTICS_SIZE: u64 = 40;
 
+/// Converts the ticks between two timestamp queries to milliseconds.
+///
+/// Timestamps may be out of order, e.g. when the counter wraps around at its number of valid
+/// bits, which `wgpu` doesn't report. Such spans are clamped to zero rather than going negative.
+fn timestamp_span_ms(begin: u64, end: u64, timestamp_period_ns: f32) -> f64 {
+    end.saturating_sub(begin) as f64 * f64::from(timestamp_period_ns) / 1e6
+}
+
 struct DiagnosticsRecorderInternal {
     timestamp_period_ns: f32,
     features: Features,
@@ -605,9 +613,9 @@ impl FrameData {
 
             if let (Some(begin), Some(end)) = (span.begin_timestamp_index, span.end_timestamp_index)
             {
-                let begin = timestamps[begin as usize] as f64;
-                let end = timestamps[end as usize] as f64;
-                let value = (end - begin) * (timestamp_period_ns as f64) / 1e6;
+                let begin = timestamps[begin as usize];
+                let end = timestamps[end as usize];
+                let value = timestamp_span_ms(begin, end, timestamp_period_ns);
 
                 #[cfg(feature = "tracing-tracy")]
                 {
@@ -810,3 +818,17 @@ pub enum PassKind {
     Render,
     Compute,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::timestamp_span_ms;
+
+    #[test]
+    fn out_of_order_timestamp_spans_are_clamped() {
+        assert_eq!(timestamp_span_ms(1_000, 3_000, 1.0), 0.002);
+        assert_eq!(timestamp_span_ms(1_000, 3_000, 2.5), 0.005);
+        // Like a 36-bit counter wrapping between the queries.
+        assert_eq!(timestamp_span_ms((1 << 36) - 1_000, 1_000, 1.0), 0.0);
+        assert_eq!(timestamp_span_ms(3_000, 1_000, 1.0), 0.0);
+    }
+}
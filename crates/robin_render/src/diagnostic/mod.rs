@@ -3,9 +3,13 @@
 //! For more info, see [`RenderDiagnosticsPlugin`].
 
 mod erased_render_asset_diagnostic_plugin;
+pub(crate) mod extract_throughput_diagnostic_plugin;
+pub(crate) mod frame_statistics;
+pub(crate) use frame_statistics::timed;
 pub(crate) mod internal;
 mod mesh_allocator_diagnostic_plugin;
 mod render_asset_diagnostic_plugin;
+pub(crate) mod render_schedule_diagnostic_plugin;
 #[cfg(feature = "tracing-tracy")]
 mod tracy_gpu;
 
@@ -28,8 +32,15 @@ use crate::{
 use self::internal::{Pass, RenderDiagnosticsMutex, WriteTimestamp, sync_diagnostics};
 pub use self::{
     erased_render_asset_diagnostic_plugin::ErasedRenderAssetDiagnosticPlugin,
-    internal::DiagnosticsRecorder, mesh_allocator_diagnostic_plugin::MeshAllocatorDiagnosticPlugin,
+    extract_throughput_diagnostic_plugin::ExtractThroughputDiagnosticPlugin,
+    frame_statistics::{
+        FrameStatisticsCounters, RenderFrameStatistics, RenderFrameStatisticsPlugin,
+        RenderSyncTimings, SyncPointTiming,
+    },
+    internal::DiagnosticsRecorder,
+    mesh_allocator_diagnostic_plugin::MeshAllocatorDiagnosticPlugin,
     render_asset_diagnostic_plugin::RenderAssetDiagnosticPlugin,
+    render_schedule_diagnostic_plugin::RenderScheduleDiagnosticPlugin,
 };
 
 use crate::renderer::RenderDevice;
@@ -0,0 +1,153 @@
+use alloc::sync::Arc;
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::{prelude::*, resource::Resource};
+use bevy_platform::time::Instant;
+use core::time::Duration;
+use std::sync::Mutex;
+
+use crate::{Render, RenderApp, RenderSystems};
+
+/// CPU time of the extraction per frame, in milliseconds
+static EXTRACT_TIME: DiagnosticPath = DiagnosticPath::const_new("render/extract_ms");
+
+/// CPU time of [`RenderSystems::Queue`] per frame, in milliseconds
+static QUEUE_TIME: DiagnosticPath = DiagnosticPath::const_new("render/queue_ms");
+
+/// CPU time of [`RenderSystems::Prepare`] per frame, in milliseconds
+static PREPARE_TIME: DiagnosticPath = DiagnosticPath::const_new("render/prepare_ms");
+
+/// CPU time of [`RenderSystems::Render`] per frame, in milliseconds
+static RENDER_TIME: DiagnosticPath = DiagnosticPath::const_new("render/render_ms");
+
+/// Publishes how long the extraction and the main sets of the [`Render`] schedule take on the
+/// CPU every frame, so they can be inspected without a profiler, e.g. with
+/// [`LogDiagnosticsPlugin`](bevy_diagnostic::LogDiagnosticsPlugin).
+///
+/// The measurements of a frame are published in the main world at the start of the next one.
+pub struct RenderScheduleDiagnosticPlugin;
+
+impl RenderScheduleDiagnosticPlugin {
+    /// Get the [`DiagnosticPath`] for the extraction time
+    pub fn extract_time_diagnostic_path() -> &'static DiagnosticPath {
+        &EXTRACT_TIME
+    }
+    /// Get the [`DiagnosticPath`] for the time of [`RenderSystems::Queue`]
+    pub fn queue_time_diagnostic_path() -> &'static DiagnosticPath {
+        &QUEUE_TIME
+    }
+    /// Get the [`DiagnosticPath`] for the time of [`RenderSystems::Prepare`]
+    pub fn prepare_time_diagnostic_path() -> &'static DiagnosticPath {
+        &PREPARE_TIME
+    }
+    /// Get the [`DiagnosticPath`] for the time of [`RenderSystems::Render`]
+    pub fn render_time_diagnostic_path() -> &'static DiagnosticPath {
+        &RENDER_TIME
+    }
+}
+
+impl Plugin for RenderScheduleDiagnosticPlugin {
+    fn build(&self, app: &mut App) {
+        let measurements = RenderScheduleMeasurements::default();
+        for path in [&EXTRACT_TIME, &QUEUE_TIME, &PREPARE_TIME, &RENDER_TIME] {
+            app.register_diagnostic(Diagnostic::new(path.clone()).with_suffix("ms"));
+        }
+        app.insert_resource(measurements.clone())
+            .add_systems(PreUpdate, add_render_schedule_measurements);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.insert_resource(measurements).add_systems(
+            Render,
+            (
+                start_phase(RenderPhase::Queue)
+                    .after(RenderSystems::PrepareViews)
+                    .before(RenderSystems::Queue),
+                end_phase(RenderPhase::Queue)
+                    .after(RenderSystems::Queue)
+                    .before(RenderSystems::PhaseSort),
+                start_phase(RenderPhase::Prepare)
+                    .after(RenderSystems::PhaseSort)
+                    .before(RenderSystems::Prepare),
+                end_phase(RenderPhase::Prepare)
+                    .after(RenderSystems::Prepare)
+                    .before(RenderSystems::Render),
+                start_phase(RenderPhase::Render)
+                    .after(RenderSystems::Prepare)
+                    .before(RenderSystems::Render),
+                end_phase(RenderPhase::Render)
+                    .after(RenderSystems::Render)
+                    .before(RenderSystems::Cleanup),
+            ),
+        );
+    }
+}
+
+/// The parts of a frame [`RenderScheduleDiagnosticPlugin`] measures.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum RenderPhase {
+    Extract,
+    Queue,
+    Prepare,
+    Render,
+}
+
+#[derive(Default)]
+struct PhaseTimings {
+    starts: [Option<Instant>; 4],
+    times: [Option<Duration>; 4],
+}
+
+/// The times of the last frame, shared by both worlds. Only present if
+/// [`RenderScheduleDiagnosticPlugin`] was added.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct RenderScheduleMeasurements(Arc<Mutex<PhaseTimings>>);
+
+impl RenderScheduleMeasurements {
+    pub(crate) fn start(&self, phase: RenderPhase) {
+        self.0.lock().unwrap().starts[phase as usize] = Some(Instant::now());
+    }
+
+    pub(crate) fn end(&self, phase: RenderPhase) {
+        let mut timings = self.0.lock().unwrap();
+        if let Some(start) = timings.starts[phase as usize].take() {
+            timings.times[phase as usize] = Some(start.elapsed());
+        }
+    }
+
+    /// Records the time of a phase measured elsewhere, e.g. the extraction in
+    /// [`ExtractTimings`](crate::extract_plugin::ExtractTimings).
+    pub(crate) fn record(&self, phase: RenderPhase, time: Duration) {
+        self.0.lock().unwrap().times[phase as usize] = Some(time);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn time(&self, phase: RenderPhase) -> Option<Duration> {
+        self.0.lock().unwrap().times[phase as usize]
+    }
+}
+
+fn start_phase(phase: RenderPhase) -> impl FnMut(Res<RenderScheduleMeasurements>) {
+    move |measurements| measurements.start(phase)
+}
+
+fn end_phase(phase: RenderPhase) -> impl FnMut(Res<RenderScheduleMeasurements>) {
+    move |measurements| measurements.end(phase)
+}
+
+fn add_render_schedule_measurements(
+    mut diagnostics: Diagnostics,
+    measurements: Res<RenderScheduleMeasurements>,
+) {
+    let times = core::mem::take(&mut measurements.0.lock().unwrap().times);
+    for (path, time) in [&EXTRACT_TIME, &QUEUE_TIME, &PREPARE_TIME, &RENDER_TIME]
+        .into_iter()
+        .zip(times)
+    {
+        // Phases that didn't run, e.g. while the renderer is reinitializing, are not measured.
+        if let Some(time) = time {
+            diagnostics.add_measurement(path, || time.as_secs_f64() * 1000.0);
+        }
+    }
+}
@@ -1,17 +1,24 @@
-use alloc::sync::Arc;
+use alloc::{collections::VecDeque, sync::Arc};
 use bevy_ecs::{
+    event::Event,
+    message::{Message, Messages},
     resource::Resource,
     world::{Mut, World},
 };
+use bevy_platform::time::Instant;
+use core::task::Poll;
 use std::sync::Mutex;
 use wgpu::ErrorSource;
 pub use wgpu_types::error::ErrorType;
 
 use crate::{
-    FutureRenderResources, RenderStartup, insert_future_resources,
+    FutureRenderResources, insert_future_device,
     render_resource::PipelineCache,
-    renderer::{RenderDevice, WgpuWrapper},
-    settings::RenderCreation,
+    renderer::{DeviceGeneration, RenderDevice, WgpuWrapper},
+    run_render_startup,
+    settings::{RenderCreation, RenderResources, WgpuFeatures, WgpuLimits, WgpuSettings},
+    spawn_future_resources,
+    view::window::SurfaceAcquireRetries,
 };
 
 /// Resource to indicate renderer behavior upon error.
@@ -25,6 +32,396 @@ pub enum RenderErrorPolicy {
     StopRendering,
     /// Attempt renderer recovery with the given [`RenderCreation`].
     Recover(RenderCreation),
+    /// Attempt renderer recovery by only requesting a new device and queue from the current
+    /// [`RenderAdapter`](crate::renderer::RenderAdapter), keeping the current
+    /// [`RenderInstance`](crate::renderer::RenderInstance) and window surfaces.
+    ///
+    /// This is faster and less likely to fail than [`RenderErrorPolicy::Recover`], as a lost
+    /// device rarely invalidates the instance or the physical adapter. If requesting the device
+    /// fails, the given [`RenderCreation`] is used instead. Both happen in the background. On the
+    /// web, the given [`RenderCreation`] is always used.
+    RecoverDeviceOnly(RenderCreation),
+    /// Attempt renderer recovery with the settings of the last automatic renderer creation,
+    /// dropping the features and limits of the next attempt of the [`FeatureDowngradePlan`].
+    ///
+    /// This is useful when an optional feature misbehaves on a driver. Once the plan is
+    /// exhausted, this behaves like [`RenderErrorPolicy::StopRendering`]. Pipelines relying on a
+    /// dropped feature fail to process their shaders against the new device and stay errored in
+    /// the [`PipelineCache`]. Use [`RenderCreationReport`] or [`RenderRecovered`] to disable
+    /// graphics options depending on them.
+    RecoverDowngraded(FeatureDowngradePlan),
+    /// Attempt renderer recovery with the settings of the last automatic renderer creation.
+    ///
+    /// The number of attempts is bounded by [`RecoveryLimits`], which can also space them out.
+    /// While waiting for the next attempt, once the attempts are used up, or if the renderer was
+    /// created manually, this behaves like [`RenderErrorPolicy::StopRendering`].
+    RecoverWithPreviousSettings,
+    /// Attempt renderer recovery with the given [`RenderCreation`], bounded by the given
+    /// [`RecoveryLimits`] rather than the resource.
+    ///
+    /// While waiting for the next attempt, or once the attempts are used up, this behaves like
+    /// [`RenderErrorPolicy::StopRendering`]. See [`RecoveryState`].
+    RecoverWithBackoff {
+        render_creation: RenderCreation,
+        limits: RecoveryLimits,
+    },
+}
+
+impl RenderErrorPolicy {
+    /// Creates a [`RenderErrorPolicy::RecoverWithBackoff`] attempting up to `max_attempts`
+    /// recoveries in a row, see [`RecoveryLimits::with_backoff`].
+    pub fn recover_with_backoff(
+        render_creation: RenderCreation,
+        max_attempts: u32,
+        initial_backoff_frames: u32,
+    ) -> Self {
+        Self::RecoverWithBackoff {
+            render_creation,
+            limits: RecoveryLimits::with_backoff(max_attempts, initial_backoff_frames),
+        }
+    }
+}
+
+/// The outcome of [`RecoveryState::poll`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecoveryBackoff {
+    /// Attempt a recovery now.
+    Attempt,
+    /// Wait for the backoff before the next attempt to elapse.
+    Wait,
+    /// The attempts are used up.
+    Exhausted,
+}
+
+/// The recoveries attempted since the last frame rendered without error, in the render world.
+///
+/// Every recovery started by the [`RenderErrorHandler`] is counted here, and the count is reset
+/// once a frame renders without error. [`RenderErrorPolicy::RecoverWithPreviousSettings`] and
+/// [`RenderErrorPolicy::RecoverWithBackoff`] use it to honor their [`RecoveryLimits`], and custom
+/// handlers can read it to implement their own backoff.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecoveryState {
+    /// The number of recoveries attempted in a row.
+    pub attempts: u32,
+    /// The number of frames waited since the last attempt.
+    pub waited_frames: u32,
+}
+
+impl RecoveryState {
+    /// Decides whether to attempt a recovery this frame within `limits`, counting the frame if it
+    /// is waited for the backoff.
+    ///
+    /// The attempt itself is counted once the recovery starts.
+    pub fn poll(&mut self, limits: &RecoveryLimits) -> RecoveryBackoff {
+        if self.attempts >= limits.max_attempts {
+            return RecoveryBackoff::Exhausted;
+        }
+        if self.waited_frames < limits.backoff_frames(self.attempts) {
+            self.waited_frames += 1;
+            return RecoveryBackoff::Wait;
+        }
+        RecoveryBackoff::Attempt
+    }
+
+    /// Resets the count after a frame rendered without error.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn count_attempt(&mut self) {
+        self.attempts += 1;
+        self.waited_frames = 0;
+    }
+}
+
+/// The policies [`RenderErrorPolicyMap`] can map error types to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MappedErrorPolicy {
+    /// See [`RenderErrorPolicy::Ignore`].
+    Ignore,
+    /// See [`RenderErrorPolicy::StopRendering`].
+    StopRendering,
+    /// See [`RenderErrorPolicy::RecoverWithPreviousSettings`].
+    RecoverWithPreviousSettings,
+}
+
+impl From<MappedErrorPolicy> for RenderErrorPolicy {
+    fn from(policy: MappedErrorPolicy) -> Self {
+        match policy {
+            MappedErrorPolicy::Ignore => RenderErrorPolicy::Ignore,
+            MappedErrorPolicy::StopRendering => RenderErrorPolicy::StopRendering,
+            MappedErrorPolicy::RecoverWithPreviousSettings => {
+                RenderErrorPolicy::RecoverWithPreviousSettings
+            }
+        }
+    }
+}
+
+/// The table of policies used by the default [`RenderErrorHandler`], per [`ErrorType`].
+///
+/// Without the `auto_recover` cargo feature every error is ignored. With it, device loss is
+/// recovered from with the previous settings, running out of memory stops rendering and
+/// everything else is ignored. Entries can be changed with [`RenderErrorPolicyMap::insert`].
+#[derive(Resource, Clone, Debug)]
+pub struct RenderErrorPolicyMap {
+    entries: Vec<(ErrorType, MappedErrorPolicy)>,
+    /// The policy for error types without an entry.
+    pub fallback: MappedErrorPolicy,
+}
+
+impl RenderErrorPolicyMap {
+    /// A table ignoring every error.
+    pub fn ignore_all() -> Self {
+        Self {
+            entries: Vec::new(),
+            fallback: MappedErrorPolicy::Ignore,
+        }
+    }
+
+    /// The table used by default with the `auto_recover` cargo feature.
+    pub fn auto_recover() -> Self {
+        Self::ignore_all()
+            .with(
+                ErrorType::DeviceLost,
+                MappedErrorPolicy::RecoverWithPreviousSettings,
+            )
+            .with(ErrorType::OutOfMemory, MappedErrorPolicy::StopRendering)
+    }
+
+    /// Sets the policy for `ty`, replacing the previous one.
+    pub fn insert(&mut self, ty: ErrorType, policy: MappedErrorPolicy) {
+        match self.entries.iter_mut().find(|(entry, _)| *entry == ty) {
+            Some((_, entry)) => *entry = policy,
+            None => self.entries.push((ty, policy)),
+        }
+    }
+
+    /// Builder version of [`RenderErrorPolicyMap::insert`].
+    pub fn with(mut self, ty: ErrorType, policy: MappedErrorPolicy) -> Self {
+        self.insert(ty, policy);
+        self
+    }
+
+    /// Returns the policy for `ty`.
+    pub fn get(&self, ty: ErrorType) -> MappedErrorPolicy {
+        self.entries
+            .iter()
+            .find(|(entry, _)| *entry == ty)
+            .map_or(self.fallback, |(_, policy)| *policy)
+    }
+}
+
+impl Default for RenderErrorPolicyMap {
+    fn default() -> Self {
+        if cfg!(feature = "auto_recover") {
+            Self::auto_recover()
+        } else {
+            Self::ignore_all()
+        }
+    }
+}
+
+/// Bounds [`RenderErrorPolicy::RecoverWithPreviousSettings`], and
+/// [`RenderErrorPolicy::RecoverWithBackoff`] through its own copy.
+///
+/// The attempts are counted in the [`RecoveryState`] of the render world.
+#[derive(Resource, Clone, Debug)]
+pub struct RecoveryLimits {
+    /// The number of recoveries to attempt in a row, until a frame renders without error.
+    pub max_attempts: u32,
+    /// The number of frames to wait before the first attempt. The wait doubles with every
+    /// following attempt.
+    pub initial_backoff_frames: u32,
+}
+
+impl RecoveryLimits {
+    /// Attempts up to `max_attempts` recoveries, waiting `initial_backoff_frames` frames before
+    /// the first one and twice as long before each following one.
+    pub fn with_backoff(max_attempts: u32, initial_backoff_frames: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff_frames,
+        }
+    }
+
+    /// The number of frames to wait before the recovery attempt `attempt`, counting from 0.
+    pub fn backoff_frames(&self, attempt: u32) -> u32 {
+        self.initial_backoff_frames
+            .saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+impl Default for RecoveryLimits {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff_frames: 0,
+        }
+    }
+}
+
+/// Polls the [`RecoveryState`] of the render world, returning `true` if a recovery bounded by
+/// `limits` should be attempted this frame.
+fn poll_recovery(render_world: &mut World, limits: &RecoveryLimits) -> bool {
+    match render_world
+        .get_resource_or_init::<RecoveryState>()
+        .poll(limits)
+    {
+        RecoveryBackoff::Attempt => true,
+        RecoveryBackoff::Wait => false,
+        RecoveryBackoff::Exhausted => {
+            bevy_log::error_once!("Renderer recovery limit reached, rendering stopped");
+            false
+        }
+    }
+}
+
+/// The features and limits to drop in one attempt of a [`FeatureDowngradePlan`].
+#[derive(Clone, Debug, Default)]
+pub struct FeatureDowngrade {
+    /// The features to disable.
+    pub features: WgpuFeatures,
+    /// The limits to constrain to, if any.
+    pub limits: Option<WgpuLimits>,
+}
+
+/// Describes how to retry renderer creation with fewer features, see
+/// [`RenderErrorPolicy::RecoverDowngraded`].
+///
+/// Attempts are cumulative: attempt `n` drops everything dropped by the attempts before it.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureDowngradePlan {
+    pub attempts: Vec<FeatureDowngrade>,
+}
+
+impl FeatureDowngradePlan {
+    /// Creates a plan dropping one set of features per attempt.
+    pub fn dropping_features(features: impl IntoIterator<Item = WgpuFeatures>) -> Self {
+        Self {
+            attempts: features
+                .into_iter()
+                .map(|features| FeatureDowngrade {
+                    features,
+                    limits: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies the first `attempt + 1` attempts of the plan on top of `settings`.
+    ///
+    /// Returns `None` if the plan has no such attempt.
+    pub fn apply(&self, settings: &WgpuSettings, attempt: usize) -> Option<WgpuSettings> {
+        let attempts = self.attempts.get(..=attempt)?;
+        let mut settings = settings.clone();
+        for downgrade in attempts {
+            settings.features.remove(downgrade.features);
+            settings.disabled_features =
+                Some(settings.disabled_features.unwrap_or_default() | downgrade.features);
+            if let Some(limits) = &downgrade.limits {
+                settings.constrained_limits = Some(match settings.constrained_limits.take() {
+                    Some(constrained_limits) => constrained_limits.or_worse_values_from(limits),
+                    None => limits.clone(),
+                });
+            }
+        }
+        Some(settings)
+    }
+}
+
+/// The settings of the last automatic renderer creation, used by
+/// [`RenderErrorPolicy::RecoverDowngraded`].
+#[derive(Resource, Clone)]
+pub(crate) struct LastWgpuSettings(pub(crate) WgpuSettings);
+
+/// The settings a [`FeatureDowngradePlan`] is applied to, and the next attempt to make.
+///
+/// It is removed once the renderer rendered a frame, so the next error downgrades the settings
+/// the renderer recovered with.
+#[derive(Resource)]
+struct FeatureDowngradeState {
+    base: WgpuSettings,
+    attempt: usize,
+}
+
+/// The features and limits of the current [`RenderDevice`], in the main world.
+///
+/// These are the features and limits granted by the device, which can be fewer than the ones
+/// requested through [`WgpuSettings`]. Plugins should check this instead of the settings before
+/// relying on a feature.
+#[derive(Resource, Clone, Debug)]
+pub struct RenderCreationReport {
+    pub features: WgpuFeatures,
+    pub limits: WgpuLimits,
+    /// The features explicitly requested through [`WgpuSettings::features`] and
+    /// [`WgpuSettings::optional_features`]. For manually created renderers, the features of the
+    /// supplied device.
+    pub requested_features: WgpuFeatures,
+    /// The requested features the device was not granted.
+    pub ungranted_features: WgpuFeatures,
+    /// The features the previous device had, but the current one doesn't, after recovery.
+    pub dropped_features: WgpuFeatures,
+}
+
+impl RenderCreationReport {
+    pub(crate) fn new(
+        requested_features: WgpuFeatures,
+        features: WgpuFeatures,
+        limits: WgpuLimits,
+    ) -> Self {
+        Self {
+            features,
+            limits,
+            requested_features,
+            ungranted_features: requested_features - features,
+            dropped_features: WgpuFeatures::empty(),
+        }
+    }
+}
+
+/// Sent in the main world after the renderer was recovered with a new device.
+#[derive(Message, Clone, Debug)]
+pub struct RenderRecovered {
+    /// The features of the new device.
+    pub features: WgpuFeatures,
+    /// The features the previous device had, but the new one doesn't.
+    pub dropped_features: WgpuFeatures,
+}
+
+/// Sent when a new [`RenderDevice`] replaced a lost one, making every GPU resource created on the
+/// previous device invalid.
+///
+/// It is triggered as an [`Event`] and sent as a [`Message`] in both worlds, right after the new
+/// device was installed and before [`RenderStartup`](crate::RenderStartup) runs for it. Caches of
+/// GPU resources should drop their content when receiving it. The renderer's own caches observe
+/// it: the [`PipelineCache`] queues its pipelines to be created again on the new device, keeping
+/// their IDs, while the [`TextureCache`](crate::texture::TextureCache) and the
+/// [`WindowSurfaces`](crate::view::window::WindowSurfaces) are emptied.
+#[derive(Message, Event, Clone, Copy, Debug)]
+pub struct RenderDeviceRecreated {
+    /// The generation of the new device.
+    pub generation: DeviceGeneration,
+}
+
+/// Increments the [`DeviceGeneration`] of both worlds for a newly installed device, and sends
+/// [`RenderDeviceRecreated`] if it replaces a previous one.
+pub(crate) fn advance_device_generation(main_world: &mut World, render_world: &mut World) {
+    let mut generation = render_world.get_resource_or_init::<DeviceGeneration>();
+    generation.0 += 1;
+    let generation = *generation;
+    main_world.insert_resource(generation);
+    if generation.0 == 1 {
+        return;
+    }
+
+    let recreated = RenderDeviceRecreated { generation };
+    render_world.trigger(recreated);
+    main_world.trigger(recreated);
+    for world in [main_world, render_world] {
+        if let Some(mut messages) = world.get_resource_mut::<Messages<RenderDeviceRecreated>>() {
+            messages.write(recreated);
+        }
+    }
 }
 
 /// Determines what [`RenderErrorPolicy`] should be used to respond to a given [`RenderError`].
@@ -41,27 +438,166 @@ pub struct RenderErrorHandler(
 
 impl RenderErrorHandler {
     fn handle(&self, error: &RenderError, main_world: &mut World, render_world: &mut World) {
-        match self.0(error, main_world, render_world) {
+        let recovery = match self.0(error, main_world, render_world) {
             RenderErrorPolicy::Ignore => {
                 // Pretend that didn't happen.
                 render_world.insert_resource(RenderState::Ready);
+                return;
             }
             RenderErrorPolicy::StopRendering => {
                 // do nothing
+                return;
+            }
+            RenderErrorPolicy::Recover(render_creation) => Recovery::Renderer(render_creation),
+            RenderErrorPolicy::RecoverDeviceOnly(render_creation) => {
+                Recovery::Device(render_creation)
             }
-            RenderErrorPolicy::Recover(render_creation) => {
-                assert!(insert_future_resources(&render_creation, main_world));
-                render_world.insert_resource(RenderState::Reinitializing);
+            RenderErrorPolicy::RecoverDowngraded(plan) => {
+                if !main_world.contains_resource::<FeatureDowngradeState>() {
+                    let base = main_world
+                        .get_resource::<LastWgpuSettings>()
+                        .map(|settings| settings.0.clone())
+                        .unwrap_or_default();
+                    main_world.insert_resource(FeatureDowngradeState { base, attempt: 0 });
+                }
+                let mut state = main_world.resource_mut::<FeatureDowngradeState>();
+                let Some(settings) = plan.apply(&state.base, state.attempt) else {
+                    bevy_log::error_once!("Feature downgrade plan exhausted, rendering stopped");
+                    return;
+                };
+                // The plan is applied to the settings from before the first attempt, it already
+                // accumulates the features dropped by the previous attempts.
+                state.attempt += 1;
+                bevy_log::warn!(
+                    "Recovering renderer with feature downgrade attempt {}",
+                    state.attempt
+                );
+                Recovery::Renderer(settings.into())
+            }
+            RenderErrorPolicy::RecoverWithPreviousSettings => {
+                let Some(settings) = main_world
+                    .get_resource::<LastWgpuSettings>()
+                    .map(|settings| settings.0.clone())
+                else {
+                    bevy_log::error_once!(
+                        "The renderer was created manually and can't be recovered with its \
+                        previous settings, rendering stopped"
+                    );
+                    return;
+                };
+                let limits = main_world
+                    .get_resource::<RecoveryLimits>()
+                    .cloned()
+                    .unwrap_or_default();
+                if !poll_recovery(render_world, &limits) {
+                    return;
+                }
+                bevy_log::warn!("Recovering renderer with the previous settings");
+                Recovery::Renderer(settings.into())
+            }
+            RenderErrorPolicy::RecoverWithBackoff {
+                render_creation,
+                limits,
+            } => {
+                if !poll_recovery(render_world, &limits) {
+                    return;
+                }
+                bevy_log::warn!(
+                    "Recovering renderer, attempt {} of {}",
+                    render_world.resource::<RecoveryState>().attempts + 1,
+                    limits.max_attempts
+                );
+                Recovery::Renderer(render_creation)
             }
+        };
+        recover(error, recovery, main_world, render_world);
+    }
+}
+
+/// How the renderer is recreated to recover from an error.
+enum Recovery {
+    /// Create a new renderer.
+    Renderer(RenderCreation),
+    /// Request a new device from the current adapter, creating a new renderer if that fails.
+    Device(RenderCreation),
+}
+
+/// Starts recreating the renderer and switches to [`RenderState::Reinitializing`], counting the
+/// attempt in the [`RecoveryState`].
+///
+/// If the creation can't start, e.g. because no adapter or device could be created, the renderer
+/// stays errored and the [`RenderErrorHandler`] is asked again next frame.
+fn recover(
+    error: &RenderError,
+    recovery: Recovery,
+    main_world: &mut World,
+    render_world: &mut World,
+) {
+    render_world
+        .get_resource_or_init::<RecoveryState>()
+        .count_attempt();
+    // Recovering with the same workload would likely run out of memory again.
+    if error.ty == ErrorType::OutOfMemory {
+        reclaim_gpu_resources(render_world);
+    }
+
+    let started = match recovery {
+        Recovery::Renderer(render_creation) => spawn_future_resources(&render_creation, main_world),
+        Recovery::Device(render_creation) => {
+            spawn_future_device(&render_creation, main_world, render_world)
         }
+    };
+    if started {
+        render_world.insert_resource(RenderState::Reinitializing(error.ty));
+        return;
     }
+    bevy_log::error!("Failed to recreate the renderer, rendering stays stopped");
+    main_world
+        .get_resource_or_init::<RenderErrorStats>()
+        .failed_recoveries += 1;
 }
 
 impl Default for RenderErrorHandler {
     fn default() -> Self {
-        // This is what we've always done historically,
-        // but we could choose a new default once recovery works better.
-        Self(|_, _, _| RenderErrorPolicy::Ignore)
+        // Ignoring every error is what we've always done historically, and is still what the
+        // `RenderErrorPolicyMap` does unless the `auto_recover` feature opts into recovery.
+        Self(|error, main_world, _| {
+            main_world
+                .get_resource::<RenderErrorPolicyMap>()
+                .map_or(MappedErrorPolicy::Ignore, |map| map.get(error.ty))
+                .into()
+        })
+    }
+}
+
+/// A render world resource holding GPU memory that can be recreated on demand, dropped before
+/// recovering from an [`ErrorType::OutOfMemory`] error.
+///
+/// Register it with
+/// [`GpuResourceAppExt::register_reclaimable_gpu_resource`](crate::GpuResourceAppExt::register_reclaimable_gpu_resource).
+/// The [`PipelineCache`] doesn't need to be registered, as it is recreated with the new device.
+pub trait ReclaimableGpuResource: Resource {
+    /// Drops everything that can be recreated on demand.
+    fn reclaim(&mut self);
+}
+
+/// The [`ReclaimableGpuResource`]s registered in the render world.
+#[derive(Resource, Default)]
+pub(crate) struct ReclaimableGpuResources(pub(crate) Vec<fn(&mut World)>);
+
+pub(crate) fn reclaim_gpu_resource<R: ReclaimableGpuResource>(world: &mut World) {
+    if let Some(mut resource) = world.get_resource_mut::<R>() {
+        resource.reclaim();
+    }
+}
+
+fn reclaim_gpu_resources(render_world: &mut World) {
+    let Some(reclaimable) = render_world.get_resource::<ReclaimableGpuResources>() else {
+        return;
+    };
+    bevy_log::warn!("Out of GPU memory, dropping reclaimable GPU resources before recovery");
+    for reclaim in reclaimable.0.clone() {
+        reclaim(render_world);
     }
 }
 
@@ -73,6 +609,27 @@ pub struct RenderError {
     pub source: Option<WgpuWrapper<ErrorSource>>,
 }
 
+/// A summary of a [`RenderError`], written as a message in the main world and triggered as an
+/// event in both worlds whenever an error is caught, before the [`RenderErrorHandler`] decides
+/// how to respond to it.
+///
+/// This is purely observational, e.g. to show errors in the UI or collect telemetry, and is sent
+/// for every error, including the ones dropped after an error stopped rendering.
+#[derive(Message, Event, Clone, Debug)]
+pub struct RenderErrorOccurred {
+    pub ty: ErrorType,
+    pub description: String,
+}
+
+impl From<&RenderError> for RenderErrorOccurred {
+    fn from(error: &RenderError) -> Self {
+        Self {
+            ty: error.ty,
+            description: error.description.clone(),
+        }
+    }
+}
+
 /// The current state of the renderer.
 #[derive(Resource, Debug)]
 pub(crate) enum RenderState {
@@ -82,128 +639,362 @@ pub(crate) enum RenderState {
     Ready,
     /// An error was encountered, and we may decide how to handle it.
     Errored(RenderError),
-    /// We are recreating the render context after an error to recover.
+    /// We are recreating the render context in the background to recover from an error of the
+    /// given type, and go back to [`RenderState::Errored`] if that fails.
+    Reinitializing(ErrorType),
+    /// Rendering is paused through [`RenderEnabled`], the device is kept.
+    Paused,
+}
+
+/// Whether the renderer renders frames, e.g. to save power while the window is minimized.
+///
+/// Insert it in the main world with `false` to pause rendering: the extraction and the
+/// [`Render`](crate::Render) schedule are skipped, while device polling and error handling keep
+/// running.
+/// Setting it back to `true` resumes rendering with the same device. It is read at the start of
+/// every extraction, and missing means enabled.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderEnabled(pub bool);
+
+impl Default for RenderEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Running counts of the errors the renderer ran into, e.g. to report to telemetry.
+///
+/// This lives in the main world and is updated for every error handled by the
+/// [`RenderErrorHandler`]. Reading it doesn't reset the counts.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct RenderErrorStats {
+    counts: Vec<(ErrorType, u64)>,
+    last_error: Option<(ErrorType, Instant)>,
+    failed_recoveries: u64,
+}
+
+impl RenderErrorStats {
+    /// The number of errors of type `ty` so far.
+    pub fn count(&self, ty: ErrorType) -> u64 {
+        self.counts
+            .iter()
+            .find(|(counted, _)| *counted == ty)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// The number of errors of any type so far.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|(_, count)| count).sum()
+    }
+
+    /// The type of the latest error and when it was handled.
+    pub fn last_error(&self) -> Option<(ErrorType, Instant)> {
+        self.last_error
+    }
+
+    /// The number of recoveries that failed to create a new renderer so far.
+    pub fn failed_recoveries(&self) -> u64 {
+        self.failed_recoveries
+    }
+
+    fn record(&mut self, ty: ErrorType, now: Instant) {
+        match self.counts.iter_mut().find(|(counted, _)| *counted == ty) {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((ty, 1)),
+        }
+        self.last_error = Some((ty, now));
+    }
+}
+
+/// A read-only snapshot of the state of the renderer, e.g. to show a message on a loading screen
+/// while the renderer recovers from an error.
+///
+/// It is present in both worlds and updated at the start of every extraction, after errors were
+/// handled. Changing it has no effect on the renderer.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderStatus {
+    /// The renderer is running [`RenderStartup`](crate::RenderStartup) for a new device.
+    #[default]
+    Initializing,
+    /// The renderer is rendering every frame.
+    Ready,
+    /// Rendering is stopped because of an error of the given type, until the
+    /// [`RenderErrorHandler`] decides how to continue.
+    Errored(ErrorType),
+    /// The renderer is waiting for a new device to recover from an error.
     Reinitializing,
+    /// Rendering is paused through [`RenderEnabled`].
+    Paused,
 }
 
-/// Resource to allow polling wgpu error handlers.
-#[derive(Resource)]
-pub(crate) struct DeviceErrorHandler {
-    device_lost: Arc<Mutex<Option<(wgpu::DeviceLostReason, String)>>>,
-    uncaptured: Arc<Mutex<Option<WgpuWrapper<wgpu::Error>>>>,
+impl RenderStatus {
+    fn of(state: &RenderState) -> Self {
+        match state {
+            RenderState::Initializing => Self::Initializing,
+            RenderState::Ready => Self::Ready,
+            RenderState::Errored(error) => Self::Errored(error.ty),
+            RenderState::Reinitializing(_) => Self::Reinitializing,
+            RenderState::Paused => Self::Paused,
+        }
+    }
+}
+
+/// Collects the errors of the [`RenderDevice`] in the render world, to be handled by the
+/// [`RenderErrorHandler`] at the start of the next frame.
+///
+/// Errors can also be pushed manually with [`DeviceErrorHandler::push_error`], e.g. to test
+/// error policies without a GPU.
+#[derive(Resource, Default)]
+pub struct DeviceErrorHandler {
+    errors: Arc<Mutex<PendingErrors>>,
+}
+
+struct PendingErrors {
+    errors: VecDeque<RenderError>,
+    max_errors: usize,
+}
+
+impl Default for PendingErrors {
+    fn default() -> Self {
+        Self {
+            errors: VecDeque::new(),
+            max_errors: DeviceErrorHandler::DEFAULT_MAX_PENDING_ERRORS,
+        }
+    }
 }
 
 impl DeviceErrorHandler {
+    /// The default of [`DeviceErrorHandler::max_pending_errors`].
+    pub const DEFAULT_MAX_PENDING_ERRORS: usize = 16;
+
     /// Creates and registers error handlers on the given device and stores them to later be polled.
-    pub(crate) fn new(device: &RenderDevice) -> Self {
-        let device_lost = Arc::new(Mutex::new(None));
-        let uncaptured = Arc::new(Mutex::new(None));
+    ///
+    /// [`DeviceErrorHandler::max_pending_errors`] is kept from `previous`, the handler of the
+    /// previous device.
+    pub(crate) fn new(device: &RenderDevice, previous: Option<&DeviceErrorHandler>) -> Self {
+        let handler = Self::default();
+        if let Some(previous) = previous {
+            handler.set_max_pending_errors(previous.max_pending_errors());
+        }
         {
-            // scoped clone to move into closures
-            let device_lost = device_lost.clone();
-            let uncaptured = uncaptured.clone();
+            // scoped clones to move into closures
+            let device_lost = handler.errors.clone();
+            let uncaptured = handler.errors.clone();
             let device = device.wgpu_device();
             // we log errors as soon as they are captured so they stay chronological in logs
-            // and only keep the first error, as it often causes other errors downstream
-            device.set_device_lost_callback(move |reason, str| {
-                bevy_log::error!("Caught DeviceLost error: {reason:?} {str}");
-                assert!(device_lost.lock().unwrap().replace((reason, str)).is_none());
+            device.set_device_lost_callback(move |reason, description| {
+                bevy_log::error!("Caught DeviceLost error: {reason:?} {description}");
+                Self::push(
+                    &device_lost,
+                    RenderError {
+                        ty: ErrorType::DeviceLost,
+                        description,
+                        source: None,
+                    },
+                );
             });
             device.on_uncaptured_error(Arc::new(move |e| {
                 bevy_log::error!("Caught rendering error: {e}");
-                uncaptured
-                    .lock()
-                    .unwrap()
-                    .get_or_insert(WgpuWrapper::new(e));
+                Self::push(&uncaptured, RenderError::from(e));
             }));
         }
-        Self {
-            device_lost,
-            uncaptured,
+        handler
+    }
+
+    /// The number of errors kept until they are drained. Later errors are dropped, as the first
+    /// errors often cause the others downstream, except for device loss.
+    pub fn max_pending_errors(&self) -> usize {
+        self.errors.lock().unwrap().max_errors
+    }
+
+    /// Sets [`DeviceErrorHandler::max_pending_errors`], which is kept across device recovery.
+    pub fn set_max_pending_errors(&self, max_errors: usize) {
+        let mut pending = self.errors.lock().unwrap();
+        pending.max_errors = max_errors;
+        pending.errors.truncate(max_errors);
+    }
+
+    /// Queues an error as if the device reported it.
+    pub fn push_error(&self, error: RenderError) {
+        Self::push(&self.errors, error);
+    }
+
+    fn push(pending: &Mutex<PendingErrors>, error: RenderError) {
+        let mut pending = pending.lock().unwrap();
+        if error.ty == ErrorType::DeviceLost {
+            // Device loss is more important so we let it take precedence, even over the limit.
+            pending.errors.push_front(error);
+            let max_errors = pending.max_errors.max(1);
+            pending.errors.truncate(max_errors);
+        } else if pending.errors.len() < pending.max_errors {
+            pending.errors.push_back(error);
         }
     }
 
-    /// Checks to see if any errors have been caught, and returns an appropriate `RenderState`
-    pub(crate) fn poll(&self) -> Option<RenderError> {
-        // Device lost is more important so we let it take precedence; every error gets logged anyways.
-        if let Some((_, description)) = self.device_lost.lock().unwrap().take() {
-            return Some(RenderError {
-                ty: ErrorType::DeviceLost,
+    /// Takes all errors caught since the last call, in the order they were caught, except for
+    /// device loss which comes first.
+    pub fn drain(&self) -> VecDeque<RenderError> {
+        core::mem::take(&mut self.errors.lock().unwrap().errors)
+    }
+}
+
+impl From<wgpu::Error> for RenderError {
+    fn from(error: wgpu::Error) -> Self {
+        let (ty, description, source) = match error {
+            wgpu::Error::OutOfMemory { source } => (ErrorType::OutOfMemory, "".to_string(), source),
+            wgpu::Error::Validation {
+                source,
                 description,
-                source: None,
-            });
-        }
-        if let Some(error) = self.uncaptured.lock().unwrap().take() {
-            let (ty, description, source) = match error.into_inner() {
-                wgpu::Error::OutOfMemory { source } => {
-                    (ErrorType::OutOfMemory, "".to_string(), source)
-                }
-                wgpu::Error::Validation {
-                    source,
-                    description,
-                } => (ErrorType::Validation, description, source),
-                wgpu::Error::Internal {
-                    source,
-                    description,
-                } => (ErrorType::Internal, description, source),
-            };
-            return Some(RenderError {
-                ty,
+            } => (ErrorType::Validation, description, source),
+            wgpu::Error::Internal {
+                source,
                 description,
-                source: Some(WgpuWrapper::new(source)),
-            });
+            } => (ErrorType::Internal, description, source),
+        };
+        RenderError {
+            ty,
+            description,
+            source: Some(WgpuWrapper::new(source)),
         }
-        None
     }
 }
 
 /// Updates the state machine that handles the renderer and device lifecycle.
-/// Polls the [`DeviceErrorHandler`] and fires the [`RenderErrorHandler`] if needed.
+/// Polls the [`DeviceErrorHandler`] and [`SurfaceAcquireRetries`], and fires the [`RenderErrorHandler`] if needed.
 ///
-/// Runs [`crate::RenderStartup`] after every time a [`RenderDevice`] is acquired.
+/// Runs [`crate::RenderStartup`] after every time a [`RenderDevice`] is acquired, skipping
+/// [`crate::RenderStartupSystems::Once`] after the first device.
 ///
 /// We need both the main and render world to properly handle errors, so we wedge ourselves into [extract](bevy_app::SubApp::set_extract).
 pub(crate) fn update_state(main_world: &mut World, render_world: &mut World) {
-    if let Some(error) = render_world.resource::<DeviceErrorHandler>().poll() {
+    handle_pending_errors(main_world, render_world);
+
+    let status = RenderStatus::of(render_world.resource::<RenderState>());
+    main_world.insert_resource(status);
+    render_world.insert_resource(status);
+}
+
+/// Handles the errors collected since the last frame, or advances the state if there are none.
+fn handle_pending_errors(main_world: &mut World, render_world: &mut World) {
+    let mut errors = render_world.resource::<DeviceErrorHandler>().drain();
+    if let Some(error) = render_world
+        .get_resource_mut::<SurfaceAcquireRetries>()
+        .and_then(|mut retries| retries.take_escalation())
+    {
+        errors.push_back(error);
+    }
+    if errors.is_empty() {
+        advance_state(main_world, render_world);
+        return;
+    }
+
+    for error in &errors {
+        let occurred = RenderErrorOccurred::from(error);
+        render_world.trigger(occurred.clone());
+        main_world.trigger(occurred.clone());
+        if let Some(mut messages) = main_world.get_resource_mut::<Messages<RenderErrorOccurred>>() {
+            messages.write(occurred);
+        }
+    }
+
+    // Errors are handled in order. Once one isn't ignored, the remaining ones are dropped, as
+    // they likely stem from the same failure and every error was logged when it was caught.
+    while let Some(error) = errors.pop_front() {
+        main_world
+            .get_resource_or_init::<RenderErrorStats>()
+            .record(error.ty, Instant::now());
         render_world.insert_resource(RenderState::Errored(error));
-    };
+        advance_state(main_world, render_world);
+        if !matches!(render_world.resource::<RenderState>(), RenderState::Ready) {
+            break;
+        }
+    }
+}
 
+/// Takes the resources of a finished renderer creation, without waiting for a pending one.
+fn poll_future_resources(main_world: &World) -> Poll<Option<RenderResources>> {
+    // The creation may be running or writing its result right now, check again next frame
+    // instead of stalling the render app.
+    main_world
+        .get_resource::<FutureRenderResources>()
+        .map_or(Poll::Pending, FutureRenderResources::poll)
+}
+
+/// Advances the [`RenderState`] by one step, calling the [`RenderErrorHandler`] if errored.
+fn advance_state(main_world: &mut World, render_world: &mut World) {
     // Remove the render state so we can provide both worlds to the `RenderErrorHandler`.
     let state = render_world.remove_resource::<RenderState>().unwrap();
 
+    let enabled = main_world
+        .get_resource::<RenderEnabled>()
+        .is_none_or(|enabled| enabled.0);
     match &state {
         RenderState::Initializing => {
-            render_world.run_schedule(RenderStartup);
-            render_world.insert_resource(RenderState::Ready);
+            run_render_startup(render_world);
+            render_world.insert_resource(if enabled {
+                RenderState::Ready
+            } else {
+                RenderState::Paused
+            });
         }
         RenderState::Ready => {
-            // all is well
+            // all is well, the previous frame rendered without error
+            if let Some(mut recovery) = render_world.get_resource_mut::<RecoveryState>()
+                && *recovery != RecoveryState::default()
+            {
+                recovery.reset();
+            }
+            main_world.remove_resource::<FeatureDowngradeState>();
+            if !enabled {
+                render_world.insert_resource(RenderState::Paused);
+            }
+        }
+        RenderState::Paused => {
+            if enabled {
+                render_world.insert_resource(RenderState::Ready);
+            }
         }
         RenderState::Errored(error) => {
             main_world.resource_scope(|main_world, error_handler: Mut<RenderErrorHandler>| {
                 error_handler.handle(error, main_world, render_world);
             });
         }
-        RenderState::Reinitializing => {
-            if let Some(render_resources) = main_world
-                .get_resource::<FutureRenderResources>()
-                .unwrap()
-                .clone()
-                .lock()
-                .unwrap()
-                .take()
-            {
+        RenderState::Reinitializing(ty) => match poll_future_resources(main_world) {
+            Poll::Pending => {}
+            Poll::Ready(Some(render_resources)) => {
                 let synchronous_pipeline_compilation = render_world
                     .resource::<PipelineCache>()
                     .synchronous_pipeline_compilation;
+                let previous_features = render_world.resource::<RenderDevice>().features();
                 render_resources.unpack_into(
                     main_world,
                     render_world,
                     synchronous_pipeline_compilation,
                 );
                 render_world.insert_resource(RenderState::Initializing);
+
+                let features = render_world.resource::<RenderDevice>().features();
+                let dropped_features = previous_features - features;
+                main_world
+                    .resource_mut::<RenderCreationReport>()
+                    .dropped_features = dropped_features;
+                main_world.write_message(RenderRecovered {
+                    features,
+                    dropped_features,
+                });
             }
-        }
+            Poll::Ready(None) => {
+                main_world.remove_resource::<FutureRenderResources>();
+                main_world
+                    .get_resource_or_init::<RenderErrorStats>()
+                    .failed_recoveries += 1;
+                render_world.insert_resource(RenderState::Errored(RenderError {
+                    ty: *ty,
+                    description: "Failed to recreate the renderer".to_owned(),
+                    source: None,
+                }));
+            }
+        },
     }
 
     // Put the state back if we didn't set a new one
@@ -211,3 +1002,1190 @@ pub(crate) fn update_state(main_world: &mut World, render_world: &mut World) {
         render_world.insert_resource(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use bevy_app::{App, SubApp};
+    use bevy_asset::{Handle, uuid_handle};
+    use bevy_ecs::{message::Messages, prelude::*};
+    use bevy_material::descriptor::ComputePipelineDescriptor;
+    use bevy_shader::Shader;
+    use core::task::Poll;
+    use wgpu::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages};
+
+    use super::{
+        DeviceErrorHandler, ErrorType, FeatureDowngrade, FeatureDowngradePlan,
+        FeatureDowngradeState, LastWgpuSettings, MappedErrorPolicy, ReclaimableGpuResource,
+        RecoveryBackoff, RecoveryLimits, RecoveryState, RenderCreationReport,
+        RenderDeviceRecreated, RenderEnabled, RenderError, RenderErrorHandler, RenderErrorOccurred,
+        RenderErrorPolicy, RenderErrorPolicyMap, RenderErrorStats, RenderRecovered, RenderState,
+        RenderStatus, advance_device_generation, update_state,
+    };
+    use crate::{
+        FutureRenderResources, GpuResourceAppExt, Render, RenderApp, RenderRecovery,
+        RenderScheduleOrder, RenderStartup, RenderStartupRuns, RenderStartupSystems, RenderSystems,
+        remember_settings,
+        render_resource::PipelineCache,
+        renderer::{
+            DeviceGeneration, RenderAdapter, RenderDevice, RenderInstance, noop_render_resources,
+        },
+        renderer_is_ready, run_once_per_device, run_render_schedule, run_render_startup,
+        settings::{
+            RENDER_INIT_STAGES, RenderCreation, RenderCreationContext, RenderInitProgress,
+            RenderInitStage, WgpuFeatures, WgpuLimits, WgpuSettings,
+        },
+        texture::{TextureCache, clear_texture_cache},
+        view::window::{WindowSurfaces, clear_window_surfaces},
+    };
+
+    #[derive(Resource, Default)]
+    struct StartupRuns {
+        once: u32,
+        per_device: u32,
+    }
+
+    #[test]
+    fn once_startup_systems_skip_reinitialization() {
+        let mut world = World::new();
+        world.init_resource::<StartupRuns>();
+
+        let mut schedule = RenderStartup::base_schedule();
+        schedule.add_systems((
+            (|mut runs: ResMut<StartupRuns>| runs.once += 1).in_set(RenderStartupSystems::Once),
+            (|mut runs: ResMut<StartupRuns>| runs.per_device += 1)
+                .in_set(RenderStartupSystems::PerDevice),
+        ));
+        world.add_schedule(schedule);
+
+        // The first device, then two reinitializations after device loss.
+        for _ in 0..3 {
+            run_render_startup(&mut world);
+        }
+
+        let runs = world.resource::<StartupRuns>();
+        assert_eq!(runs.once, 1);
+        assert_eq!(runs.per_device, 3);
+    }
+
+    #[test]
+    fn render_startup_systems_run_per_device() {
+        #[derive(Resource, Default)]
+        struct DeviceResource;
+
+        // Without a render app, registering startup systems does nothing.
+        let mut app = App::new();
+        app.add_render_startup_systems(|mut runs: ResMut<StartupRuns>| runs.per_device += 1);
+
+        let mut render_app = SubApp::new();
+        render_app.add_schedule(RenderStartup::base_schedule());
+        render_app.init_resource::<StartupRuns>();
+        app.insert_sub_app(RenderApp, render_app);
+        app.add_render_startup_systems(|mut runs: ResMut<StartupRuns>| runs.per_device += 1)
+            .init_render_resource_on_startup::<DeviceResource>();
+
+        // The first device, then a reinitialization after device loss.
+        let render_world = app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+        run_render_startup(render_world);
+        assert!(render_world.remove_resource::<DeviceResource>().is_some());
+        run_render_startup(render_world);
+        assert_eq!(render_world.resource::<StartupRuns>().per_device, 2);
+        assert!(render_world.contains_resource::<DeviceResource>());
+    }
+
+    #[test]
+    fn startup_systems_run_once_per_device() {
+        let mut main_world = World::new();
+        main_world.init_resource::<Messages<RenderDeviceRecreated>>();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.init_resource::<StartupRuns>();
+        let mut schedule = RenderStartup::base_schedule();
+        schedule.add_systems(
+            (|mut runs: ResMut<StartupRuns>| runs.per_device += 1).run_if(run_once_per_device()),
+        );
+        render_world.add_schedule(schedule);
+
+        noop_render_resources(WgpuFeatures::empty()).unpack_into(
+            &mut main_world,
+            &mut render_world,
+            true,
+        );
+        assert_eq!(render_world.resource::<DeviceGeneration>().0, 1);
+        render_world.insert_resource(RenderState::Initializing);
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(render_world.resource::<StartupRuns>().per_device, 1);
+
+        // Running the schedule again for the same device doesn't rebuild anything.
+        run_render_startup(&mut render_world);
+        assert_eq!(render_world.resource::<StartupRuns>().per_device, 1);
+
+        // Reinitializing installs a new device, then goes back to initializing.
+        noop_render_resources(WgpuFeatures::empty()).unpack_into(
+            &mut main_world,
+            &mut render_world,
+            true,
+        );
+        assert_eq!(render_world.resource::<DeviceGeneration>().0, 2);
+        assert_eq!(main_world.resource::<DeviceGeneration>().0, 2);
+        render_world.insert_resource(RenderState::Initializing);
+        update_state(&mut main_world, &mut render_world);
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(render_world.resource::<StartupRuns>().per_device, 2);
+        assert_eq!(
+            *render_world.resource::<RenderStatus>(),
+            RenderStatus::Ready
+        );
+    }
+
+    #[test]
+    fn device_recreation_is_sent_once_per_new_device() {
+        #[derive(Resource, Default)]
+        struct Recreated(Vec<u64>);
+
+        let mut main_world = World::new();
+        main_world.init_resource::<Messages<RenderDeviceRecreated>>();
+        let mut render_world = World::new();
+        render_world.init_resource::<Recreated>();
+        render_world.add_observer(
+            |recreated: On<RenderDeviceRecreated>, mut log: ResMut<Recreated>| {
+                log.0.push(recreated.generation.0);
+            },
+        );
+        let sent = |main_world: &mut World| {
+            let mut messages = main_world.resource_mut::<Messages<RenderDeviceRecreated>>();
+            messages
+                .drain()
+                .map(|sent| sent.generation.0)
+                .collect::<Vec<_>>()
+        };
+
+        // The first device doesn't replace anything.
+        advance_device_generation(&mut main_world, &mut render_world);
+        assert_eq!(
+            *main_world.resource::<DeviceGeneration>(),
+            DeviceGeneration(1)
+        );
+        assert!(sent(&mut main_world).is_empty());
+
+        // Two recoveries.
+        advance_device_generation(&mut main_world, &mut render_world);
+        advance_device_generation(&mut main_world, &mut render_world);
+        assert_eq!(
+            *render_world.resource::<DeviceGeneration>(),
+            DeviceGeneration(3)
+        );
+        assert_eq!(sent(&mut main_world), [2, 3]);
+        assert_eq!(render_world.resource::<Recreated>().0, [2, 3]);
+    }
+
+    #[test]
+    fn errors_are_counted_by_type() {
+        let mut main_world = World::new();
+        main_world.init_resource::<HandledErrors>();
+        main_world.insert_resource(recording_handler());
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+
+        for ty in [
+            ErrorType::Validation,
+            ErrorType::Internal,
+            ErrorType::Validation,
+            ErrorType::OutOfMemory,
+        ] {
+            render_world
+                .resource::<DeviceErrorHandler>()
+                .push_error(error(ty, ""));
+            update_state(&mut main_world, &mut render_world);
+            render_world.insert_resource(RenderState::Ready);
+        }
+
+        let stats = main_world.resource::<RenderErrorStats>();
+        assert_eq!(stats.count(ErrorType::Validation), 2);
+        assert_eq!(stats.count(ErrorType::Internal), 1);
+        assert_eq!(stats.count(ErrorType::OutOfMemory), 1);
+        assert_eq!(stats.count(ErrorType::DeviceLost), 0);
+        assert_eq!(stats.total(), 4);
+        assert_eq!(
+            stats.last_error().map(|(ty, _)| ty),
+            Some(ErrorType::OutOfMemory)
+        );
+    }
+
+    #[test]
+    fn render_status_follows_state() {
+        let mut main_world = World::new();
+        main_world.init_resource::<HandledErrors>();
+        main_world.init_resource::<RenderStatus>();
+        main_world.insert_resource(recording_handler());
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.add_schedule(RenderStartup::base_schedule());
+        render_world.insert_resource(RenderState::Initializing);
+
+        assert_eq!(
+            *main_world.resource::<RenderStatus>(),
+            RenderStatus::Initializing
+        );
+
+        // Startup runs during the first update, which then renders.
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(*main_world.resource::<RenderStatus>(), RenderStatus::Ready);
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(
+            *render_world.resource::<RenderStatus>(),
+            RenderStatus::Ready
+        );
+
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::OutOfMemory, "stop"));
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(
+            *main_world.resource::<RenderStatus>(),
+            RenderStatus::Errored(ErrorType::OutOfMemory)
+        );
+    }
+
+    #[test]
+    fn rendering_pauses_and_resumes() {
+        #[derive(Resource, Default)]
+        struct RenderRuns(u32);
+
+        let mut main_world = World::new();
+        main_world.init_resource::<HandledErrors>();
+        main_world.insert_resource(recording_handler());
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.init_resource::<RenderScheduleOrder>();
+        render_world.init_resource::<RenderRuns>();
+        render_world.add_schedule(RenderStartup::base_schedule());
+        render_world.insert_resource(RenderState::Initializing);
+        let mut render = Render::base_schedule();
+        render.add_systems(
+            (|mut runs: ResMut<RenderRuns>| runs.0 += 1).in_set(RenderSystems::Render),
+        );
+        render_world.add_schedule(render);
+        let mut recovery = Schedule::new(RenderRecovery);
+        recovery.add_systems(run_render_schedule.run_if(renderer_is_ready));
+        render_world.add_schedule(recovery);
+
+        let frame = |main_world: &mut World, render_world: &mut World| {
+            update_state(main_world, render_world);
+            render_world.run_schedule(RenderRecovery);
+            (
+                *render_world.resource::<RenderStatus>(),
+                render_world.resource::<RenderRuns>().0,
+            )
+        };
+
+        assert_eq!(
+            frame(&mut main_world, &mut render_world),
+            (RenderStatus::Ready, 1)
+        );
+
+        main_world.insert_resource(RenderEnabled(false));
+        for _ in 0..3 {
+            assert_eq!(
+                frame(&mut main_world, &mut render_world),
+                (RenderStatus::Paused, 1)
+            );
+        }
+
+        // Resuming keeps the device, startup doesn't run again.
+        main_world.insert_resource(RenderEnabled(true));
+        assert_eq!(
+            frame(&mut main_world, &mut render_world),
+            (RenderStatus::Ready, 2)
+        );
+        assert_eq!(render_world.resource::<RenderStartupRuns>().0, 1);
+    }
+
+    #[test]
+    fn feature_downgrade_plan_is_cumulative() {
+        let base = WgpuSettings {
+            features: WgpuFeatures::SHADER_F16 | WgpuFeatures::TEXTURE_COMPRESSION_BC,
+            ..Default::default()
+        };
+        let plan = FeatureDowngradePlan {
+            attempts: vec![
+                FeatureDowngrade {
+                    features: WgpuFeatures::SHADER_F16,
+                    limits: None,
+                },
+                FeatureDowngrade {
+                    features: WgpuFeatures::TEXTURE_COMPRESSION_BC,
+                    limits: Some(WgpuLimits {
+                        max_texture_dimension_2d: 4096,
+                        ..WgpuLimits::default()
+                    }),
+                },
+            ],
+        };
+
+        let first = plan.apply(&base, 0).unwrap();
+        assert_eq!(first.features, WgpuFeatures::TEXTURE_COMPRESSION_BC);
+        assert_eq!(first.disabled_features, Some(WgpuFeatures::SHADER_F16));
+        assert!(first.constrained_limits.is_none());
+
+        let second = plan.apply(&base, 1).unwrap();
+        assert!(second.features.is_empty());
+        assert_eq!(
+            second.disabled_features,
+            Some(WgpuFeatures::SHADER_F16 | WgpuFeatures::TEXTURE_COMPRESSION_BC)
+        );
+        assert_eq!(
+            second
+                .constrained_limits
+                .map(|limits| limits.max_texture_dimension_2d),
+            Some(4096)
+        );
+
+        assert!(plan.apply(&base, 2).is_none());
+    }
+
+    #[test]
+    fn feature_downgrade_restarts_after_a_successful_frame() {
+        let mut main_world = World::new();
+        // These settings can't create a renderer, so every attempt fails right away.
+        main_world.insert_resource(LastWgpuSettings(WgpuSettings {
+            backends: None,
+            features: WgpuFeatures::SHADER_F16 | WgpuFeatures::TEXTURE_COMPRESSION_BC,
+            ..Default::default()
+        }));
+        main_world.insert_resource(RenderErrorHandler(|_, _, _| {
+            RenderErrorPolicy::RecoverDowngraded(FeatureDowngradePlan::dropping_features([
+                WgpuFeatures::SHADER_F16,
+                WgpuFeatures::TEXTURE_COMPRESSION_BC,
+            ]))
+        }));
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+        let lose_device = |main_world: &mut World, render_world: &mut World| {
+            render_world
+                .resource::<DeviceErrorHandler>()
+                .push_error(error(ErrorType::DeviceLost, "lost"));
+            update_state(main_world, render_world);
+            main_world.resource::<LastWgpuSettings>().0.features
+        };
+
+        assert_eq!(
+            lose_device(&mut main_world, &mut render_world),
+            WgpuFeatures::TEXTURE_COMPRESSION_BC
+        );
+        assert_eq!(main_world.resource::<FeatureDowngradeState>().attempt, 1);
+
+        // Pretend the downgraded renderer was created and rendered a frame.
+        render_world.insert_resource(RenderState::Ready);
+        update_state(&mut main_world, &mut render_world);
+        assert!(!main_world.contains_resource::<FeatureDowngradeState>());
+
+        // The plan starts over from the settings the renderer recovered with.
+        assert_eq!(
+            lose_device(&mut main_world, &mut render_world),
+            WgpuFeatures::TEXTURE_COMPRESSION_BC
+        );
+        assert_eq!(main_world.resource::<FeatureDowngradeState>().attempt, 1);
+    }
+
+    #[test]
+    fn creation_report_lists_ungranted_features() {
+        // The adapter only supports `SHADER_F16`.
+        let report = RenderCreationReport::new(
+            WgpuFeatures::SHADER_F16 | WgpuFeatures::TEXTURE_COMPRESSION_BC,
+            WgpuFeatures::SHADER_F16 | WgpuFeatures::TIMESTAMP_QUERY,
+            WgpuLimits::default(),
+        );
+        assert_eq!(
+            report.ungranted_features,
+            WgpuFeatures::TEXTURE_COMPRESSION_BC
+        );
+    }
+
+    #[test]
+    fn manual_creation_reports_the_supplied_features() {
+        let mut main_world = World::new();
+        main_world.init_resource::<Messages<RenderRecovered>>();
+        main_world.init_resource::<Messages<RenderDeviceRecreated>>();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.add_schedule(RenderStartup::base_schedule());
+        // Left over from a previous automatic creation.
+        main_world.insert_resource(LastWgpuSettings(WgpuSettings {
+            features: WgpuFeatures::TEXTURE_COMPRESSION_BC,
+            ..Default::default()
+        }));
+
+        let resources = noop_render_resources(WgpuFeatures::DEPTH_CLIP_CONTROL);
+        remember_settings(&RenderCreation::Manual(resources.clone()), &mut main_world);
+        resources.unpack_into(&mut main_world, &mut render_world, true);
+
+        let report = main_world.resource::<RenderCreationReport>();
+        assert_eq!(
+            report.requested_features,
+            main_world.resource::<RenderDevice>().features()
+        );
+        assert!(report.ungranted_features.is_empty());
+    }
+
+    #[test]
+    fn automatic_creation_reports_progress() {
+        let mut main_world = World::new();
+        main_world.init_resource::<Messages<RenderRecovered>>();
+        main_world.init_resource::<Messages<RenderDeviceRecreated>>();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.add_schedule(RenderStartup::base_schedule());
+        RENDER_INIT_STAGES.take();
+
+        let creation = RenderCreation::headless(WgpuSettings::default());
+        let context = RenderCreationContext::from_main_world(&mut main_world);
+        remember_settings(&creation, &mut main_world);
+        let future_resources = FutureRenderResources::default();
+        let created = creation.create_render(future_resources.clone(), context);
+
+        let mut stages = RENDER_INIT_STAGES.take();
+        assert_eq!(
+            stages[..2],
+            [
+                RenderInitStage::CreatingInstance,
+                RenderInitStage::RequestingAdapter
+            ]
+        );
+        // There is no adapter, not even a software one.
+        let Poll::Ready(Some(resources)) = future_resources.poll() else {
+            assert!(!created);
+            return;
+        };
+        resources.unpack_into(&mut main_world, &mut render_world, true);
+
+        stages.extend(RENDER_INIT_STAGES.take());
+        assert_eq!(
+            stages,
+            [
+                RenderInitStage::CreatingInstance,
+                RenderInitStage::RequestingAdapter,
+                RenderInitStage::RequestingDevice,
+                RenderInitStage::Unpacking,
+                RenderInitStage::Ready,
+            ]
+        );
+        assert_eq!(
+            main_world.resource::<RenderInitProgress>().stage(),
+            RenderInitStage::Ready
+        );
+    }
+
+    fn handle(
+        main_world: &mut World,
+        map: &RenderErrorPolicyMap,
+        ty: ErrorType,
+    ) -> Option<RenderState> {
+        main_world.insert_resource(map.clone());
+        let mut render_world = World::new();
+        let error = RenderError {
+            ty,
+            description: String::new(),
+            source: None,
+        };
+        RenderErrorHandler::default().handle(&error, main_world, &mut render_world);
+        render_world.remove_resource::<RenderState>()
+    }
+
+    fn handle_with_auto_recover(ty: ErrorType) -> Option<RenderState> {
+        handle(&mut World::new(), &RenderErrorPolicyMap::auto_recover(), ty)
+    }
+
+    #[test]
+    fn auto_recover_policy_table() {
+        let map = RenderErrorPolicyMap::auto_recover();
+        assert_eq!(
+            map.get(ErrorType::DeviceLost),
+            MappedErrorPolicy::RecoverWithPreviousSettings
+        );
+        assert_eq!(
+            map.get(ErrorType::OutOfMemory),
+            MappedErrorPolicy::StopRendering
+        );
+        assert_eq!(map.get(ErrorType::Validation), MappedErrorPolicy::Ignore);
+        assert_eq!(map.get(ErrorType::Internal), MappedErrorPolicy::Ignore);
+
+        // Ignored errors go back to rendering, stopping keeps the errored state.
+        assert!(matches!(
+            handle_with_auto_recover(ErrorType::Validation),
+            Some(RenderState::Ready)
+        ));
+        assert!(matches!(
+            handle_with_auto_recover(ErrorType::Internal),
+            Some(RenderState::Ready)
+        ));
+        assert!(handle_with_auto_recover(ErrorType::OutOfMemory).is_none());
+        // Without previous settings, e.g. for manually created renderers, recovery stops rendering.
+        assert!(handle_with_auto_recover(ErrorType::DeviceLost).is_none());
+
+        let map = RenderErrorPolicyMap::auto_recover()
+            .with(ErrorType::OutOfMemory, MappedErrorPolicy::Ignore);
+        assert_eq!(map.get(ErrorType::OutOfMemory), MappedErrorPolicy::Ignore);
+    }
+
+    #[cfg(feature = "auto_recover")]
+    #[test]
+    fn default_policy_table_recovers_with_auto_recover() {
+        let map = RenderErrorPolicyMap::default();
+        assert_eq!(
+            map.get(ErrorType::DeviceLost),
+            MappedErrorPolicy::RecoverWithPreviousSettings
+        );
+        assert_eq!(
+            map.get(ErrorType::OutOfMemory),
+            MappedErrorPolicy::StopRendering
+        );
+        assert_eq!(map.get(ErrorType::Validation), MappedErrorPolicy::Ignore);
+        assert_eq!(map.get(ErrorType::Internal), MappedErrorPolicy::Ignore);
+    }
+
+    #[cfg(not(feature = "auto_recover"))]
+    #[test]
+    fn default_policy_table_ignores_everything() {
+        let map = RenderErrorPolicyMap::default();
+        for ty in [
+            ErrorType::DeviceLost,
+            ErrorType::OutOfMemory,
+            ErrorType::Validation,
+            ErrorType::Internal,
+        ] {
+            assert_eq!(map.get(ty), MappedErrorPolicy::Ignore);
+            assert!(matches!(
+                handle(&mut World::new(), &map, ty),
+                Some(RenderState::Ready)
+            ));
+        }
+    }
+
+    #[test]
+    fn device_loss_is_recovered_again_after_a_successful_frame() {
+        let mut main_world = World::new();
+        // These settings can't create a renderer, so every attempt fails right away.
+        main_world.insert_resource(LastWgpuSettings(WgpuSettings {
+            backends: None,
+            ..Default::default()
+        }));
+        main_world.insert_resource(RenderErrorPolicyMap::auto_recover());
+        main_world.init_resource::<RecoveryLimits>();
+        main_world.init_resource::<RenderErrorHandler>();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+        let failed_recoveries = |main_world: &World| {
+            main_world
+                .resource::<RenderErrorStats>()
+                .failed_recoveries()
+        };
+
+        // The single attempt of the default limits is used up.
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::DeviceLost, "lost"));
+        update_state(&mut main_world, &mut render_world);
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(failed_recoveries(&main_world), 1);
+
+        // Pretend a later recovery succeeded, and a frame rendered without error.
+        render_world.insert_resource(RenderState::Ready);
+        update_state(&mut main_world, &mut render_world);
+
+        // Losing the device again, e.g. hours later, is recovered from again.
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::DeviceLost, "lost again"));
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(failed_recoveries(&main_world), 2);
+    }
+
+    fn error(ty: ErrorType, description: &str) -> RenderError {
+        RenderError {
+            ty,
+            description: description.into(),
+            source: None,
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct HandledErrors(Vec<String>);
+
+    /// Records the handled errors, stopping rendering for out of memory errors.
+    fn recording_handler() -> RenderErrorHandler {
+        RenderErrorHandler(|error, main_world, _| {
+            main_world
+                .resource_mut::<HandledErrors>()
+                .0
+                .push(error.description.clone());
+            match error.ty {
+                ErrorType::OutOfMemory => RenderErrorPolicy::StopRendering,
+                _ => RenderErrorPolicy::Ignore,
+            }
+        })
+    }
+
+    #[test]
+    fn device_errors_are_queued_in_order() {
+        let handler = DeviceErrorHandler::default();
+        handler.set_max_pending_errors(3);
+        for i in 0..4 {
+            handler.push_error(error(ErrorType::Validation, &i.to_string()));
+        }
+        let descriptions = |handler: &DeviceErrorHandler| {
+            handler
+                .drain()
+                .into_iter()
+                .map(|error| error.description)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(descriptions(&handler), ["0", "1", "2"]);
+        assert!(handler.drain().is_empty());
+
+        // Device loss comes first, even when the queue is full.
+        for i in 0..3 {
+            handler.push_error(error(ErrorType::Validation, &i.to_string()));
+        }
+        handler.push_error(error(ErrorType::DeviceLost, "lost"));
+        assert_eq!(descriptions(&handler), ["lost", "0", "1"]);
+    }
+
+    #[test]
+    fn update_state_handles_errors_sequentially() {
+        let mut main_world = World::new();
+        main_world.init_resource::<HandledErrors>();
+        main_world.insert_resource(recording_handler());
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+
+        let device_errors = render_world.resource::<DeviceErrorHandler>();
+        device_errors.push_error(error(ErrorType::Validation, "first"));
+        device_errors.push_error(error(ErrorType::Validation, "second"));
+        update_state(&mut main_world, &mut render_world);
+        assert!(matches!(
+            render_world.resource::<RenderState>(),
+            RenderState::Ready
+        ));
+
+        // Errors after one that stops rendering are dropped.
+        let device_errors = render_world.resource::<DeviceErrorHandler>();
+        device_errors.push_error(error(ErrorType::OutOfMemory, "third"));
+        device_errors.push_error(error(ErrorType::Validation, "fourth"));
+        update_state(&mut main_world, &mut render_world);
+        assert!(matches!(
+            render_world.resource::<RenderState>(),
+            RenderState::Errored(_)
+        ));
+        assert_eq!(
+            main_world.resource::<HandledErrors>().0,
+            ["first", "second", "third"]
+        );
+
+        // The errored state is handled again every frame.
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(main_world.resource::<HandledErrors>().0.len(), 4);
+    }
+
+    #[test]
+    fn errors_are_sent_as_messages() {
+        let mut main_world = World::new();
+        main_world.init_resource::<Messages<RenderErrorOccurred>>();
+        main_world.init_resource::<HandledErrors>();
+        main_world.insert_resource(recording_handler());
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+
+        let device_errors = render_world.resource::<DeviceErrorHandler>();
+        device_errors.push_error(error(ErrorType::OutOfMemory, "first"));
+        device_errors.push_error(error(ErrorType::Validation, "second"));
+        update_state(&mut main_world, &mut render_world);
+
+        // Only the first error is handled, but both are sent.
+        assert_eq!(main_world.resource::<HandledErrors>().0, ["first"]);
+        let sent: Vec<_> = main_world
+            .resource::<Messages<RenderErrorOccurred>>()
+            .iter_current_update_messages()
+            .map(|occurred| (occurred.ty, occurred.description.as_str()))
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                (ErrorType::OutOfMemory, "first"),
+                (ErrorType::Validation, "second")
+            ]
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct FakeCache {
+        reclaimed: u32,
+    }
+
+    impl ReclaimableGpuResource for FakeCache {
+        fn reclaim(&mut self) {
+            self.reclaimed += 1;
+        }
+    }
+
+    #[test]
+    fn out_of_memory_recovery_reclaims_resources() {
+        let mut render_app = SubApp::new();
+        render_app
+            .init_resource::<FakeCache>()
+            .init_resource::<DeviceErrorHandler>()
+            .insert_resource(RenderState::Ready)
+            .register_reclaimable_gpu_resource::<FakeCache>();
+        let render_world = render_app.world_mut();
+        let mut main_world = World::new();
+        main_world.insert_resource(RenderErrorHandler(|error, _, _| match error.ty {
+            ErrorType::OutOfMemory => RenderErrorPolicy::RecoverWithPreviousSettings,
+            _ => RenderErrorPolicy::Ignore,
+        }));
+        let mut frame = |main_world: &mut World, ty: Option<ErrorType>| {
+            if let Some(ty) = ty {
+                render_world
+                    .resource::<DeviceErrorHandler>()
+                    .push_error(error(ty, ""));
+            }
+            update_state(main_world, render_world);
+            render_world.resource::<FakeCache>().reclaimed
+        };
+
+        assert_eq!(frame(&mut main_world, Some(ErrorType::Validation)), 0);
+
+        // Without previous settings, no recovery starts, so nothing is reclaimed.
+        assert_eq!(frame(&mut main_world, Some(ErrorType::OutOfMemory)), 0);
+        assert_eq!(frame(&mut main_world, None), 0);
+
+        // These settings can't create a renderer, but the attempt starts and reclaims first.
+        main_world.insert_resource(LastWgpuSettings(WgpuSettings {
+            backends: None,
+            ..Default::default()
+        }));
+        assert_eq!(frame(&mut main_world, None), 1);
+
+        // Once the attempts are used up, the errored frames don't reclaim anymore.
+        for _ in 0..3 {
+            assert_eq!(frame(&mut main_world, None), 1);
+        }
+    }
+
+    /// Worlds with a renderer on the noop backend, as unpacked by the plugin.
+    fn noop_worlds() -> (World, World) {
+        let mut main_world = World::new();
+        main_world.init_resource::<Messages<RenderRecovered>>();
+        main_world.init_resource::<Messages<RenderDeviceRecreated>>();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.add_schedule(RenderStartup::base_schedule());
+        noop_render_resources(WgpuFeatures::empty()).unpack_into(
+            &mut main_world,
+            &mut render_world,
+            true,
+        );
+        (main_world, render_world)
+    }
+
+    #[test]
+    fn reinitializing_does_not_wait_for_creation() {
+        let (mut main_world, mut render_world) = noop_worlds();
+        let future_resources = FutureRenderResources::default();
+        main_world.insert_resource(future_resources.clone());
+        render_world.insert_resource(RenderState::Reinitializing(ErrorType::DeviceLost));
+        let status = |main_world: &mut World, render_world: &mut World| {
+            update_state(main_world, render_world);
+            *render_world.resource::<RenderStatus>()
+        };
+
+        // The creation hasn't finished yet.
+        for _ in 0..3 {
+            assert_eq!(
+                status(&mut main_world, &mut render_world),
+                RenderStatus::Reinitializing
+            );
+        }
+
+        // A creation that panicked while holding the lock doesn't block recovery forever.
+        let poisoner = future_resources.clone();
+        std::thread::spawn(move || {
+            let _resources = poisoner.resources.lock().unwrap();
+            panic!("poisoning the lock");
+        })
+        .join()
+        .unwrap_err();
+        assert_eq!(
+            status(&mut main_world, &mut render_world),
+            RenderStatus::Reinitializing
+        );
+
+        future_resources.set(noop_render_resources(WgpuFeatures::empty()));
+        assert_eq!(
+            status(&mut main_world, &mut render_world),
+            RenderStatus::Initializing
+        );
+        assert_eq!(
+            status(&mut main_world, &mut render_world),
+            RenderStatus::Ready
+        );
+        assert_eq!(
+            *render_world.resource::<DeviceGeneration>(),
+            DeviceGeneration(2)
+        );
+        assert_eq!(
+            main_world
+                .resource_mut::<Messages<RenderRecovered>>()
+                .drain()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn device_only_recovery_keeps_the_instance_and_adapter() {
+        let (mut main_world, mut render_world) = noop_worlds();
+        // Creating a new renderer would fail, so only the device can be recreated.
+        main_world.insert_resource(RenderErrorHandler(|_, _, _| {
+            RenderErrorPolicy::RecoverDeviceOnly(
+                WgpuSettings {
+                    backends: None,
+                    ..Default::default()
+                }
+                .into(),
+            )
+        }));
+        render_world.insert_resource(RenderState::Ready);
+        let instance = render_world.resource::<RenderInstance>().0.clone();
+        let adapter = render_world.resource::<RenderAdapter>().0.clone();
+        let device = render_world.resource::<RenderDevice>().clone();
+
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::DeviceLost, "lost"));
+        update_until_ready(&mut main_world, &mut render_world);
+
+        assert_eq!(
+            *render_world.resource::<DeviceGeneration>(),
+            DeviceGeneration(2)
+        );
+        assert!(Arc::ptr_eq(
+            &instance,
+            &render_world.resource::<RenderInstance>().0
+        ));
+        assert!(Arc::ptr_eq(
+            &adapter,
+            &render_world.resource::<RenderAdapter>().0
+        ));
+        assert_ne!(
+            render_world.resource::<RenderDevice>().wgpu_device(),
+            device.wgpu_device()
+        );
+    }
+
+    /// Updates until the renderer recovered, as it is created in the background.
+    fn update_until_ready(main_world: &mut World, render_world: &mut World) {
+        for _ in 0..1000 {
+            bevy_tasks::tick_global_task_pools_on_main_thread();
+            update_state(main_world, render_world);
+            if *render_world.resource::<RenderStatus>() == RenderStatus::Ready {
+                return;
+            }
+            std::thread::sleep(core::time::Duration::from_millis(1));
+        }
+        panic!("the renderer didn't recover");
+    }
+
+    #[test]
+    fn recovery_recreates_the_caches_for_the_new_device() {
+        const SHADER: Handle<Shader> = uuid_handle!("c6a7f1e2-5d4b-4f0e-9a3c-7b2d1e8f6a40");
+        #[derive(Resource, Default)]
+        struct Recreated(u32);
+
+        let (mut main_world, mut render_world) = noop_worlds();
+        main_world.insert_resource(RenderErrorHandler(|_, _, _| {
+            RenderErrorPolicy::Recover(noop_render_resources(WgpuFeatures::empty()).into())
+        }));
+        render_world.insert_resource(RenderState::Ready);
+        render_world.init_resource::<Messages<RenderDeviceRecreated>>();
+        render_world.init_resource::<TextureCache>();
+        render_world.init_resource::<WindowSurfaces>();
+        render_world.init_resource::<Recreated>();
+        render_world.add_observer(PipelineCache::recreate_on_device_recreated);
+        render_world.add_observer(clear_texture_cache);
+        render_world.add_observer(clear_window_surfaces);
+        render_world.add_observer(
+            |_: On<RenderDeviceRecreated>, mut recreated: ResMut<Recreated>| recreated.0 += 1,
+        );
+
+        let shader = || {
+            Shader::from_wgsl(
+                "@compute @workgroup_size(1)\nfn main() {}\n",
+                "recreated.wgsl",
+            )
+        };
+        let mut pipeline_cache = render_world.resource_mut::<PipelineCache>();
+        pipeline_cache.set_shader(SHADER.id(), shader());
+        let pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            shader: SHADER,
+            ..Default::default()
+        });
+        pipeline_cache.process_queue();
+        assert!(pipeline_cache.get_compute_pipeline(pipeline).is_some());
+
+        let texture_descriptor = TextureDescriptor {
+            label: None,
+            size: Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        };
+        let cached_texture = |render_world: &mut World| {
+            render_world.resource_scope(|render_world, mut texture_cache: Mut<TextureCache>| {
+                let texture = texture_cache.get(
+                    render_world.resource::<RenderDevice>(),
+                    texture_descriptor.clone(),
+                );
+                // Make it available to the next frame.
+                texture_cache.update();
+                texture.texture.id()
+            })
+        };
+        let texture = cached_texture(&mut render_world);
+        assert_eq!(cached_texture(&mut render_world), texture);
+
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::DeviceLost, "lost"));
+        update_until_ready(&mut main_world, &mut render_world);
+
+        assert_eq!(
+            *render_world.resource::<DeviceGeneration>(),
+            DeviceGeneration(2)
+        );
+        assert_eq!(render_world.resource::<Recreated>().0, 1);
+        for world in [&mut main_world, &mut render_world] {
+            let mut messages = world.resource_mut::<Messages<RenderDeviceRecreated>>();
+            assert_eq!(messages.drain().count(), 1);
+        }
+
+        // The pipeline keeps its ID, and is created again once its shader is reloaded.
+        let mut pipeline_cache = render_world.resource_mut::<PipelineCache>();
+        assert!(pipeline_cache.get_compute_pipeline(pipeline).is_none());
+        pipeline_cache.set_shader(SHADER.id(), shader());
+        pipeline_cache.process_queue();
+        assert!(pipeline_cache.get_compute_pipeline(pipeline).is_some());
+
+        // Textures of the lost device aren't reused.
+        assert_ne!(cached_texture(&mut render_world), texture);
+    }
+
+    #[test]
+    fn failed_creation_goes_back_to_errored() {
+        let (mut main_world, mut render_world) = noop_worlds();
+        let future_resources = FutureRenderResources::default();
+        main_world.insert_resource(future_resources.clone());
+        render_world.insert_resource(RenderState::Reinitializing(ErrorType::DeviceLost));
+
+        future_resources.fail();
+        update_state(&mut main_world, &mut render_world);
+
+        assert_eq!(
+            *render_world.resource::<RenderStatus>(),
+            RenderStatus::Errored(ErrorType::DeviceLost)
+        );
+        assert_eq!(
+            main_world
+                .resource::<RenderErrorStats>()
+                .failed_recoveries(),
+            1
+        );
+        assert_eq!(
+            *render_world.resource::<DeviceGeneration>(),
+            DeviceGeneration(1)
+        );
+    }
+
+    #[test]
+    fn recovery_backoff_is_capped() {
+        let limits = RecoveryLimits::with_backoff(3, 1);
+        let mut state = RecoveryState::default();
+
+        // The handler polls every frame as long as the renderer stays errored.
+        let frames: String = (0..11)
+            .map(|_| match state.poll(&limits) {
+                RecoveryBackoff::Attempt => {
+                    state.count_attempt();
+                    'r'
+                }
+                RecoveryBackoff::Wait => '.',
+                RecoveryBackoff::Exhausted => 'x',
+            })
+            .collect();
+        // Waits 1, 2 and 4 frames before the attempts, then gives up.
+        assert_eq!(frames, ".r..r....rx");
+    }
+
+    #[test]
+    fn repeated_device_loss_stops_recovering_after_the_cap() {
+        let mut main_world = World::new();
+        // These settings can't create a renderer, so every attempt fails right away.
+        main_world.insert_resource(RenderErrorHandler(|_, _, _| {
+            RenderErrorPolicy::recover_with_backoff(
+                WgpuSettings {
+                    backends: None,
+                    ..Default::default()
+                }
+                .into(),
+                2,
+                0,
+            )
+        }));
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+
+        // The device keeps getting lost every frame.
+        for _ in 0..5 {
+            render_world
+                .resource::<DeviceErrorHandler>()
+                .push_error(error(ErrorType::DeviceLost, "lost"));
+            update_state(&mut main_world, &mut render_world);
+        }
+
+        assert_eq!(render_world.resource::<RecoveryState>().attempts, 2);
+        assert_eq!(
+            main_world
+                .resource::<RenderErrorStats>()
+                .failed_recoveries(),
+            2
+        );
+        assert_eq!(
+            *render_world.resource::<RenderStatus>(),
+            RenderStatus::Errored(ErrorType::DeviceLost)
+        );
+    }
+
+    #[test]
+    fn recovery_state_resets_after_successful_frame() {
+        let mut main_world = World::new();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RecoveryState {
+            attempts: 2,
+            waited_frames: 1,
+        });
+        render_world.insert_resource(RenderState::Ready);
+
+        update_state(&mut main_world, &mut render_world);
+        assert_eq!(
+            *render_world.resource::<RecoveryState>(),
+            RecoveryState::default()
+        );
+    }
+
+    #[test]
+    fn failed_recovery_stays_errored() {
+        let mut main_world = World::new();
+        // Without backends, no renderer can be created.
+        main_world.insert_resource(RenderErrorHandler(|_, _, _| {
+            RenderErrorPolicy::Recover(
+                WgpuSettings {
+                    backends: None,
+                    ..Default::default()
+                }
+                .into(),
+            )
+        }));
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::DeviceLost, "lost"));
+
+        // The handler is asked again every frame, without panicking.
+        for _ in 0..2 {
+            update_state(&mut main_world, &mut render_world);
+            assert_eq!(
+                *render_world.resource::<RenderStatus>(),
+                RenderStatus::Errored(ErrorType::DeviceLost)
+            );
+        }
+        assert_eq!(
+            main_world
+                .resource::<RenderErrorStats>()
+                .failed_recoveries(),
+            2
+        );
+    }
+
+    #[test]
+    fn recovery_is_bounded_by_limits() {
+        let limits = RecoveryLimits {
+            max_attempts: 2,
+            ..Default::default()
+        };
+        let mut state = RecoveryState::default();
+        for _ in 0..2 {
+            assert_eq!(state.poll(&limits), RecoveryBackoff::Attempt);
+            state.count_attempt();
+        }
+        assert_eq!(state.poll(&limits), RecoveryBackoff::Exhausted);
+    }
+
+    #[test]
+    fn recovery_backs_off_through_update_state() {
+        let mut main_world = World::new();
+        // These settings can't create a renderer, so every attempt fails right away.
+        main_world.insert_resource(LastWgpuSettings(WgpuSettings {
+            backends: None,
+            ..Default::default()
+        }));
+        main_world.insert_resource(RecoveryLimits::with_backoff(3, 1));
+        main_world.insert_resource(RenderErrorPolicyMap::ignore_all().with(
+            ErrorType::DeviceLost,
+            MappedErrorPolicy::RecoverWithPreviousSettings,
+        ));
+        main_world.init_resource::<RenderErrorHandler>();
+        let mut render_world = World::new();
+        render_world.init_resource::<DeviceErrorHandler>();
+        render_world.insert_resource(RenderState::Ready);
+        render_world
+            .resource::<DeviceErrorHandler>()
+            .push_error(error(ErrorType::DeviceLost, "lost"));
+
+        let frames: String = (0..11)
+            .map(|_| {
+                let failed = main_world
+                    .get_resource::<RenderErrorStats>()
+                    .map_or(0, RenderErrorStats::failed_recoveries);
+                update_state(&mut main_world, &mut render_world);
+                let stats = main_world.resource::<RenderErrorStats>();
+                if stats.failed_recoveries() > failed {
+                    'r'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        // Waits 1, 2 and 4 frames before the attempts, then stays errored.
+        assert_eq!(frames, ".r..r....r.");
+        assert_eq!(render_world.resource::<RecoveryState>().attempts, 3);
+        assert_eq!(
+            *render_world.resource::<RenderStatus>(),
+            RenderStatus::Errored(ErrorType::DeviceLost)
+        );
+    }
+}
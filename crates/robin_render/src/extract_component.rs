@@ -1,5 +1,8 @@
+#[cfg(feature = "debug")]
+use crate::sync_world::ExtractChangeSummary;
 use crate::{
-    Extract, ExtractSchedule, RenderApp,
+    Extract, ExtractSchedule, MainWorld, RenderApp,
+    diagnostic::extract_throughput_diagnostic_plugin::ExtractThroughputMeasurements,
     sync_component::{SyncComponent, SyncComponentPlugin},
     sync_world::RenderEntity,
 };
@@ -59,6 +62,8 @@ pub trait ExtractComponent<F = ()>: SyncComponent<F> {
 /// marker, e.g. the type of the plugin that calls [`ExtractComponentPlugin`].
 pub struct ExtractComponentPlugin<C, F = ()> {
     only_extract_visible: bool,
+    only_extract_changed: bool,
+    run_condition: Option<fn(&World) -> bool>,
     marker: PhantomData<fn() -> (C, F)>,
 }
 
@@ -66,6 +71,8 @@ impl<C, F> Default for ExtractComponentPlugin<C, F> {
     fn default() -> Self {
         Self {
             only_extract_visible: false,
+            only_extract_changed: false,
+            run_condition: None,
             marker: PhantomData,
         }
     }
@@ -75,9 +82,45 @@ impl<C, F> ExtractComponentPlugin<C, F> {
     pub fn extract_visible() -> Self {
         Self {
             only_extract_visible: true,
-            marker: PhantomData,
+            ..Self::default()
+        }
+    }
+
+    /// Only extracts the components that were added or changed since the last extraction, or
+    /// whose entity was newly synced to the render world.
+    ///
+    /// Unchanged components keep their previously extracted value in the render world, and are
+    /// still removed from it when they are removed from the main world. Only changes to the
+    /// component itself are detected: if [`ExtractComponent::QueryData`] reads other components,
+    /// changing those alone won't extract it again.
+    pub fn extract_changed() -> Self {
+        Self {
+            only_extract_changed: true,
+            ..Self::default()
+        }
+    }
+
+    /// Combines [`ExtractComponentPlugin::extract_visible`] and
+    /// [`ExtractComponentPlugin::extract_changed`]. Components are also extracted again when the
+    /// visibility of their entity changed.
+    pub fn extract_visible_changed() -> Self {
+        Self {
+            only_extract_visible: true,
+            only_extract_changed: true,
+            ..Self::default()
         }
     }
+
+    /// Only extracts the components in frames where `condition` returns `true` for the main
+    /// world, e.g. for a debug overlay that is disabled most of the time.
+    ///
+    /// While the condition is `false`, the previously extracted components are left as they are
+    /// in the render world, they aren't cleared. Removing the component from the main world still
+    /// removes it from the render world, as that is handled by [`SyncComponentPlugin`].
+    pub fn run_if(mut self, condition: fn(&World) -> bool) -> Self {
+        self.run_condition = Some(condition);
+        self
+    }
 }
 
 impl<C: ExtractComponent<F>, F: 'static + Send + Sync> Plugin for ExtractComponentPlugin<C, F> {
@@ -85,20 +128,40 @@ impl<C: ExtractComponent<F>, F: 'static + Send + Sync> Plugin for ExtractCompone
         app.add_plugins(SyncComponentPlugin::<C, F>::default());
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
-            if self.only_extract_visible {
-                render_app.add_systems(ExtractSchedule, extract_visible_components::<C, F>);
-            } else {
-                render_app.add_systems(ExtractSchedule, extract_components::<C, F>);
+            let mut system = match (self.only_extract_visible, self.only_extract_changed) {
+                (false, false) => extract_components::<C, F, ()>.into_configs(),
+                (false, true) => extract_components::<C, F, ChangedOrSynced<C>>.into_configs(),
+                (true, false) => extract_visible_components::<C, F, ()>.into_configs(),
+                (true, true) => extract_visible_components::<
+                    C,
+                    F,
+                    Or<(ChangedOrSynced<C>, Changed<ViewVisibility>)>,
+                >
+                    .into_configs(),
+            };
+            if let Some(condition) = self.run_condition {
+                system = system.run_if(move |main_world: Res<MainWorld>| condition(&main_world));
             }
+            render_app.add_systems(ExtractSchedule, system);
         }
     }
 }
 
+/// Matches components that changed since the last extraction, or whose entity was (re)synced to
+/// the render world since then and therefore has no extracted copy yet.
+type ChangedOrSynced<C> = Or<(Changed<C>, Changed<RenderEntity>)>;
+
 /// This system extracts all components of the corresponding [`ExtractComponent`], for entities that are synced via [`crate::sync_world::SyncToRenderWorld`].
-fn extract_components<C: ExtractComponent<F>, F>(
+///
+/// `Q` further filters the extracted entities, e.g. [`Changed`] to skip unchanged components.
+/// [`Extract`] keeps the change ticks of the main world per frame, so [`Changed`] is relative to
+/// the last extraction.
+fn extract_components<C: ExtractComponent<F>, F, Q: QueryFilter>(
     mut commands: Commands,
     mut previous_len: Local<usize>,
-    query: Extract<Query<(RenderEntity, C::QueryData), C::QueryFilter>>,
+    query: Extract<Query<(RenderEntity, C::QueryData), (C::QueryFilter, Q)>>,
+    measurements: Extract<Option<Res<ExtractThroughputMeasurements>>>,
+    #[cfg(feature = "debug")] summary: Option<Res<ExtractChangeSummary>>,
 ) {
     let mut values = Vec::with_capacity(*previous_len);
     for (entity, query_item) in &query {
@@ -106,17 +169,30 @@ fn extract_components<C: ExtractComponent<F>, F>(
             values.push((entity, component));
         } else {
             commands.entity(entity).remove::<C::Target>();
+            #[cfg(feature = "debug")]
+            if let Some(summary) = &summary {
+                summary.record_removed(1);
+            }
         }
     }
     *previous_len = values.len();
+    if let Some(measurements) = &*measurements {
+        measurements.record_extracted(values.len());
+    }
+    #[cfg(feature = "debug")]
+    if let Some(summary) = &summary {
+        summary.record_inserted(values.len());
+    }
     commands.try_insert_batch(values);
 }
 
 /// This system extracts all components of the corresponding [`ExtractComponent`], for entities that are visible and synced via [`crate::sync_world::SyncToRenderWorld`].
-fn extract_visible_components<C: ExtractComponent<F>, F>(
+fn extract_visible_components<C: ExtractComponent<F>, F, Q: QueryFilter>(
     mut commands: Commands,
     mut previous_len: Local<usize>,
-    query: Extract<Query<(RenderEntity, &ViewVisibility, C::QueryData), C::QueryFilter>>,
+    query: Extract<Query<(RenderEntity, &ViewVisibility, C::QueryData), (C::QueryFilter, Q)>>,
+    measurements: Extract<Option<Res<ExtractThroughputMeasurements>>>,
+    #[cfg(feature = "debug")] summary: Option<Res<ExtractChangeSummary>>,
 ) {
     let mut values = Vec::with_capacity(*previous_len);
     for (entity, view_visibility, query_item) in &query {
@@ -125,9 +201,20 @@ fn extract_visible_components<C: ExtractComponent<F>, F>(
                 values.push((entity, component));
             } else {
                 commands.entity(entity).remove::<C::Target>();
+                #[cfg(feature = "debug")]
+                if let Some(summary) = &summary {
+                    summary.record_removed(1);
+                }
             }
         }
     }
     *previous_len = values.len();
+    if let Some(measurements) = &*measurements {
+        measurements.record_extracted(values.len());
+    }
+    #[cfg(feature = "debug")]
+    if let Some(summary) = &summary {
+        summary.record_inserted(values.len());
+    }
     commands.try_insert_batch(values);
 }
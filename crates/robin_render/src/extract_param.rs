@@ -73,7 +73,12 @@ where
     type Item<'w, 's> = Extract<'w, 's, P>;
 
     fn init_state(world: &mut World) -> Self::State {
-        let mut main_world = world.resource_mut::<MainWorld>();
+        let Some(mut main_world) = world.get_resource_mut::<MainWorld>() else {
+            panic!(
+                "`Extract` can only be used by systems in the `ExtractSchedule`, \
+                as the `MainWorld` is only available during extraction"
+            );
+        };
         ExtractState {
             state: SystemState::new(&mut main_world),
             main_world_state: Res::<MainWorld>::init_state(world),
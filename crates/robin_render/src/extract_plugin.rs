@@ -1,6 +1,15 @@
 use crate::{
     Render, RenderApp, RenderSystems,
-    sync_world::{SyncWorldPlugin, despawn_temporary_render_entities, entity_sync_system},
+    diagnostic::{
+        SyncPointTiming,
+        render_schedule_diagnostic_plugin::{RenderPhase, RenderScheduleMeasurements},
+        timed,
+    },
+    error_handler::RenderState,
+    frame_count::RenderFrameTime,
+    sync_world::{
+        EntitySyncMap, SyncWorldPlugin, despawn_temporary_render_entities, entity_sync_system,
+    },
 };
 use bevy_app::{App, Plugin, SubApp};
 use bevy_derive::{Deref, DerefMut};
@@ -9,22 +18,75 @@ use bevy_ecs::{
     schedule::{IntoScheduleConfigs, Schedule, ScheduleBuildSettings, ScheduleLabel, Schedules},
     world::{Mut, World},
 };
+use bevy_platform::time::Instant;
 use bevy_utils::default;
+use core::time::Duration;
+
+/// A function run at the beginning of each extraction, see [`PreExtractAppExt`].
+///
+/// Gets the main world and render world as arguments (in that order).
+pub type PreExtractHook = fn(&mut World, &mut World);
 
 /// Plugin that sets up the [`RenderApp`] and handles extracting data from the
 /// main world to the render world.
+#[derive(Default)]
 pub struct ExtractPlugin {
-    /// Function that gets run at the beginning of each extraction.
+    /// Functions that get run at the beginning of each extraction, in order, before entities are
+    /// synced. More can be added later with [`PreExtractAppExt::add_pre_extract_hook`].
+    ///
+    /// [`RenderPlugin`](crate::RenderPlugin) registers the renderer state update as the first one.
+    pub pre_extract: Vec<PreExtractHook>,
+}
+
+/// How long the parts of the extraction took on the CPU, in the render world.
+///
+/// Extraction blocks the main app, so it should be kept short. The timings are measured with two
+/// [`Instant`] reads per part and recorded at the end of every extraction, making them cheap
+/// enough to always be on. Their sum is the extraction time published by
+/// [`RenderScheduleDiagnosticPlugin`](crate::diagnostic::RenderScheduleDiagnosticPlugin) to
+/// the main world.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ExtractTimings {
+    /// Syncing entities to the render world, see [`SyncWorldPlugin`].
+    pub entity_sync: SyncPointTiming,
+    /// Running the [`ExtractSchedule`].
+    pub schedule: SyncPointTiming,
+}
+
+impl ExtractTimings {
+    /// The number of extractions [`SyncPointTiming::window_average`] and
+    /// [`SyncPointTiming::worst`] look back.
+    pub const WINDOW: usize = 60;
+
+    fn record(&mut self, entity_sync: Duration, schedule: Duration) {
+        self.entity_sync.record(entity_sync, Self::WINDOW);
+        self.schedule.record(schedule, Self::WINDOW);
+    }
+}
+
+/// The [`PreExtractHook`]s of the render world, in the order they run.
+#[derive(Resource, Default)]
+struct PreExtractHooks(Vec<PreExtractHook>);
+
+/// Registers functions to run at the beginning of each extraction.
+pub trait PreExtractAppExt {
+    /// Adds a `hook` that runs at the beginning of each extraction, after the ones added before
+    /// it and before entities are synced to the render world.
     ///
-    /// Gets the main world and render world as arguments (in that order).
-    pub pre_extract: fn(&mut World, &mut World),
+    /// Does nothing if there is no [`RenderApp`].
+    fn add_pre_extract_hook(&mut self, hook: PreExtractHook) -> &mut Self;
 }
 
-impl Default for ExtractPlugin {
-    fn default() -> Self {
-        Self {
-            pre_extract: |_, _| {},
+impl PreExtractAppExt for App {
+    fn add_pre_extract_hook(&mut self, hook: PreExtractHook) -> &mut Self {
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app
+                .world_mut()
+                .get_resource_or_init::<PreExtractHooks>()
+                .0
+                .push(hook);
         }
+        self
     }
 }
 
@@ -48,6 +110,9 @@ impl Plugin for ExtractPlugin {
             .add_schedule(Render::base_schedule())
             .add_schedule(extract_schedule)
             .allow_ambiguous_resource::<MainWorld>()
+            .init_resource::<EntitySyncMap>()
+            .init_resource::<ExtractTimings>()
+            .insert_resource(PreExtractHooks(self.pre_extract.clone()))
             .add_systems(
                 Render,
                 (
@@ -58,18 +123,46 @@ impl Plugin for ExtractPlugin {
                 ),
             );
 
-        let pre_extract = self.pre_extract;
+        #[cfg(feature = "debug")]
+        render_app.init_resource::<crate::sync_world::ExtractChangeSummary>();
+
         render_app.set_extract(move |main_world, render_world| {
-            pre_extract(main_world, render_world);
+            render_world
+                .get_resource_or_init::<RenderFrameTime>()
+                .advance(Instant::now());
+            // Copied out, as the hooks get the render world mutably.
+            let hooks = render_world
+                .get_resource::<PreExtractHooks>()
+                .map(|hooks| hooks.0.clone())
+                .unwrap_or_default();
+            for hook in hooks {
+                hook(main_world, render_world);
+            }
 
+            // Nothing is extracted for a frame that isn't rendered, as the temporary render
+            // entities and the extract commands are only cleaned up by the `Render` schedule.
+            if !render_world
+                .get_resource::<RenderState>()
+                .is_none_or(|state| matches!(state, RenderState::Ready))
             {
+                return;
+            }
+
+            let ((), entity_sync_time) = timed(|| {
                 #[cfg(feature = "trace")]
                 let _stage_span = bevy_log::info_span!("entity_sync").entered();
                 entity_sync_system(main_world, render_world);
-            }
+            });
 
             // run extract schedule
-            extract(main_world, render_world);
+            let ((), schedule_time) = timed(|| extract(main_world, render_world));
+
+            render_world
+                .get_resource_or_init::<ExtractTimings>()
+                .record(entity_sync_time, schedule_time);
+            if let Some(measurements) = render_world.get_resource::<RenderScheduleMeasurements>() {
+                measurements.record(RenderPhase::Extract, entity_sync_time + schedule_time);
+            }
         });
 
         app.insert_sub_app(RenderApp, render_app);
@@ -127,15 +220,22 @@ pub fn extract(main_world: &mut World, render_world: &mut World) {
 
 #[cfg(test)]
 mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
     use bevy_app::{App, Startup};
-    use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+    use bevy_ecs::{change_detection::Tick, prelude::*, schedule::ScheduleLabel};
 
     use crate::{
-        Render, RenderApp,
+        Extract, ExtractSchedule, Render, RenderApp,
+        diagnostic::render_schedule_diagnostic_plugin::{RenderPhase, RenderScheduleMeasurements},
+        error_handler::RenderState,
         extract_component::{ExtractComponent, ExtractComponentPlugin},
-        extract_plugin::ExtractPlugin,
+        extract_plugin::{ExtractPlugin, ExtractTimings, PreExtractAppExt},
         sync_component::SyncComponent,
-        sync_world::MainEntity,
+        sync_world::{
+            EntitySyncMap, MainEntity, SyncToRenderWorld, SyncWithMarkerPlugin,
+            TemporaryRenderEntity,
+        },
     };
 
     #[derive(Component, Clone, Debug)]
@@ -251,4 +351,539 @@ mod test {
                 .unwrap();
         }
     }
+
+    #[derive(Resource, Default)]
+    struct ExtractedCount(usize);
+
+    #[test]
+    fn extract_param_reads_main_world() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(RenderComponent);
+            commands.spawn((RenderComponent, RenderComponentSeparate));
+            commands.spawn(RenderComponentSeparate);
+        });
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        render_app.init_resource::<ExtractedCount>();
+        render_app.add_systems(
+            ExtractSchedule,
+            |query: Extract<Query<&RenderComponent>>, mut count: ResMut<ExtractedCount>| {
+                count.0 = query.iter().count();
+            },
+        );
+
+        app.update();
+
+        let render_app = app.get_sub_app(RenderApp).unwrap();
+        assert_eq!(render_app.world().resource::<ExtractedCount>().0, 2);
+    }
+
+    #[test]
+    fn extraction_is_skipped_while_not_rendering() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.world_mut().spawn(SyncToRenderWorld);
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        render_app.init_resource::<ExtractedCount>();
+        render_app.insert_resource(RenderState::Paused);
+        render_app.add_systems(
+            ExtractSchedule,
+            |mut commands: Commands, mut count: ResMut<ExtractedCount>| {
+                count.0 += 1;
+                commands.spawn(TemporaryRenderEntity);
+            },
+        );
+        let render_world = |app: &mut App| app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+
+        for _ in 0..3 {
+            app.update();
+        }
+        let world = render_world(&mut app);
+        assert_eq!(world.resource::<ExtractedCount>().0, 0);
+        assert_eq!(world.query::<&MainEntity>().iter(world).count(), 0);
+
+        world.insert_resource(RenderState::Ready);
+        app.update();
+        let world = render_world(&mut app);
+        assert_eq!(world.resource::<ExtractedCount>().0, 1);
+        assert_eq!(world.query::<&MainEntity>().iter(world).count(), 1);
+        // Despawned at the end of the rendered frame.
+        assert_eq!(
+            world.query::<&TemporaryRenderEntity>().iter(world).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn extract_param_change_detection_is_per_frame() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(RenderComponent);
+        });
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        render_app.init_resource::<ExtractedCount>();
+        render_app.add_systems(
+            ExtractSchedule,
+            |query: Extract<Query<(), Changed<RenderComponent>>>,
+             mut count: ResMut<ExtractedCount>| {
+                count.0 = query.iter().count();
+            },
+        );
+        let changed = |app: &App| {
+            app.get_sub_app(RenderApp)
+                .unwrap()
+                .world()
+                .resource::<ExtractedCount>()
+                .0
+        };
+
+        app.update();
+        assert_eq!(changed(&app), 1);
+
+        app.update();
+        assert_eq!(changed(&app), 0);
+
+        app.world_mut()
+            .run_system_cached(|mut query: Query<&mut RenderComponent>| {
+                for mut component in &mut query {
+                    component.set_changed();
+                }
+            })
+            .unwrap();
+        app.update();
+        assert_eq!(changed(&app), 1);
+    }
+
+    #[derive(Component, Clone, Debug)]
+    struct ChangedComponent;
+
+    static CHANGED_EXTRACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    impl SyncComponent for ChangedComponent {
+        type Target = ChangedComponent;
+    }
+
+    impl ExtractComponent for ChangedComponent {
+        type QueryData = &'static Self;
+        type QueryFilter = ();
+        type Out = ChangedComponent;
+
+        fn extract_component(
+            item: bevy_ecs::query::QueryItem<'_, '_, Self::QueryData>,
+        ) -> Option<Self::Out> {
+            CHANGED_EXTRACTIONS.fetch_add(1, Ordering::Relaxed);
+            Some(item.clone())
+        }
+    }
+
+    #[test]
+    fn extract_changed_skips_unchanged_components() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(ExtractComponentPlugin::<ChangedComponent>::extract_changed());
+        let changed = app.world_mut().spawn(ChangedComponent).id();
+        app.world_mut().spawn(ChangedComponent);
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        let extracted = |app: &mut App| {
+            let world = app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+            let mut query = world.query::<&ChangedComponent>();
+            query.iter(world).count()
+        };
+
+        app.update();
+        assert_eq!(CHANGED_EXTRACTIONS.load(Ordering::Relaxed), 2);
+        assert_eq!(extracted(&mut app), 2);
+
+        // Nothing changed, the extracted components are kept as they are.
+        app.update();
+        assert_eq!(CHANGED_EXTRACTIONS.load(Ordering::Relaxed), 2);
+        assert_eq!(extracted(&mut app), 2);
+
+        app.world_mut()
+            .get_mut::<ChangedComponent>(changed)
+            .unwrap()
+            .set_changed();
+        app.update();
+        assert_eq!(CHANGED_EXTRACTIONS.load(Ordering::Relaxed), 3);
+
+        // Removals are still synced.
+        app.world_mut()
+            .entity_mut(changed)
+            .remove::<ChangedComponent>();
+        app.update();
+        assert_eq!(CHANGED_EXTRACTIONS.load(Ordering::Relaxed), 3);
+        assert_eq!(extracted(&mut app), 1);
+    }
+
+    #[derive(Component, Clone, Debug)]
+    struct ResyncedComponent;
+
+    static RESYNCED_EXTRACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    impl SyncComponent for ResyncedComponent {
+        type Target = ResyncedComponent;
+    }
+
+    impl ExtractComponent for ResyncedComponent {
+        type QueryData = &'static Self;
+        type QueryFilter = ();
+        type Out = ResyncedComponent;
+
+        fn extract_component(
+            item: bevy_ecs::query::QueryItem<'_, '_, Self::QueryData>,
+        ) -> Option<Self::Out> {
+            RESYNCED_EXTRACTIONS.fetch_add(1, Ordering::Relaxed);
+            Some(item.clone())
+        }
+    }
+
+    #[derive(Component)]
+    struct Rendered;
+
+    #[test]
+    fn extract_changed_extracts_entities_synced_after_their_change() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(SyncWithMarkerPlugin::<Rendered>::default());
+        app.add_plugins(ExtractComponentPlugin::<ResyncedComponent>::extract_changed());
+        let entity = app.world_mut().spawn(ResyncedComponent).id();
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        let extracted = |app: &mut App| {
+            let world = app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+            let mut query = world.query::<&ResyncedComponent>();
+            query.iter(world).count()
+        };
+
+        app.update();
+        assert_eq!(RESYNCED_EXTRACTIONS.load(Ordering::Relaxed), 0);
+
+        // The entity is synced once the component was extracted in a previous frame.
+        app.world_mut().entity_mut(entity).insert(Rendered);
+        app.update();
+        assert_eq!(RESYNCED_EXTRACTIONS.load(Ordering::Relaxed), 1);
+        assert_eq!(extracted(&mut app), 1);
+
+        // And again to a new render entity, the component still being unchanged.
+        app.world_mut().entity_mut(entity).remove::<Rendered>();
+        app.update();
+        assert_eq!(extracted(&mut app), 0);
+        app.world_mut().entity_mut(entity).insert(Rendered);
+        app.update();
+        assert_eq!(RESYNCED_EXTRACTIONS.load(Ordering::Relaxed), 2);
+        assert_eq!(extracted(&mut app), 1);
+    }
+
+    #[test]
+    fn extract_changed_keeps_render_copies_untouched() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(ExtractComponentPlugin::<RenderComponentSeparate>::extract_changed());
+        let changed = app.world_mut().spawn(RenderComponentSeparate).id();
+        app.world_mut().spawn(RenderComponentSeparate);
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        let change_ticks = |app: &mut App| {
+            let world = app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+            let mut query = world.query::<(Entity, Ref<RenderComponentSeparate>)>();
+            let mut ticks: Vec<(Entity, Tick)> = query
+                .iter(world)
+                .map(|(entity, component)| (entity, component.last_changed()))
+                .collect();
+            ticks.sort_by_key(|(entity, _)| *entity);
+            ticks
+        };
+        let rewritten = |before: &[(Entity, Tick)], after: &[(Entity, Tick)]| {
+            before.iter().zip(after).filter(|(a, b)| a != b).count()
+        };
+
+        // The initial extraction of newly synced entities isn't skipped.
+        app.update();
+        let initial = change_ticks(&mut app);
+        assert_eq!(initial.len(), 2);
+
+        app.update();
+        let unchanged = change_ticks(&mut app);
+        assert_eq!(rewritten(&initial, &unchanged), 0);
+
+        app.world_mut()
+            .get_mut::<RenderComponentSeparate>(changed)
+            .unwrap()
+            .set_changed();
+        app.update();
+        assert_eq!(rewritten(&unchanged, &change_ticks(&mut app)), 1);
+    }
+
+    #[test]
+    fn pre_extract_hooks_run_in_order() {
+        #[derive(Resource, Default)]
+        struct HookRuns(Vec<(&'static str, usize)>);
+
+        fn record(render_world: &mut World, hook: &'static str) {
+            let synced = render_world.resource::<EntitySyncMap>().len();
+            render_world
+                .get_resource_or_init::<HookRuns>()
+                .0
+                .push((hook, synced));
+        }
+        fn first(_: &mut World, render_world: &mut World) {
+            record(render_world, "first");
+        }
+        fn second(_: &mut World, render_world: &mut World) {
+            record(render_world, "second");
+        }
+
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin {
+            pre_extract: vec![first],
+        });
+        app.add_pre_extract_hook(second);
+        app.world_mut().spawn(SyncToRenderWorld);
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+
+        app.update();
+
+        // Both hooks ran before the new entity was synced.
+        let render_app = app.get_sub_app(RenderApp).unwrap();
+        assert_eq!(
+            render_app.world().resource::<HookRuns>().0,
+            [("first", 0), ("second", 0)]
+        );
+        assert_eq!(render_app.world().resource::<EntitySyncMap>().len(), 1);
+    }
+
+    #[test]
+    fn extract_timings_are_recorded() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.world_mut().spawn(SyncToRenderWorld);
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+
+        app.update();
+        app.update();
+
+        let render_app = app.get_sub_app(RenderApp).unwrap();
+        let timings = render_app.world().resource::<ExtractTimings>();
+        // Clocks may be too coarse to measure the extraction, e.g. on the web.
+        for timing in [&timings.entity_sync, &timings.schedule] {
+            assert_eq!(timing.frames(), 2);
+            assert!(timing.worst() >= timing.latest());
+        }
+    }
+
+    #[test]
+    fn extract_timings_feed_the_schedule_measurements() {
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        render_app.init_resource::<RenderScheduleMeasurements>();
+
+        app.update();
+
+        let render_world = app.get_sub_app(RenderApp).unwrap().world();
+        let timings = render_world.resource::<ExtractTimings>();
+        assert_eq!(
+            render_world
+                .resource::<RenderScheduleMeasurements>()
+                .time(RenderPhase::Extract),
+            Some(timings.entity_sync.latest() + timings.schedule.latest())
+        );
+    }
+
+    #[test]
+    fn extraction_follows_run_condition() {
+        #[derive(Resource)]
+        struct Enabled(bool);
+
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(
+            ExtractComponentPlugin::<RenderComponentSeparate>::default()
+                .run_if(|main_world| main_world.resource::<Enabled>().0),
+        );
+        app.insert_resource(Enabled(false));
+        let entity = app.world_mut().spawn(RenderComponentSeparate).id();
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+        let extracted = |app: &mut App| {
+            let world = app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+            let mut query = world.query::<&RenderComponentSeparate>();
+            query.iter(world).count()
+        };
+
+        app.update();
+        assert_eq!(extracted(&mut app), 0);
+
+        app.insert_resource(Enabled(true));
+        app.update();
+        assert_eq!(extracted(&mut app), 1);
+
+        // Disabling extraction keeps the extracted components, but removals are still synced.
+        app.insert_resource(Enabled(false));
+        app.update();
+        assert_eq!(extracted(&mut app), 1);
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<RenderComponentSeparate>();
+        app.update();
+        assert_eq!(extracted(&mut app), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ExtractSchedule")]
+    fn extract_param_outside_extract_schedule() {
+        use bevy_ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let _ = world.run_system_once(|_query: Extract<Query<&RenderComponent>>| {});
+    }
+
+    #[test]
+    fn extract_throughput_diagnostics() {
+        use crate::diagnostic::ExtractThroughputDiagnosticPlugin;
+        use bevy_diagnostic::DiagnosticsStore;
+
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(ExtractThroughputDiagnosticPlugin);
+        app.add_plugins(ExtractComponentPlugin::<RenderComponent>::default());
+        app.add_plugins(ExtractComponentPlugin::<RenderComponentSeparate>::default());
+        for _ in 0..3 {
+            app.world_mut()
+                .spawn((RenderComponent, RenderComponentSeparate));
+        }
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+
+        // Measurements of an extraction are published at the start of the next frame.
+        app.update();
+        app.update();
+
+        let measurement = |app: &App, path| {
+            app.world()
+                .resource::<DiagnosticsStore>()
+                .get(path)
+                .and_then(|diagnostic| diagnostic.measurement())
+                .map(|measurement| measurement.value)
+        };
+        assert_eq!(
+            measurement(
+                &app,
+                ExtractThroughputDiagnosticPlugin::entities_synced_diagnostic_path()
+            ),
+            Some(3.0)
+        );
+        assert_eq!(
+            measurement(
+                &app,
+                ExtractThroughputDiagnosticPlugin::components_extracted_diagnostic_path()
+            ),
+            Some(6.0)
+        );
+    }
+
+    #[test]
+    fn render_schedule_diagnostics() {
+        use crate::diagnostic::RenderScheduleDiagnosticPlugin;
+        use bevy_diagnostic::DiagnosticsStore;
+
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(RenderScheduleDiagnosticPlugin);
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+
+        app.update();
+        app.update();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        for path in [
+            RenderScheduleDiagnosticPlugin::extract_time_diagnostic_path(),
+            RenderScheduleDiagnosticPlugin::queue_time_diagnostic_path(),
+            RenderScheduleDiagnosticPlugin::prepare_time_diagnostic_path(),
+            RenderScheduleDiagnosticPlugin::render_time_diagnostic_path(),
+        ] {
+            let diagnostic = store.get(path).unwrap();
+            assert!(diagnostic.measurement().is_some(), "{path} wasn't measured");
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn extract_change_summary() {
+        use crate::sync_world::ExtractChangeSummary;
+
+        let mut app = App::new();
+
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(ExtractComponentPlugin::<RenderComponent>::default());
+        app.add_plugins(ExtractComponentPlugin::<RenderComponentSeparate>::default());
+        let despawned = app
+            .world_mut()
+            .spawn((RenderComponent, RenderComponentSeparate))
+            .id();
+        let kept = app
+            .world_mut()
+            .spawn((RenderComponent, RenderComponentSeparate))
+            .id();
+
+        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        render_app.update_schedule = Some(Render.intern());
+
+        let summary = |app: &App| {
+            let summary = app
+                .get_sub_app(RenderApp)
+                .unwrap()
+                .world()
+                .resource::<ExtractChangeSummary>();
+            (
+                summary.entities_spawned(),
+                summary.entities_despawned(),
+                summary.components_inserted(),
+                summary.components_removed(),
+            )
+        };
+
+        app.update();
+        // Two entities, each with two extracted components.
+        assert_eq!(summary(&app), (2, 0, 4, 0));
+
+        app.world_mut().despawn(despawned);
+        app.world_mut().entity_mut(kept).remove::<RenderComponent>();
+        app.update();
+        // Only `RenderComponentSeparate` of the kept entity is extracted again.
+        assert_eq!(summary(&app), (0, 1, 1, 1));
+    }
 }
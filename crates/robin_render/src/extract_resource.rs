@@ -5,7 +5,7 @@ use bevy_ecs::prelude::*;
 pub use bevy_render_macros::ExtractResource;
 use bevy_utils::once;
 
-use crate::{Extract, ExtractSchedule, RenderApp};
+use crate::{Extract, ExtractSchedule, MainWorld, RenderApp};
 
 /// Describes how a resource gets extracted for rendering.
 ///
@@ -18,10 +18,26 @@ use crate::{Extract, ExtractSchedule, RenderApp};
 pub trait ExtractResource<F = ()>: Resource {
     type Source: Resource;
 
+    /// Whether the resource is removed from the "render world" when its source is removed from
+    /// the "main world". By default the last copy is kept.
+    const REMOVE_WITH_SOURCE: bool = false;
+
     /// Defines how the resource is transferred into the "render world".
     fn extract_resource(source: &Self::Source) -> Self;
 }
 
+/// The marker to extract any [`Clone`] resource as is, without implementing
+/// [`ExtractResource`], e.g. `ExtractResourcePlugin::<MyResource, CloneResource>::default()`.
+pub struct CloneResource;
+
+impl<R: Resource + Clone> ExtractResource<CloneResource> for R {
+    type Source = R;
+
+    fn extract_resource(source: &Self::Source) -> Self {
+        source.clone()
+    }
+}
+
 /// This plugin extracts the resources into the "render world".
 ///
 /// Therefore it sets up the[`ExtractSchedule`] step
@@ -30,18 +46,40 @@ pub trait ExtractResource<F = ()>: Resource {
 /// The marker type `F` is only used as a way to bypass the orphan rules. To
 /// implement the trait for a foreign type you can use a local type as the
 /// marker, e.g. the type of the plugin that calls [`ExtractResourcePlugin`].
-pub struct ExtractResourcePlugin<R: ExtractResource<F>, F = ()>(PhantomData<(R, F)>);
+pub struct ExtractResourcePlugin<R: ExtractResource<F>, F = ()> {
+    run_condition: Option<fn(&World) -> bool>,
+    marker: PhantomData<(R, F)>,
+}
 
 impl<R: ExtractResource<F>, F> Default for ExtractResourcePlugin<R, F> {
     fn default() -> Self {
-        Self(PhantomData)
+        Self {
+            run_condition: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R: ExtractResource<F>, F> ExtractResourcePlugin<R, F> {
+    /// Only extracts the resource in frames where `condition` returns `true` for the main world.
+    ///
+    /// While the condition is `false`, the render world keeps its last copy of the resource, even
+    /// if the source is changed or removed. The copy is updated or removed the next time the
+    /// condition is `true`.
+    pub fn run_if(mut self, condition: fn(&World) -> bool) -> Self {
+        self.run_condition = Some(condition);
+        self
     }
 }
 
 impl<R: ExtractResource<F>, F: 'static + Send + Sync> Plugin for ExtractResourcePlugin<R, F> {
     fn build(&self, app: &mut App) {
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
-            render_app.add_systems(ExtractSchedule, extract_resource::<R, F>);
+            let mut system = extract_resource::<R, F>.into_configs();
+            if let Some(condition) = self.run_condition {
+                system = system.run_if(move |main_world: Res<MainWorld>| condition(&main_world));
+            }
+            render_app.add_systems(ExtractSchedule, system);
         } else {
             once!(bevy_log::error!(
                 "Render app did not exist when trying to add `extract_resource` for <{}>.",
@@ -52,6 +90,9 @@ impl<R: ExtractResource<F>, F: 'static + Send + Sync> Plugin for ExtractResource
 }
 
 /// This system extracts the resource of the corresponding [`Resource`] type
+///
+/// The render world copy is updated whenever the source changes. It is only removed along with
+/// the source if [`ExtractResource::REMOVE_WITH_SOURCE`] is `true`.
 pub fn extract_resource<R: ExtractResource<F>, F>(
     mut commands: Commands,
     main_resource: Extract<Option<Res<R::Source>>>,
@@ -74,5 +115,116 @@ pub fn extract_resource<R: ExtractResource<F>, F>(
 
             commands.insert_resource(R::extract_resource(main_resource));
         }
+    } else if R::REMOVE_WITH_SOURCE && target_resource.is_some() {
+        commands.remove_resource::<R>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::App;
+    use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+
+    use super::{CloneResource, ExtractResource, ExtractResourcePlugin};
+    use crate::{Render, RenderApp, extract_plugin::ExtractPlugin};
+
+    #[derive(Resource, Clone, Debug, PartialEq)]
+    struct Config(u32);
+
+    impl ExtractResource for Config {
+        type Source = Config;
+
+        const REMOVE_WITH_SOURCE: bool = true;
+
+        fn extract_resource(source: &Self::Source) -> Self {
+            source.clone()
+        }
+    }
+
+    #[derive(Resource, Clone, Debug, PartialEq)]
+    struct Cloned(u32);
+
+    fn render_resource<R: Resource>(app: &App) -> Option<&R> {
+        app.get_sub_app(RenderApp)
+            .unwrap()
+            .world()
+            .get_resource::<R>()
+    }
+
+    fn render_config(app: &App) -> Option<&Config> {
+        render_resource(app)
+    }
+
+    #[test]
+    fn resource_is_extracted_and_updated() {
+        let mut app = App::new();
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(ExtractResourcePlugin::<Config>::default());
+        app.get_sub_app_mut(RenderApp).unwrap().update_schedule = Some(Render.intern());
+
+        app.update();
+        assert_eq!(render_config(&app), None);
+
+        app.insert_resource(Config(1));
+        app.update();
+        assert_eq!(render_config(&app), Some(&Config(1)));
+
+        app.world_mut().resource_mut::<Config>().0 = 2;
+        app.update();
+        assert_eq!(render_config(&app), Some(&Config(2)));
+
+        app.world_mut().remove_resource::<Config>();
+        app.update();
+        assert_eq!(render_config(&app), None);
+    }
+
+    #[test]
+    fn extraction_follows_run_condition() {
+        #[derive(Resource)]
+        struct Enabled(bool);
+
+        let mut app = App::new();
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(
+            ExtractResourcePlugin::<Config>::default()
+                .run_if(|main_world| main_world.resource::<Enabled>().0),
+        );
+        app.get_sub_app_mut(RenderApp).unwrap().update_schedule = Some(Render.intern());
+
+        app.insert_resource(Enabled(false));
+        app.insert_resource(Config(1));
+        app.update();
+        assert_eq!(render_config(&app), None);
+
+        app.insert_resource(Enabled(true));
+        app.update();
+        assert_eq!(render_config(&app), Some(&Config(1)));
+
+        // The last copy is kept while disabled, and updated once enabled again.
+        app.insert_resource(Enabled(false));
+        app.world_mut().resource_mut::<Config>().0 = 2;
+        app.update();
+        assert_eq!(render_config(&app), Some(&Config(1)));
+
+        app.insert_resource(Enabled(true));
+        app.update();
+        assert_eq!(render_config(&app), Some(&Config(2)));
+    }
+
+    #[test]
+    fn clone_resources_are_extracted() {
+        let mut app = App::new();
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(ExtractResourcePlugin::<Cloned, CloneResource>::default());
+        app.get_sub_app_mut(RenderApp).unwrap().update_schedule = Some(Render.intern());
+
+        app.insert_resource(Cloned(3));
+        app.update();
+        assert_eq!(render_resource::<Cloned>(&app), Some(&Cloned(3)));
+
+        // Only removed along with the source when opted in.
+        app.world_mut().remove_resource::<Cloned>();
+        app.update();
+        assert_eq!(render_resource::<Cloned>(&app), Some(&Cloned(3)));
     }
 }
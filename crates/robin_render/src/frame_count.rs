@@ -0,0 +1,157 @@
+use alloc::sync::Arc;
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::{prelude::*, resource::Resource};
+use bevy_platform::time::Instant;
+use core::time::Duration;
+use std::sync::Mutex;
+
+use crate::{RenderApp, error_handler::RenderState};
+
+/// The canonical frame counter of the renderer.
+///
+/// Both counters are advanced once per update of the render app, right after the
+/// [`Render`](crate::Render) schedule finished (i.e. after [`RenderSystems::Cleanup`](crate::RenderSystems::Cleanup)),
+/// or would have run if the renderer wasn't ready. They never go backwards, including across
+/// device recovery, so they can be used to tag work with the frame it belongs to.
+///
+/// Systems in the [`Render`](crate::Render) schedule see the number of frames rendered before the
+/// current one. The resource is mirrored to the main world at the start of the next frame.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderFrameCount {
+    /// The number of frames the [`Render`](crate::Render) schedule ran for.
+    pub rendered_frames: u64,
+    /// The number of updates of the render app, including the ones skipped because the renderer
+    /// was errored, stopped, reinitializing or paused.
+    pub elapsed_updates: u64,
+}
+
+impl RenderFrameCount {
+    /// The number of updates skipped without rendering.
+    pub fn skipped_updates(&self) -> u64 {
+        self.elapsed_updates - self.rendered_frames
+    }
+}
+
+/// The time between the updates of the render app, in the render world.
+///
+/// This is advanced at the very start of extraction, before the renderer state is updated, so it
+/// is up to date in [`ExtractSchedule`](crate::ExtractSchedule), in
+/// [`RenderStartup`](crate::RenderStartup) and in the [`Render`](crate::Render) schedule. Like
+/// [`RenderFrameCount`], it keeps counting across device recovery.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct RenderFrameTime {
+    /// The time since the previous update, zero for the first one.
+    pub delta: Duration,
+    /// The time since the first update.
+    pub elapsed: Duration,
+    last_update: Option<Instant>,
+}
+
+impl RenderFrameTime {
+    /// Advances the time to an update at `now`.
+    pub fn advance(&mut self, now: Instant) {
+        self.delta = self.last_update.map_or(Duration::ZERO, |last_update| {
+            now.saturating_duration_since(last_update)
+        });
+        self.elapsed += self.delta;
+        self.last_update = Some(now);
+    }
+}
+
+/// Shares the latest [`RenderFrameCount`] of the render world with the main world.
+#[derive(Resource, Clone, Default)]
+struct RenderFrameCountMutex(Arc<Mutex<Option<RenderFrameCount>>>);
+
+/// Maintains the [`RenderFrameCount`] of both worlds.
+pub struct RenderFrameCountPlugin;
+
+impl Plugin for RenderFrameCountPlugin {
+    fn build(&self, app: &mut App) {
+        let mutex = RenderFrameCountMutex::default();
+        app.init_resource::<RenderFrameCount>()
+            .insert_resource(mutex.clone())
+            .add_systems(PreUpdate, sync_render_frame_count);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<RenderFrameCount>()
+                .insert_resource(mutex);
+        }
+    }
+}
+
+/// Advances the [`RenderFrameCount`], run after the [`Render`](crate::Render) schedule.
+pub(crate) fn count_render_frame(
+    state: Res<RenderState>,
+    mut frame_count: ResMut<RenderFrameCount>,
+    mutex: Option<Res<RenderFrameCountMutex>>,
+) {
+    frame_count.elapsed_updates += 1;
+    if matches!(*state, RenderState::Ready) {
+        frame_count.rendered_frames += 1;
+    }
+    if let Some(mutex) = mutex {
+        *mutex.0.lock().unwrap() = Some(*frame_count);
+    }
+}
+
+fn sync_render_frame_count(
+    mutex: Res<RenderFrameCountMutex>,
+    mut frame_count: ResMut<RenderFrameCount>,
+) {
+    if let Some(received) = mutex.0.lock().ok().and_then(|mut v| v.take()) {
+        *frame_count = received;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::error_handler::{ErrorType, RenderError};
+
+    #[test]
+    fn frame_time_advances() {
+        let mut time = RenderFrameTime::default();
+        let start = Instant::now();
+
+        time.advance(start);
+        assert_eq!(time.delta, Duration::ZERO);
+        time.advance(start + Duration::from_millis(16));
+        time.advance(start + Duration::from_millis(40));
+        assert_eq!(time.delta, Duration::from_millis(24));
+        assert_eq!(time.elapsed, Duration::from_millis(40));
+    }
+
+    #[test]
+    fn frame_count_across_recovery() {
+        let mut world = World::new();
+        world.init_resource::<RenderFrameCount>();
+
+        let advance = |world: &mut World, state: RenderState| {
+            world.insert_resource(state);
+            world.run_system_once(count_render_frame).unwrap();
+            *world.resource::<RenderFrameCount>()
+        };
+
+        advance(&mut world, RenderState::Ready);
+        advance(&mut world, RenderState::Ready);
+        // A device loss stops rendering for a frame, then the renderer recovers.
+        let errored = RenderState::Errored(RenderError {
+            ty: ErrorType::DeviceLost,
+            description: String::new(),
+            source: None,
+        });
+        advance(&mut world, errored);
+        advance(
+            &mut world,
+            RenderState::Reinitializing(ErrorType::DeviceLost),
+        );
+        let count = advance(&mut world, RenderState::Ready);
+
+        assert_eq!(count.rendered_frames, 3);
+        assert_eq!(count.elapsed_updates, 5);
+        assert_eq!(count.skipped_updates(), 2);
+    }
+}
@@ -1,6 +1,4 @@
-use crate::render_resource::{
-    CachedPipelineState, ComputePipeline, Pipeline, PipelineCache, RenderPipeline,
-};
+use crate::render_resource::{ComputePipeline, Pipeline, PipelineCache, RenderPipeline};
 
 pub trait GetPipelineContainer {
     fn get_pipeline_container(&self) -> PipelineContainer;
@@ -32,16 +30,14 @@ impl GetPipelineContainer for PipelineCache {
     fn get_pipeline_container(&self) -> PipelineContainer {
         let mut container = PipelineContainer::default();
         for pipeline in self.pipelines() {
-            match &pipeline.state {
-                CachedPipelineState::Ok(pipeline) => match pipeline {
-                    Pipeline::ComputePipeline(pipeline) => {
-                        container.push(Some(Pipeline::ComputePipeline(pipeline.clone())));
-                    }
-                    Pipeline::RenderPipeline(pipeline) => {
-                        container.push(Some(Pipeline::RenderPipeline(pipeline.clone())));
-                    }
-                },
-                _ => {
+            match pipeline.pipeline() {
+                Some(Pipeline::ComputePipeline(pipeline)) => {
+                    container.push(Some(Pipeline::ComputePipeline(pipeline.clone())));
+                }
+                Some(Pipeline::RenderPipeline(pipeline)) => {
+                    container.push(Some(Pipeline::RenderPipeline(pipeline.clone())));
+                }
+                None => {
                     container.push(None);
                 }
             }
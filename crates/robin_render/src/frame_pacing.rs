@@ -0,0 +1,216 @@
+use bevy_app::{App, Plugin};
+use bevy_ecs::{prelude::*, resource::Resource};
+use bevy_platform::time::Instant;
+use core::time::Duration;
+
+use crate::{
+    RenderApp, RenderRecovery,
+    extract_resource::{ExtractResource, ExtractResourcePlugin},
+    frame_count::count_render_frame,
+    poll_paused_device, run_render_schedule,
+    view::ExtractedWindows,
+};
+
+/// Caps the frame rate of the renderer, e.g. to keep the GPU from rendering thousands of frames
+/// per second on a menu screen.
+///
+/// Insert it in the main world. At the end of every frame, the render world waits until the
+/// target frame time passed since the end of the previous frame. Frames that already took longer
+/// aren't delayed. Frames are also paced while rendering is paused or recovering from an error,
+/// so the app doesn't spin while the renderer is idle. Frame pacing is skipped on the web, where
+/// the browser paces frames.
+///
+/// By default, the frame rate is capped to the refresh rate of the monitor the primary window is
+/// shown on, see [`WindowDisplayInfo`](crate::view::window::display::WindowDisplayInfo). It
+/// follows the window when it is moved to another monitor.
+#[derive(Resource, ExtractResource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FramePacing {
+    /// The frame rate to hold.
+    ///
+    /// `None` holds the refresh rate of the monitor of the primary window, or renders as fast as
+    /// possible if it is unknown, e.g. without windows. Rates that aren't positive render as fast
+    /// as possible.
+    pub target_fps: Option<f64>,
+}
+
+impl FramePacing {
+    /// Caps the frame rate to `target_fps` frames per second.
+    pub fn capped(target_fps: f64) -> Self {
+        Self {
+            target_fps: Some(target_fps),
+        }
+    }
+
+    /// Renders as fast as possible, regardless of the refresh rate of the monitor.
+    pub fn uncapped() -> Self {
+        Self::capped(0.0)
+    }
+
+    /// The time a frame should at least take, if the frame rate is capped.
+    ///
+    /// `refresh_interval` is the time between two refreshes of the monitor of the primary
+    /// window, used when [`FramePacing::target_fps`] is `None`.
+    pub fn target_frame_time(&self, refresh_interval: Option<Duration>) -> Option<Duration> {
+        match self.target_fps {
+            None => refresh_interval,
+            Some(fps) => Some(fps)
+                .filter(|fps| *fps > 0.0)
+                .and_then(|fps| Duration::try_from_secs_f64(fps.recip()).ok()),
+        }
+    }
+}
+
+/// Holds the frame rate of [`FramePacing`].
+pub struct FramePacingPlugin;
+
+impl Plugin for FramePacingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FramePacing>()
+            .add_plugins(ExtractResourcePlugin::<FramePacing>::default());
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            // Outside of the `Render` schedule, which is skipped while the renderer isn't ready.
+            render_app.add_systems(
+                RenderRecovery,
+                pace_frame
+                    .after(run_render_schedule)
+                    .after(poll_paused_device)
+                    .before(count_render_frame),
+            );
+        }
+    }
+}
+
+/// Below this, [`pace_frame`] spins instead of sleeping, as sleeps are too coarse on most
+/// platforms.
+const SPIN_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// The time left to wait at `now` for a frame that started at `frame_start` to take
+/// `target_frame_time`.
+fn remaining_frame_time(
+    frame_start: Instant,
+    now: Instant,
+    target_frame_time: Duration,
+) -> Duration {
+    target_frame_time.saturating_sub(now.saturating_duration_since(frame_start))
+}
+
+/// Waits for the target frame time of [`FramePacing`] to pass since the end of the previous frame.
+fn pace_frame(
+    pacing: Option<Res<FramePacing>>,
+    windows: Option<Res<ExtractedWindows>>,
+    mut previous_frame_end: Local<Option<Instant>>,
+) {
+    // Read every frame, so a window moved to another monitor is paced to its new refresh rate.
+    let refresh_interval = windows.and_then(|windows| {
+        let primary = windows.get(&windows.primary?)?;
+        primary.display_info.refresh_interval()
+    });
+    let target_frame_time = pacing.and_then(|pacing| pacing.target_frame_time(refresh_interval));
+    // Blocking the browser's event loop would only delay the next animation frame.
+    if cfg!(target_arch = "wasm32") {
+        return;
+    }
+    if let (Some(target_frame_time), Some(frame_start)) = (target_frame_time, *previous_frame_end) {
+        let remaining = remaining_frame_time(frame_start, Instant::now(), target_frame_time);
+        if remaining > SPIN_THRESHOLD {
+            std::thread::sleep(remaining - SPIN_THRESHOLD);
+        }
+        while remaining_frame_time(frame_start, Instant::now(), target_frame_time) > Duration::ZERO
+        {
+            core::hint::spin_loop();
+        }
+    }
+    *previous_frame_end = target_frame_time.map(|_| Instant::now());
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::App;
+    use bevy_ecs::schedule::ScheduleLabel;
+
+    use super::*;
+    use crate::{extract_plugin::ExtractPlugin, view::window::display::WindowDisplayInfo};
+
+    #[test]
+    fn frames_are_paced_to_the_target() {
+        let mut world = World::new();
+        world.insert_resource(FramePacing::capped(50.0));
+        let pace_frame = world.register_system(pace_frame);
+
+        let mut frame_ends = Vec::new();
+        for _ in 0..4 {
+            world.run_system(pace_frame).unwrap();
+            frame_ends.push(Instant::now());
+        }
+
+        // The timestamps are taken slightly after the frames ended.
+        let target_frame_time = Duration::from_millis(20) - Duration::from_micros(500);
+        for frames in frame_ends.windows(2) {
+            assert!(frames[1] - frames[0] >= target_frame_time);
+        }
+    }
+
+    #[test]
+    fn paused_frames_are_paced() {
+        let mut app = App::new();
+        app.add_plugins((ExtractPlugin::default(), FramePacingPlugin));
+        // The `Render` schedule never runs, like while rendering is paused.
+        app.sub_app_mut(RenderApp).update_schedule = Some(RenderRecovery.intern());
+        app.insert_resource(FramePacing::capped(50.0));
+
+        let mut frame_ends = Vec::new();
+        for _ in 0..4 {
+            app.update();
+            frame_ends.push(Instant::now());
+        }
+
+        let target_frame_time = Duration::from_millis(20) - Duration::from_micros(500);
+        for frames in frame_ends.windows(2) {
+            assert!(frames[1] - frames[0] >= target_frame_time);
+        }
+    }
+
+    #[test]
+    fn late_frames_are_not_delayed() {
+        let start = Instant::now();
+        let target_frame_time = Duration::from_millis(16);
+
+        assert_eq!(
+            remaining_frame_time(start, start + Duration::from_millis(10), target_frame_time),
+            Duration::from_millis(6)
+        );
+        assert_eq!(
+            remaining_frame_time(start, start + Duration::from_millis(40), target_frame_time),
+            Duration::ZERO
+        );
+        assert_eq!(FramePacing::default().target_frame_time(None), None);
+        assert_eq!(FramePacing::uncapped().target_frame_time(None), None);
+    }
+
+    #[test]
+    fn uncapped_frames_follow_the_monitor() {
+        let display = |refresh_rate_millihertz| WindowDisplayInfo {
+            monitor: None,
+            refresh_rate_millihertz: Some(refresh_rate_millihertz),
+        };
+        let at_60hz = display(60_000).refresh_interval();
+        let at_144hz = display(144_000).refresh_interval();
+
+        assert_eq!(
+            FramePacing::default().target_frame_time(at_60hz),
+            Some(Duration::from_secs_f64(1.0 / 60.0))
+        );
+        // The window was dragged to a faster monitor.
+        assert_eq!(
+            FramePacing::default().target_frame_time(at_144hz),
+            Some(Duration::from_secs_f64(1.0 / 144.0))
+        );
+        // An explicit rate wins over the monitor.
+        assert_eq!(
+            FramePacing::capped(50.0).target_frame_time(at_144hz),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(FramePacing::uncapped().target_frame_time(at_144hz), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! A vertex shader drawing a single triangle that covers the whole render target, for
+//! post-processing and other fullscreen passes.
+//!
+//! Draw it with `pass.draw(0..3, 0..1)` and no vertex buffer. The fragment shader can import
+//! `bevy_render::fullscreen_vertex_shader::FullscreenVertexOutput` to get the UV of the pixel.
+
+use bevy_app::{App, Plugin};
+use bevy_asset::{Handle, embedded_asset, load_embedded_asset};
+use bevy_ecs::{prelude::*, world::FromWorld};
+use bevy_material::descriptor::VertexState;
+use bevy_shader::Shader;
+
+use crate::RenderApp;
+
+/// The WGSL source of the fullscreen vertex shader.
+pub const FULLSCREEN_SHADER_SOURCE: &str = include_str!("fullscreen_vertex_shader.wgsl");
+
+/// Loads the [`FullscreenShader`].
+pub struct FullscreenShaderPlugin;
+
+impl Plugin for FullscreenShaderPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "fullscreen_vertex_shader.wgsl");
+    }
+
+    fn finish(&self, app: &mut App) {
+        // The asset server is only available in the render world once the render plugin is built.
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<FullscreenShader>();
+        }
+    }
+}
+
+/// A shader that renders to the whole screen, available in the render world. Useful for
+/// post-processing.
+#[derive(Resource, Clone)]
+pub struct FullscreenShader(pub Handle<Shader>);
+
+impl FromWorld for FullscreenShader {
+    fn from_world(world: &mut World) -> Self {
+        Self(load_embedded_asset!(world, "fullscreen_vertex_shader.wgsl"))
+    }
+}
+
+impl FullscreenShader {
+    /// Gets the raw shader handle.
+    pub fn shader(&self) -> Handle<Shader> {
+        self.0.clone()
+    }
+
+    /// Returns the [`VertexState`] of the fullscreen vertex shader.
+    pub fn to_vertex_state(&self) -> VertexState {
+        fullscreen_shader_vertex_state(self.0.clone())
+    }
+}
+
+/// Returns the [`VertexState`] of the fullscreen vertex shader, given its handle.
+///
+/// The state has no vertex buffers, the triangle is generated from the vertex index.
+pub fn fullscreen_shader_vertex_state(shader: Handle<Shader>) -> VertexState {
+    VertexState {
+        shader,
+        entry_point: Some("fullscreen_vertex_shader".into()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::uuid_handle;
+
+    use super::*;
+    use crate::{
+        render_resource::*,
+        renderer::{gpu_render_resources, noop_render_resources},
+        settings::RenderResources,
+    };
+
+    const FULLSCREEN_SHADER: Handle<Shader> = uuid_handle!("3c1e0f5a-8b2d-4e7c-9f61-2a4d8b0c7e13");
+    const SOLID_SHADER: Handle<Shader> = uuid_handle!("a9d2c4e6-1f3b-4a58-b7c0-6e8f2d1a5b94");
+    const SIZE: u32 = 64;
+
+    /// Queues a pipeline filling the target with magenta.
+    fn solid_pipeline(resources: &RenderResources) -> (PipelineCache, CachedRenderPipelineId) {
+        let mut cache = PipelineCache::new(resources.0.clone(), resources.3.clone(), true);
+        cache.set_shader(
+            FULLSCREEN_SHADER.id(),
+            Shader::from_wgsl(FULLSCREEN_SHADER_SOURCE, "fullscreen_vertex_shader.wgsl"),
+        );
+        cache.set_shader(
+            SOLID_SHADER.id(),
+            Shader::from_wgsl(
+                "#import bevy_render::fullscreen_vertex_shader::FullscreenVertexOutput\n\
+                @fragment\n\
+                fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {\n    \
+                    return vec4(1.0, 0.0, 1.0, 1.0);\n\
+                }\n",
+                "solid.wgsl",
+            ),
+        );
+        let id = cache.queue_render_pipeline(RenderPipelineDescriptor {
+            label: Some("solid".into()),
+            vertex: fullscreen_shader_vertex_state(FULLSCREEN_SHADER),
+            fragment: Some(FragmentState {
+                shader: SOLID_SHADER,
+                targets: vec![Some(ColorTargetState {
+                    format: TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            cache.process_queue();
+        }
+        (cache, id)
+    }
+
+    #[test]
+    fn fullscreen_pipeline_compiles() {
+        let resources = noop_render_resources(wgpu::Features::empty());
+        let (cache, id) = solid_pipeline(&resources);
+
+        assert!(matches!(
+            cache.get_render_pipeline_state(id),
+            CachedPipelineState::Ok(_)
+        ));
+    }
+
+    #[test]
+    fn fullscreen_triangle_fills_the_target() {
+        // The noop backend doesn't rasterize, this needs an actual adapter.
+        let Some(resources) = gpu_render_resources() else {
+            return;
+        };
+        let (cache, id) = solid_pipeline(&resources);
+        let pipeline = cache
+            .get_render_pipeline(id)
+            .expect("the fullscreen pipeline should compile");
+        let device = &resources.0;
+        let extent = Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("fullscreen_target"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        // 64 pixels of 4 bytes are already aligned to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+        let bytes_per_row = SIZE * 4;
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("fullscreen_readback"),
+            size: u64::from(bytes_per_row * SIZE),
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("fullscreen_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(wgpu::Color::BLACK),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.draw(0..3, 0..1);
+        }
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        resources.1.submit([encoder.finish()]);
+        buffer.slice(..).map_async(MapMode::Read, |result| {
+            result.expect("the readback buffer should map");
+        });
+        device.poll(PollType::wait_indefinitely()).unwrap();
+
+        let data = buffer.slice(..).get_mapped_range();
+        assert!(
+            data.chunks_exact(4)
+                .all(|pixel| pixel == [255, 0, 255, 255]),
+            "every pixel should be covered by the fullscreen triangle"
+        );
+    }
+}
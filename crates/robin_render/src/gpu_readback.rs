@@ -1,36 +1,47 @@
 use crate::{
-    ExtractSchedule, MainWorld, Render, RenderApp, RenderSystems,
+    ExtractSchedule, MainWorld, Render, RenderApp, RenderStartup, RenderSystems,
     extract_component::ExtractComponentPlugin,
     render_asset::RenderAssets,
     render_resource::{
-        Buffer, BufferUsages, CommandEncoder, Extent3d, TexelCopyBufferLayout, Texture,
+        BindGroup, BindGroupEntries, BindGroupLayoutDescriptor, BindGroupLayoutEntries, Buffer,
+        BufferUsages, CachedComputePipelineId, CommandEncoder, ComputePassDescriptor,
+        ComputePipelineDescriptor, Extent3d, PipelineCache, TexelCopyBufferLayout, Texture,
         TextureFormat,
+        binding_types::{storage_buffer_sized, texture_depth_2d, uniform_buffer},
     },
     renderer::RenderDevice,
     storage::{GpuShaderBuffer, ShaderBuffer},
     sync_world::MainEntity,
     texture::GpuImage,
+    view::ViewDepthTexture,
 };
+use alloc::sync::Arc;
 use async_channel::{Receiver, Sender};
 use bevy_app::{App, Plugin};
-use bevy_asset::Handle;
+use bevy_asset::{AssetServer, Handle, embedded_asset, load_embedded_asset};
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
     change_detection::ResMut,
     entity::Entity,
     event::EntityEvent,
     prelude::{Component, Resource, World},
-    system::{Query, Res},
+    system::{Commands, Query, Res},
 };
 use bevy_ecs::{schedule::IntoScheduleConfigs, template::FromTemplate};
 use bevy_image::{Image, TextureFormatPixelInfo};
 use bevy_log::warn;
+use bevy_math::{UVec2, UVec4};
 use bevy_platform::collections::HashMap;
 use bevy_reflect::Reflect;
 use bevy_render_macros::ExtractComponent;
+use bevy_shader::Shader;
+use bevy_utils::default;
 use encase::ShaderType;
 use encase::internal::ReadFrom;
 use encase::private::Reader;
+use std::sync::Mutex;
+use thiserror::Error;
+use wgpu::{Origin3d, ShaderStages, TextureAspect, TextureUsages};
 
 /// A plugin that enables reading back gpu buffers and textures to the cpu.
 pub struct GpuReadbackPlugin {
@@ -49,18 +60,25 @@ impl Default for GpuReadbackPlugin {
 
 impl Plugin for GpuReadbackPlugin {
     fn build(&self, app: &mut App) {
+        embedded_asset!(app, "depth_readback.wgsl");
         app.add_plugins(ExtractComponentPlugin::<Readback>::default());
 
+        let depth_readbacks = DepthReadbacks::default();
+        app.insert_resource(depth_readbacks.clone());
+
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
+                .insert_resource(depth_readbacks)
                 .init_resource::<GpuReadbackBufferPool>()
                 .init_resource::<GpuReadbacks>()
                 .insert_resource(GpuReadbackMaxUnusedFrames(self.max_unused_frames))
+                .add_systems(RenderStartup, init_depth_blit_pipeline)
                 .add_systems(ExtractSchedule, sync_readbacks.ambiguous_with_all())
                 .add_systems(
                     Render,
                     (
                         prepare_buffers.in_set(RenderSystems::PrepareResources),
+                        prepare_depth_readbacks.in_set(RenderSystems::PrepareBindGroups),
                         // TODO: this should be in the graph somehow
                         map_buffers.in_set(RenderSystems::Cleanup),
                     ),
@@ -71,8 +89,14 @@ impl Plugin for GpuReadbackPlugin {
 
 /// A component that registers the wrapped handle for gpu readback, either a texture or a buffer.
 ///
+/// Depth textures are read back through their depth aspect, see [`depth_copy_info`] for the
+/// supported formats. The depth of `Depth24Plus` formats can't be copied, it is written to a
+/// buffer in a compute pass instead, as one `f32` per texel without row padding. Their textures
+/// must be created with [`TextureUsages::TEXTURE_BINDING`].
+///
 /// Data is read asynchronously and will be triggered on the entity via the [`ReadbackComplete`] event
-/// when complete. If this component is not removed, the readback will be attempted every frame
+/// when complete. If this component is not removed, the readback will be attempted every frame.
+/// Add [`ReadbackOnce`] to only read the data once.
 #[derive(Component, ExtractComponent, Clone, Debug, FromTemplate)]
 pub enum Readback {
     #[default]
@@ -107,6 +131,13 @@ impl Readback {
     }
 }
 
+/// Despawns a [`Readback`] entity after its first [`ReadbackComplete`] was triggered.
+///
+/// Readbacks take a few frames to complete, so more of them can be in flight by then. Their
+/// results are dropped.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct ReadbackOnce;
+
 /// An event that is triggered when a gpu readback is complete.
 ///
 /// The event contains the data as a `Vec<u8>`, which can be interpreted as the raw bytes of the
@@ -129,6 +160,117 @@ impl ReadbackComplete {
     }
 }
 
+/// An error that prevented reading back a depth value.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DepthReadbackError {
+    #[error("{0:?} is not a depth format")]
+    NotDepth(TextureFormat),
+    /// The depth aspect of `Depth24Plus` formats can't be copied to a buffer on any backend.
+    #[error("the depth of {0:?} textures cannot be copied to a buffer, use `Depth32Float` instead")]
+    NotCopyable(TextureFormat),
+    #[error("the depth texture was not created with `TextureUsages::COPY_SRC`")]
+    MissingCopySrc,
+    /// `Depth24Plus` formats are read in a compute pass, which binds the texture.
+    #[error("the depth texture was not created with `TextureUsages::TEXTURE_BINDING`")]
+    MissingTextureBinding,
+    #[error("multisampled depth textures cannot be copied to a buffer")]
+    Multisampled,
+    #[error("view {0} has no depth texture")]
+    ViewNotFound(Entity),
+    #[error("pixel {pixel} is outside of the {size} depth texture")]
+    OutOfBounds { pixel: UVec2, size: UVec2 },
+    #[error("the depth readback returned {len} bytes, a depth value needs {expected}")]
+    Truncated { len: usize, expected: usize },
+    #[error("the depth readback was cancelled")]
+    Cancelled,
+}
+
+/// Returns the aspect to copy and its size in bytes per texel to read back the depth of a
+/// texture of the given format.
+///
+/// `Depth24Plus` formats return [`DepthReadbackError::NotCopyable`], the readbacks fall back to
+/// a compute pass for them.
+pub fn depth_copy_info(format: TextureFormat) -> Result<(TextureAspect, u32), DepthReadbackError> {
+    match format {
+        TextureFormat::Depth32Float | TextureFormat::Depth32FloatStencil8 => {
+            Ok((TextureAspect::DepthOnly, 4))
+        }
+        TextureFormat::Depth16Unorm => Ok((TextureAspect::DepthOnly, 2)),
+        TextureFormat::Depth24Plus | TextureFormat::Depth24PlusStencil8 => {
+            Err(DepthReadbackError::NotCopyable(format))
+        }
+        _ => Err(DepthReadbackError::NotDepth(format)),
+    }
+}
+
+/// Decodes the first depth value in `data`, read back from a texture of the given format.
+pub fn decode_depth(format: TextureFormat, data: &[u8]) -> Result<f32, DepthReadbackError> {
+    let (_, size) = depth_copy_info(format)?;
+    let Some(texel) = data.get(..size as usize) else {
+        return Err(DepthReadbackError::Truncated {
+            len: data.len(),
+            expected: size as usize,
+        });
+    };
+    Ok(match format {
+        TextureFormat::Depth16Unorm => u16::from_le_bytes([texel[0], texel[1]]) as f32 / 65535.0,
+        _ => f32::from_le_bytes([texel[0], texel[1], texel[2], texel[3]]),
+    })
+}
+
+/// Converts a raw depth buffer value to the view space distance from the camera.
+///
+/// `far` may be [`f32::INFINITY`] for infinite perspective projections. With `reversed_z`,
+/// the near plane is at depth `1.0` and the far plane at `0.0`, as used by Bevy's cameras.
+pub fn linearize_depth(raw: f32, near: f32, far: f32, reversed_z: bool) -> f32 {
+    let raw = if reversed_z { raw } else { 1.0 - raw };
+    if far.is_infinite() {
+        near / raw
+    } else {
+        near * far / (near + raw * (far - near))
+    }
+}
+
+/// Reads single depth values of views back to the CPU, e.g. for mouse picking.
+///
+/// The depth texture of the view must be created with [`TextureUsages::COPY_SRC`] and have a
+/// format supported by [`depth_copy_info`], or with [`TextureUsages::TEXTURE_BINDING`] for
+/// `Depth24Plus` formats, which are read in a compute pass. Only a 256 byte buffer is copied, so
+/// this is much cheaper than reading back the whole depth texture.
+#[derive(Resource, Clone, Default)]
+pub struct DepthReadbacks(Arc<Mutex<Vec<DepthReadbackRequest>>>);
+
+struct DepthReadbackRequest {
+    view: Entity,
+    pixel: UVec2,
+    sender: Sender<Result<f32, DepthReadbackError>>,
+}
+
+impl DepthReadbacks {
+    /// Reads the raw depth of `view` at `pixel` back to the CPU.
+    ///
+    /// The value is the depth after the next frame is rendered. Use [`linearize_depth`] to
+    /// convert it to a distance.
+    pub fn read_depth_at(
+        &self,
+        view: Entity,
+        pixel: UVec2,
+    ) -> impl Future<Output = Result<f32, DepthReadbackError>> + use<> {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.0.lock().unwrap().push(DepthReadbackRequest {
+            view,
+            pixel,
+            sender,
+        });
+        async move {
+            receiver
+                .recv()
+                .await
+                .unwrap_or(Err(DepthReadbackError::Cancelled))
+        }
+    }
+}
+
 #[derive(Resource)]
 struct GpuReadbackMaxUnusedFrames(usize);
 
@@ -210,11 +352,110 @@ enum ReadbackSource {
         texture: Texture,
         layout: TexelCopyBufferLayout,
         size: Extent3d,
+        origin: Origin3d,
+        aspect: TextureAspect,
     },
     Buffer {
         buffer: Buffer,
         start_offset_and_size: Option<(u64, u64)>,
     },
+    /// The depth of a region written to `values` by the [`DepthBlitPipeline`].
+    DepthBlit {
+        pipeline: CachedComputePipelineId,
+        bind_group: BindGroup,
+        size: UVec2,
+        values: Buffer,
+    },
+}
+
+/// Writes the depth of textures whose depth aspect can't be copied, see
+/// [`DepthReadbackError::NotCopyable`], to a buffer in a compute pass.
+#[derive(Resource)]
+pub struct DepthBlitPipeline {
+    pub bind_group_layout: BindGroupLayoutDescriptor,
+    pub pipeline_id: CachedComputePipelineId,
+}
+
+impl DepthBlitPipeline {
+    fn new(pipeline_cache: &PipelineCache, shader: Handle<Shader>) -> Self {
+        let bind_group_layout = BindGroupLayoutDescriptor::new(
+            "depth_blit_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    // @group(0) @binding(0) var depth_texture: texture_depth_2d;
+                    texture_depth_2d(),
+                    // @group(0) @binding(1) var<uniform> region: vec4<u32>;
+                    uniform_buffer::<UVec4>(false),
+                    // @group(0) @binding(2) var<storage, read_write> depth_values: array<f32>;
+                    storage_buffer_sized(false, None),
+                ),
+            ),
+        );
+        let pipeline_id = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("depth_blit_pipeline".into()),
+            layout: vec![bind_group_layout.clone()],
+            shader,
+            ..default()
+        });
+        Self {
+            bind_group_layout,
+            pipeline_id,
+        }
+    }
+
+    /// Prepares writing the depth of the `size` texels of `texture` at `origin` to a buffer, or
+    /// returns `None` while the pipeline is compiling.
+    fn blit(
+        &self,
+        render_device: &RenderDevice,
+        pipeline_cache: &PipelineCache,
+        texture: &Texture,
+        origin: UVec2,
+        size: UVec2,
+    ) -> Option<ReadbackSource> {
+        pipeline_cache.get_compute_pipeline(self.pipeline_id)?;
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("depth_blit_view"),
+            aspect: TextureAspect::DepthOnly,
+            ..default()
+        });
+        let region = render_device.create_buffer_with_data(&wgpu::util::BufferInitDescriptor {
+            label: Some("depth_blit_region"),
+            contents: bytemuck::cast_slice(&[origin.x, origin.y, size.x, size.y]),
+            usage: BufferUsages::UNIFORM,
+        });
+        let values = render_device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("depth_blit_values"),
+            size: u64::from(size.x * size.y) * 4,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let bind_group = render_device.create_bind_group(
+            "depth_blit_bind_group",
+            &pipeline_cache.get_bind_group_layout(&self.bind_group_layout),
+            &BindGroupEntries::sequential((
+                &view,
+                region.as_entire_binding(),
+                values.as_entire_binding(),
+            )),
+        );
+        Some(ReadbackSource::DepthBlit {
+            pipeline: self.pipeline_id,
+            bind_group,
+            size,
+            values,
+        })
+    }
+}
+
+fn init_depth_blit_pipeline(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pipeline_cache: Res<PipelineCache>,
+) {
+    let shader = load_embedded_asset!(asset_server.as_ref(), "depth_readback.wgsl");
+    commands.insert_resource(DepthBlitPipeline::new(&pipeline_cache, shader));
 }
 
 #[derive(Resource, Default)]
@@ -223,12 +464,23 @@ struct GpuReadbacks {
     mapped: Vec<GpuReadback>,
 }
 
+/// Where the data of a [`GpuReadback`] is delivered.
+enum ReadbackTarget {
+    /// Triggers [`ReadbackComplete`] on the entity.
+    Entity(Entity),
+    /// Answers a [`DepthReadbacks::read_depth_at`] request.
+    Depth {
+        format: TextureFormat,
+        sender: Sender<Result<f32, DepthReadbackError>>,
+    },
+}
+
 struct GpuReadback {
-    pub entity: Entity,
+    pub target: ReadbackTarget,
     pub src: ReadbackSource,
     pub buffer: Buffer,
-    pub rx: Receiver<(Entity, Buffer, Vec<u8>)>,
-    pub tx: Sender<(Entity, Buffer, Vec<u8>)>,
+    pub rx: Receiver<(Buffer, Vec<u8>)>,
+    pub tx: Sender<(Buffer, Vec<u8>)>,
 }
 
 fn sync_readbacks(
@@ -238,8 +490,26 @@ fn sync_readbacks(
     max_unused_frames: Res<GpuReadbackMaxUnusedFrames>,
 ) {
     readbacks.mapped.retain(|readback| {
-        if let Ok((entity, buffer, data)) = readback.rx.try_recv() {
-            main_world.trigger(ReadbackComplete { data, entity });
+        if let Ok((buffer, data)) = readback.rx.try_recv() {
+            match &readback.target {
+                ReadbackTarget::Entity(entity) => {
+                    if let Ok(entity_ref) = main_world.get_entity(*entity) {
+                        let once = entity_ref.contains::<ReadbackOnce>();
+                        main_world.trigger(ReadbackComplete {
+                            data,
+                            entity: *entity,
+                        });
+                        // Observers may have despawned it already.
+                        if once && let Ok(entity) = main_world.get_entity_mut(*entity) {
+                            entity.despawn();
+                        }
+                    }
+                }
+                ReadbackTarget::Depth { format, sender } => {
+                    // The future may have been dropped.
+                    let _ = sender.try_send(decode_depth(*format, &data));
+                }
+            }
             buffer_pool.return_buffer(&buffer);
             false
         } else {
@@ -256,30 +526,83 @@ fn prepare_buffers(
     mut buffer_pool: ResMut<GpuReadbackBufferPool>,
     gpu_images: Res<RenderAssets<GpuImage>>,
     ssbos: Res<RenderAssets<GpuShaderBuffer>>,
+    pipeline_cache: Res<PipelineCache>,
+    depth_blit_pipeline: Res<DepthBlitPipeline>,
     handles: Query<(&MainEntity, &Readback)>,
 ) {
     for (entity, readback) in handles.iter() {
         match readback {
             Readback::Texture(image) => {
-                if let Some(gpu_image) = gpu_images.get(image)
-                    && let Ok(pixel_size) = gpu_image.texture_descriptor.format.pixel_size()
-                {
-                    let layout = layout_data(
+                let Some(gpu_image) = gpu_images.get(image) else {
+                    continue;
+                };
+                let format = gpu_image.texture_descriptor.format;
+                if matches!(
+                    depth_copy_info(format),
+                    Err(DepthReadbackError::NotCopyable(_))
+                ) {
+                    if !gpu_image
+                        .texture_descriptor
+                        .usage
+                        .contains(TextureUsages::TEXTURE_BINDING)
+                    {
+                        warn!(
+                            "Cannot read back depth texture: {}",
+                            DepthReadbackError::MissingTextureBinding
+                        );
+                        continue;
+                    }
+                    let size = gpu_image.texture_descriptor.size;
+                    let size = UVec2::new(size.width, size.height);
+                    // Tried again next frame while the pipeline is compiling.
+                    let Some(src) = depth_blit_pipeline.blit(
+                        &render_device,
+                        &pipeline_cache,
+                        &gpu_image.texture,
+                        UVec2::ZERO,
+                        size,
+                    ) else {
+                        continue;
+                    };
+                    let (tx, rx) = async_channel::bounded(1);
+                    readbacks.requested.push(GpuReadback {
+                        target: ReadbackTarget::Entity(entity.id()),
+                        src,
+                        buffer: buffer_pool.get(&render_device, u64::from(size.x * size.y) * 4),
+                        rx,
+                        tx,
+                    });
+                    continue;
+                }
+                let copy_info = if format.is_depth_stencil_format() {
+                    depth_copy_info(format).map_err(|error| {
+                        warn!("Cannot read back depth texture: {error}");
+                    })
+                } else {
+                    format
+                        .pixel_size()
+                        .map(|pixel_size| (TextureAspect::All, pixel_size as u32))
+                        .map_err(|_| ())
+                };
+                if let Ok((aspect, pixel_size)) = copy_info {
+                    let layout = layout_data_with_pixel_size(
                         gpu_image.texture_descriptor.size,
-                        gpu_image.texture_descriptor.format,
+                        format,
+                        pixel_size,
                     );
                     let buffer = buffer_pool.get(
                         &render_device,
-                        get_aligned_size(gpu_image.texture_descriptor.size, pixel_size as u32)
-                            as u64,
+                        get_aligned_size(gpu_image.texture_descriptor.size, pixel_size) as u64,
                     );
                     let (tx, rx) = async_channel::bounded(1);
                     readbacks.requested.push(GpuReadback {
-                        entity: entity.id(),
+                        target: ReadbackTarget::Entity(entity.id()),
                         src: ReadbackSource::Texture {
                             texture: gpu_image.texture.clone(),
                             layout,
                             size: gpu_image.texture_descriptor.size,
+                            origin: Origin3d::ZERO,
+                            aspect,
                         },
                         buffer,
                         rx,
@@ -308,7 +631,7 @@ fn prepare_buffers(
                     let buffer = buffer_pool.get(&render_device, size);
                     let (tx, rx) = async_channel::bounded(1);
                     readbacks.requested.push(GpuReadback {
-                        entity: entity.id(),
+                        target: ReadbackTarget::Entity(entity.id()),
                         src: ReadbackSource::Buffer {
                             start_offset_and_size: *start_offset_and_size,
                             buffer: ssbo.buffer.clone(),
@@ -323,17 +646,139 @@ fn prepare_buffers(
     }
 }
 
+fn prepare_depth_readbacks(
+    render_device: Res<RenderDevice>,
+    depth_readbacks: Res<DepthReadbacks>,
+    mut readbacks: ResMut<GpuReadbacks>,
+    mut buffer_pool: ResMut<GpuReadbackBufferPool>,
+    pipeline_cache: Res<PipelineCache>,
+    depth_blit_pipeline: Res<DepthBlitPipeline>,
+    views: Query<(&MainEntity, &ViewDepthTexture)>,
+) {
+    let requests = core::mem::take(&mut *depth_readbacks.0.lock().unwrap());
+    // Blits waiting for their pipeline to compile, tried again next frame.
+    let mut waiting = Vec::new();
+    for request in requests {
+        let result = views
+            .iter()
+            .find(|(main_entity, _)| main_entity.id() == request.view)
+            .ok_or(DepthReadbackError::ViewNotFound(request.view))
+            .and_then(|(_, depth)| {
+                let texture = &depth.texture;
+                // `None` if the depth is read in a compute pass.
+                let aspect = match depth_copy_info(texture.format()) {
+                    Ok((aspect, _)) => {
+                        if !texture.usage().contains(TextureUsages::COPY_SRC) {
+                            return Err(DepthReadbackError::MissingCopySrc);
+                        }
+                        Some(aspect)
+                    }
+                    Err(DepthReadbackError::NotCopyable(_)) => {
+                        if !texture.usage().contains(TextureUsages::TEXTURE_BINDING) {
+                            return Err(DepthReadbackError::MissingTextureBinding);
+                        }
+                        None
+                    }
+                    Err(error) => return Err(error),
+                };
+                if texture.sample_count() > 1 {
+                    return Err(DepthReadbackError::Multisampled);
+                }
+                let size = UVec2::new(texture.width(), texture.height());
+                if request.pixel.cmpge(size).any() {
+                    return Err(DepthReadbackError::OutOfBounds {
+                        pixel: request.pixel,
+                        size,
+                    });
+                }
+                Ok((texture.clone(), aspect))
+            });
+        let (texture, aspect) = match result {
+            Ok(source) => source,
+            Err(error) => {
+                let _ = request.sender.try_send(Err(error));
+                continue;
+            }
+        };
+
+        let Some(aspect) = aspect else {
+            let Some(src) = depth_blit_pipeline.blit(
+                &render_device,
+                &pipeline_cache,
+                &texture,
+                request.pixel,
+                UVec2::ONE,
+            ) else {
+                waiting.push(request);
+                continue;
+            };
+            let (tx, rx) = async_channel::bounded(1);
+            readbacks.requested.push(GpuReadback {
+                // The blit writes `f32`s.
+                target: ReadbackTarget::Depth {
+                    format: TextureFormat::Depth32Float,
+                    sender: request.sender,
+                },
+                src,
+                buffer: buffer_pool.get(&render_device, 4),
+                rx,
+                tx,
+            });
+            continue;
+        };
+
+        let buffer = buffer_pool.get(&render_device, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64);
+        let (tx, rx) = async_channel::bounded(1);
+        readbacks.requested.push(GpuReadback {
+            target: ReadbackTarget::Depth {
+                format: texture.format(),
+                sender: request.sender,
+            },
+            src: ReadbackSource::Texture {
+                texture,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+                size: Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                origin: Origin3d {
+                    x: request.pixel.x,
+                    y: request.pixel.y,
+                    z: 0,
+                },
+                aspect,
+            },
+            buffer,
+            rx,
+            tx,
+        });
+    }
+    depth_readbacks.0.lock().unwrap().extend(waiting);
+}
+
 pub(crate) fn submit_readback_commands(world: &World, command_encoder: &mut CommandEncoder) {
     let readbacks = world.resource::<GpuReadbacks>();
+    let pipeline_cache = world.resource::<PipelineCache>();
     for readback in &readbacks.requested {
         match &readback.src {
             ReadbackSource::Texture {
                 texture,
                 layout,
                 size,
+                origin,
+                aspect,
             } => {
                 command_encoder.copy_texture_to_buffer(
-                    texture.as_image_copy(),
+                    wgpu::TexelCopyTextureInfo {
+                        origin: *origin,
+                        aspect: *aspect,
+                        ..texture.as_image_copy()
+                    },
                     wgpu::TexelCopyBufferInfo {
                         buffer: &readback.buffer,
                         layout: *layout,
@@ -348,6 +793,32 @@ pub(crate) fn submit_readback_commands(world: &World, command_encoder: &mut Comm
                 let (src_start, size) = start_offset_and_size.unwrap_or((0, buffer.size()));
                 command_encoder.copy_buffer_to_buffer(buffer, src_start, &readback.buffer, 0, size);
             }
+            ReadbackSource::DepthBlit {
+                pipeline,
+                bind_group,
+                size,
+                values,
+            } => {
+                // Checked when the readback was prepared.
+                let Some(pipeline) = pipeline_cache.get_compute_pipeline(*pipeline) else {
+                    continue;
+                };
+                let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("depth_blit_pass"),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, Some(&**bind_group), &[]);
+                pass.dispatch_workgroups(size.x.div_ceil(8), size.y.div_ceil(8), 1);
+                drop(pass);
+                command_encoder.copy_buffer_to_buffer(
+                    values,
+                    0,
+                    &readback.buffer,
+                    0,
+                    values.size(),
+                );
+            }
         }
     }
 }
@@ -357,7 +828,6 @@ fn map_buffers(mut readbacks: ResMut<GpuReadbacks>) {
     let requested = readbacks.requested.drain(..).collect::<Vec<GpuReadback>>();
     for readback in requested {
         let slice = readback.buffer.slice(..);
-        let entity = readback.entity;
         let buffer = readback.buffer.clone();
         let tx = readback.tx.clone();
         slice.map_async(wgpu::MapMode::Read, move |res| {
@@ -367,7 +837,7 @@ fn map_buffers(mut readbacks: ResMut<GpuReadbacks>) {
             let result = Vec::from(&*data);
             drop(data);
             buffer.unmap();
-            if let Err(e) = tx.try_send((entity, buffer, result)) {
+            if let Err(e) = tx.try_send((buffer, result)) {
                 warn!("Failed to send readback result: {}", e);
             }
         });
@@ -389,21 +859,31 @@ pub(crate) const fn get_aligned_size(extent: Extent3d, pixel_size: u32) -> u32 {
 
 /// Get a [`TexelCopyBufferLayout`] aligned such that the image can be copied into a buffer.
 pub(crate) fn layout_data(extent: Extent3d, format: TextureFormat) -> TexelCopyBufferLayout {
+    match format.pixel_size() {
+        Ok(pixel_size) => layout_data_with_pixel_size(extent, format, pixel_size as u32),
+        Err(_) => layout_data_with_pixel_size(extent, format, 0),
+    }
+}
+
+/// Like [`layout_data`], for texels of `pixel_size` bytes, e.g. a single aspect of a depth
+/// stencil texture. A `pixel_size` of `0` leaves the row size unspecified.
+pub(crate) fn layout_data_with_pixel_size(
+    extent: Extent3d,
+    format: TextureFormat,
+    pixel_size: u32,
+) -> TexelCopyBufferLayout {
     TexelCopyBufferLayout {
-        bytes_per_row: if extent.height > 1 || extent.depth_or_array_layers > 1 {
-            if let Ok(pixel_size) = format.pixel_size() {
-                // 1 = 1 row
-                Some(get_aligned_size(
-                    Extent3d {
-                        width: extent.width,
-                        height: 1,
-                        depth_or_array_layers: 1,
-                    },
-                    pixel_size as u32,
-                ))
-            } else {
-                None
-            }
+        bytes_per_row: if (extent.height > 1 || extent.depth_or_array_layers > 1) && pixel_size > 0
+        {
+            // 1 = 1 row
+            Some(get_aligned_size(
+                Extent3d {
+                    width: extent.width,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                pixel_size,
+            ))
         } else {
             None
         },
@@ -416,3 +896,322 @@ pub(crate) fn layout_data(extent: Extent3d, format: TextureFormat) -> TexelCopyB
         offset: 0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::uuid_handle;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_tasks::futures::now_or_never;
+
+    use super::*;
+    use crate::{
+        renderer::{RenderQueue, gpu_render_resources, noop_render_resources},
+        settings::RenderResources,
+        texture::CachedTexture,
+    };
+
+    const DEPTH_BLIT_SHADER: Handle<Shader> = uuid_handle!("5e0b7c2a-9d41-4f6e-a3b8-1c7d2e9f4a06");
+
+    fn depth_texture(
+        device: &RenderDevice,
+        format: TextureFormat,
+        usage: TextureUsages,
+    ) -> Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("depth"),
+            size: Extent3d {
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        })
+    }
+
+    /// Creates a render world with a view rendering its depth to `texture`, and returns it with
+    /// the main world entity of the view.
+    fn depth_readback_world(resources: RenderResources, texture: Texture) -> (World, Entity) {
+        let RenderResources(device, queue, _, adapter, ..) = resources;
+        let pipeline_cache = PipelineCache::new(device.clone(), adapter, true);
+        let mut world = World::new();
+        world.insert_resource(DepthBlitPipeline::new(&pipeline_cache, DEPTH_BLIT_SHADER));
+        world.insert_resource(pipeline_cache);
+        world.insert_resource(device);
+        world.insert_resource(queue);
+        world.init_resource::<DepthReadbacks>();
+        world.init_resource::<GpuReadbacks>();
+        world.init_resource::<GpuReadbackBufferPool>();
+        world.insert_resource(GpuReadbackMaxUnusedFrames(10));
+
+        let mut main_world = MainWorld::default();
+        let view = main_world.spawn_empty().id();
+        world.insert_resource(main_world);
+        let default_view = texture.create_view(&default());
+        world.spawn((
+            MainEntity::from(view),
+            ViewDepthTexture::new(
+                CachedTexture {
+                    texture,
+                    default_view,
+                },
+                None,
+            ),
+        ));
+        (world, view)
+    }
+
+    /// Requests the depth of `view` at `pixel` and runs the readback systems of a frame to
+    /// answer it.
+    fn read_depth(
+        world: &mut World,
+        view: Entity,
+        pixel: UVec2,
+    ) -> Result<f32, DepthReadbackError> {
+        let depth = world
+            .resource::<DepthReadbacks>()
+            .read_depth_at(view, pixel);
+        world.run_system_once(prepare_depth_readbacks).unwrap();
+        let device = world.resource::<RenderDevice>().clone();
+        let mut encoder = device.create_command_encoder(&default());
+        submit_readback_commands(world, &mut encoder);
+        world.resource::<RenderQueue>().submit([encoder.finish()]);
+        world.run_system_once(map_buffers).unwrap();
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+        world.run_system_once(sync_readbacks).unwrap();
+        now_or_never(depth).expect("the depth readback should be answered after a frame")
+    }
+
+    #[test]
+    fn depth_copy_capabilities() {
+        assert_eq!(
+            depth_copy_info(TextureFormat::Depth32Float),
+            Ok((TextureAspect::DepthOnly, 4))
+        );
+        assert_eq!(
+            depth_copy_info(TextureFormat::Depth32FloatStencil8),
+            Ok((TextureAspect::DepthOnly, 4))
+        );
+        assert_eq!(
+            depth_copy_info(TextureFormat::Depth24PlusStencil8),
+            Err(DepthReadbackError::NotCopyable(
+                TextureFormat::Depth24PlusStencil8
+            ))
+        );
+        assert_eq!(
+            depth_copy_info(TextureFormat::Rgba8Unorm),
+            Err(DepthReadbackError::NotDepth(TextureFormat::Rgba8Unorm))
+        );
+    }
+
+    #[test]
+    fn depth_is_decoded_and_linearized() {
+        // A reversed-z depth with an infinite far plane.
+        let raw = decode_depth(TextureFormat::Depth32Float, &0.25f32.to_le_bytes()).unwrap();
+        assert_eq!(raw, 0.25);
+        assert_eq!(linearize_depth(raw, 0.1, f32::INFINITY, true), 0.4);
+
+        let (near, far) = (1.0, 100.0);
+        assert_eq!(linearize_depth(1.0, near, far, true), near);
+        assert_eq!(linearize_depth(0.0, near, far, true), far);
+        assert_eq!(linearize_depth(0.0, near, far, false), near);
+        assert_eq!(linearize_depth(1.0, near, far, false), far);
+
+        let raw = decode_depth(TextureFormat::Depth16Unorm, &u16::MAX.to_le_bytes()).unwrap();
+        assert_eq!(raw, 1.0);
+    }
+
+    #[test]
+    fn short_depth_readbacks_are_errors() {
+        assert_eq!(
+            decode_depth(TextureFormat::Depth32Float, &[0; 2]),
+            Err(DepthReadbackError::Truncated {
+                len: 2,
+                expected: 4
+            })
+        );
+    }
+
+    #[test]
+    fn depth24plus_is_read_in_a_compute_pass() {
+        // The noop backend doesn't run compute passes, this needs an actual adapter.
+        let Some(RenderResources(device, queue, _, adapter, ..)) = gpu_render_resources() else {
+            return;
+        };
+        let mut pipeline_cache = PipelineCache::new(device.clone(), adapter, true);
+        pipeline_cache.set_shader(
+            DEPTH_BLIT_SHADER.id(),
+            Shader::from_wgsl(include_str!("depth_readback.wgsl"), "depth_readback.wgsl"),
+        );
+        let depth_blit_pipeline = DepthBlitPipeline::new(&pipeline_cache, DEPTH_BLIT_SHADER);
+        for _ in 0..3 {
+            pipeline_cache.process_queue();
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("depth24plus"),
+            size: Extent3d {
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::Depth24Plus,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&default());
+        let mut encoder = device.create_command_encoder(&default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("depth_clear"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0.25),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+
+        let (origin, size) = (UVec2::new(4, 2), UVec2::new(8, 4));
+        let src = depth_blit_pipeline
+            .blit(&device, &pipeline_cache, &texture, origin, size)
+            .expect("the depth blit pipeline should compile");
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("depth_readback"),
+            size: u64::from(size.x * size.y) * 4,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let (tx, rx) = async_channel::bounded(1);
+        let mut world = World::new();
+        world.insert_resource(pipeline_cache);
+        world.insert_resource(GpuReadbacks {
+            requested: vec![GpuReadback {
+                target: ReadbackTarget::Entity(Entity::PLACEHOLDER),
+                src,
+                buffer: buffer.clone(),
+                rx,
+                tx,
+            }],
+            mapped: Vec::new(),
+        });
+        submit_readback_commands(&world, &mut encoder);
+        queue.submit([encoder.finish()]);
+        buffer.slice(..).map_async(wgpu::MapMode::Read, |result| {
+            result.expect("the readback buffer should map");
+        });
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+        let data = buffer.slice(..).get_mapped_range();
+        for value in data.chunks_exact(4) {
+            let depth = decode_depth(TextureFormat::Depth32Float, value).unwrap();
+            // 24 bit depth isn't exact.
+            assert!((depth - 0.25).abs() < 1e-6, "read back {depth}");
+        }
+    }
+
+    #[test]
+    fn unreadable_depth_is_reported_without_a_copy() {
+        let cases = [
+            (
+                TextureFormat::Rgba8Unorm,
+                TextureUsages::COPY_SRC,
+                UVec2::ZERO,
+                DepthReadbackError::NotDepth(TextureFormat::Rgba8Unorm),
+            ),
+            (
+                TextureFormat::Depth32Float,
+                TextureUsages::RENDER_ATTACHMENT,
+                UVec2::ZERO,
+                DepthReadbackError::MissingCopySrc,
+            ),
+            (
+                TextureFormat::Depth24Plus,
+                TextureUsages::RENDER_ATTACHMENT,
+                UVec2::ZERO,
+                DepthReadbackError::MissingTextureBinding,
+            ),
+            (
+                TextureFormat::Depth16Unorm,
+                TextureUsages::COPY_SRC,
+                UVec2::new(3, 16),
+                DepthReadbackError::OutOfBounds {
+                    pixel: UVec2::new(3, 16),
+                    size: UVec2::splat(16),
+                },
+            ),
+        ];
+        for (format, usage, pixel, error) in cases {
+            let resources = noop_render_resources(wgpu::Features::empty());
+            let texture = depth_texture(&resources.0, format, usage);
+            let (mut world, view) = depth_readback_world(resources, texture);
+            assert_eq!(
+                read_depth(&mut world, view, pixel),
+                Err(error),
+                "{format:?}"
+            );
+        }
+
+        let resources = noop_render_resources(wgpu::Features::empty());
+        let texture = depth_texture(
+            &resources.0,
+            TextureFormat::Depth32Float,
+            TextureUsages::COPY_SRC,
+        );
+        let (mut world, _) = depth_readback_world(resources, texture);
+        assert_eq!(
+            read_depth(&mut world, Entity::PLACEHOLDER, UVec2::ZERO),
+            Err(DepthReadbackError::ViewNotFound(Entity::PLACEHOLDER))
+        );
+    }
+
+    #[test]
+    fn depth_is_read_at_a_pixel() {
+        // The noop backend doesn't clear textures, this needs an actual adapter.
+        let Some(resources) = gpu_render_resources() else {
+            return;
+        };
+        let (device, queue) = (resources.0.clone(), resources.1.clone());
+        let texture = depth_texture(
+            &device,
+            TextureFormat::Depth32Float,
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        );
+        let view = texture.create_view(&default());
+        let mut encoder = device.create_command_encoder(&default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("depth_clear"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0.25),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+        queue.submit([encoder.finish()]);
+
+        let (mut world, view) = depth_readback_world(resources, texture);
+        let raw = read_depth(&mut world, view, UVec2::new(3, 5)).unwrap();
+        assert_eq!(raw, 0.25);
+        // A reversed-z depth with an infinite far plane.
+        assert_eq!(linearize_depth(raw, 0.1, f32::INFINITY, true), 0.4);
+    }
+}
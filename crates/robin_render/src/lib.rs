@@ -46,7 +46,10 @@ pub mod extract_instances;
 mod extract_param;
 pub mod extract_plugin;
 pub mod extract_resource;
+pub mod frame_count;
 pub mod frame_graph;
+pub mod frame_pacing;
+pub mod fullscreen_vertex_shader;
 pub mod globals;
 pub mod gpu_component_array_buffer;
 pub mod gpu_readback;
@@ -80,7 +83,7 @@ pub mod prelude {
 }
 
 pub use extract_param::Extract;
-pub use extract_plugin::{ExtractSchedule, MainWorld};
+pub use extract_plugin::{ExtractSchedule, MainWorld, PreExtractAppExt};
 
 use crate::{
     camera::CameraPlugin,
@@ -92,7 +95,7 @@ use crate::{
     render_graph::RenderGraphPlugin,
     render_resource::{PipelineCache, SparseBufferPlugin},
     renderer::{RenderAdapterInfo, RenderGraph, render_system},
-    settings::{RenderCreation, WgpuLimits},
+    settings::{RenderCreation, RenderCreationContext, WgpuLimits},
     storage::StoragePlugin,
     texture::TexturePlugin,
     view::{ViewPlugin, WindowRenderPlugin},
@@ -101,16 +104,20 @@ use alloc::sync::Arc;
 use batching::gpu_preprocessing::BatchingPlugin;
 use bevy_app::{App, AppLabel, Plugin, SubApp};
 use bevy_asset::{AssetApp, AssetServer};
-use bevy_derive::Deref;
 use bevy_ecs::{
+    message::Messages,
     prelude::*,
     schedule::{InternedScheduleLabel, ScheduleLabel},
+    system::ScheduleSystem,
+};
+use bevy_platform::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
 };
-use bevy_platform::time::Instant;
 use bevy_shader::{Shader, ShaderLoader, load_shader_library};
 use bevy_time::TimeSender;
-use bevy_window::{PrimaryWindow, RawHandleWrapperHolder};
 use bitflags::bitflags;
+use core::task::Poll;
 use globals::GlobalsPlugin;
 use occlusion_culling::OcclusionCullingPlugin;
 use render_asset::{
@@ -118,7 +125,7 @@ use render_asset::{
     extract_render_asset_bytes_per_frame, reset_render_asset_bytes_per_frame,
 };
 use settings::RenderResources;
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError, TryLockError};
 
 /// Contains the default Bevy rendering backend based on wgpu.
 ///
@@ -214,9 +221,79 @@ pub enum RenderSystems {
 /// This can potentially run multiple times, and not on a fresh render world.
 /// Every time a new [`RenderDevice`](renderer::RenderDevice) is acquired,
 /// this schedule runs to initialize any gpu resources needed for rendering on it.
+///
+/// Systems in [`RenderStartupSystems::Once`] only run the first time, which is useful for
+/// setup that doesn't depend on the device.
 #[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone, Default)]
 pub struct RenderStartup;
 
+impl RenderStartup {
+    /// Sets up the base structure of the [`RenderStartup`] [`Schedule`].
+    ///
+    /// [`RenderStartupSystems::Once`] runs before [`RenderStartupSystems::PerDevice`].
+    pub fn base_schedule() -> Schedule {
+        let mut schedule = Schedule::new(Self);
+        schedule.set_executor(bevy_ecs::schedule::SingleThreadedExecutor::new());
+        schedule.configure_sets(
+            (
+                RenderStartupSystems::Once.run_if(is_first_render_startup),
+                RenderStartupSystems::PerDevice,
+            )
+                .chain(),
+        );
+        schedule
+    }
+}
+
+/// The sets of the [`RenderStartup`] schedule, controlling whether systems run again when a new
+/// [`RenderDevice`](renderer::RenderDevice) is acquired.
+///
+/// Systems that are in neither set behave like [`RenderStartupSystems::PerDevice`].
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum RenderStartupSystems {
+    /// Device independent setup, only run the first time [`RenderStartup`] runs.
+    Once,
+    /// Setup that is run again for every new [`RenderDevice`](renderer::RenderDevice).
+    PerDevice,
+}
+
+/// A run condition for [`RenderStartup`] systems, that is only `true` the first time it is checked
+/// for every new [`RenderDevice`](renderer::RenderDevice).
+///
+/// [`RenderStartupSystems::PerDevice`] systems run every time [`RenderStartup`] runs. This
+/// instead keys off the [`DeviceGeneration`](renderer::DeviceGeneration), so resources are
+/// rebuilt for a new device but not when the schedule runs again for the same one.
+///
+/// ```ignore
+/// app.add_render_startup_systems(init_my_buffers.run_if(run_once_per_device()));
+/// ```
+pub fn run_once_per_device() -> impl FnMut(Option<Res<renderer::DeviceGeneration>>) -> bool + Clone
+{
+    let mut ran_for = None;
+    move |generation: Option<Res<renderer::DeviceGeneration>>| {
+        let generation = generation.map_or(0, |generation| generation.0);
+        if ran_for == Some(generation) {
+            return false;
+        }
+        ran_for = Some(generation);
+        true
+    }
+}
+
+/// The number of times [`RenderStartup`] has run.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct RenderStartupRuns(u32);
+
+fn is_first_render_startup(runs: Option<Res<RenderStartupRuns>>) -> bool {
+    runs.is_none_or(|runs| runs.0 == 0)
+}
+
+/// Runs [`RenderStartup`] for a newly acquired device.
+pub(crate) fn run_render_startup(render_world: &mut World) {
+    render_world.run_schedule(RenderStartup);
+    render_world.get_resource_or_init::<RenderStartupRuns>().0 += 1;
+}
+
 /// Constructs a `T` resource with `from_world` and inserts it.
 pub fn init_gpu_resource<R: Resource + FromWorld>(world: &mut World) {
     let res = R::from_world(world);
@@ -224,6 +301,14 @@ pub fn init_gpu_resource<R: Resource + FromWorld>(world: &mut World) {
 }
 
 /// Convenience methods for render-recovery-aware resource initialization.
+///
+/// This is also the render startup extension: [`add_render_startup_systems`] registers systems
+/// that run for every new device, and [`init_render_resource_on_startup`] is another name for
+/// [`init_gpu_resource`].
+///
+/// [`add_render_startup_systems`]: GpuResourceAppExt::add_render_startup_systems
+/// [`init_render_resource_on_startup`]: GpuResourceAppExt::init_render_resource_on_startup
+/// [`init_gpu_resource`]: GpuResourceAppExt::init_gpu_resource
 pub trait GpuResourceAppExt {
     /// Causes the provided GPU resource to be re-initialized during [`RenderStartup`].
     ///
@@ -234,12 +319,85 @@ pub trait GpuResourceAppExt {
     /// app.add_systems(RenderStartup, init_gpu_resource::<R>.ambiguous_with_all());
     /// ```
     fn init_gpu_resource<R: Resource + FromWorld>(&mut self) -> &mut Self;
+
+    /// Initializes `R` with [`FromWorld`] in [`RenderStartup`], once for every new device.
+    ///
+    /// Same as [`init_gpu_resource`](GpuResourceAppExt::init_gpu_resource).
+    fn init_render_resource_on_startup<R: Resource + FromWorld>(&mut self) -> &mut Self {
+        self.init_gpu_resource::<R>()
+    }
+
+    /// Registers a [`ReclaimableGpuResource`](error_handler::ReclaimableGpuResource) to be
+    /// reclaimed before recovering from running out of GPU memory.
+    fn register_reclaimable_gpu_resource<R: error_handler::ReclaimableGpuResource>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Adds systems to [`RenderStartup`], to initialize resources for every new
+    /// [`RenderDevice`](renderer::RenderDevice).
+    ///
+    /// [`RenderStartup`] runs again after recovering from a lost device, on the existing render
+    /// world, so these systems must be idempotent: they should replace the resources they create
+    /// rather than assume they are missing. Put them in [`RenderStartupSystems::Once`] to only run
+    /// them the first time.
+    ///
+    /// On an [`App`], this does nothing if there is no [`RenderApp`], e.g. because no rendering
+    /// backend is available.
+    fn add_render_startup_systems<M>(
+        &mut self,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
 }
 
 impl GpuResourceAppExt for SubApp {
     fn init_gpu_resource<R: Resource + FromWorld>(&mut self) -> &mut Self {
         self.add_systems(RenderStartup, init_gpu_resource::<R>.ambiguous_with_all())
     }
+
+    fn register_reclaimable_gpu_resource<R: error_handler::ReclaimableGpuResource>(
+        &mut self,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<error_handler::ReclaimableGpuResources>()
+            .0
+            .push(error_handler::reclaim_gpu_resource::<R>);
+        self
+    }
+
+    fn add_render_startup_systems<M>(
+        &mut self,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        self.add_systems(RenderStartup, systems)
+    }
+}
+
+impl GpuResourceAppExt for App {
+    fn init_gpu_resource<R: Resource + FromWorld>(&mut self) -> &mut Self {
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app.init_gpu_resource::<R>();
+        }
+        self
+    }
+
+    fn register_reclaimable_gpu_resource<R: error_handler::ReclaimableGpuResource>(
+        &mut self,
+    ) -> &mut Self {
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app.register_reclaimable_gpu_resource::<R>();
+        }
+        self
+    }
+
+    fn add_render_startup_systems<M>(
+        &mut self,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app.add_render_startup_systems(systems);
+        }
+        self
+    }
 }
 
 /// The render recovery schedule. This schedule runs the [`RenderScheduleOrder`] schedules if
@@ -341,10 +499,59 @@ impl Render {
     }
 }
 
-#[derive(Resource, Default, Clone, Deref)]
-pub(crate) struct FutureRenderResources(Arc<Mutex<Option<RenderResources>>>);
+/// The [`RenderResources`] of a renderer creation that may still be running.
+#[derive(Resource, Default, Clone)]
+pub(crate) struct FutureRenderResources {
+    resources: Arc<Mutex<Option<RenderResources>>>,
+    /// Set when the creation finished without creating a renderer.
+    failed: Arc<AtomicBool>,
+}
+
+impl FutureRenderResources {
+    /// Stores the resources of the finished creation.
+    pub(crate) fn set(&self, render_resources: RenderResources) {
+        *self
+            .resources
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(render_resources);
+    }
+
+    /// Marks the creation as finished without a renderer.
+    pub(crate) fn fail(&self) {
+        self.failed.store(true, Ordering::Release);
+    }
+
+    /// Locks the resources, or returns `None` if the creation is writing them right now.
+    ///
+    /// A poisoned lock is recovered, the resources are only ever replaced as a whole.
+    fn try_lock_resources(&self) -> Option<MutexGuard<'_, Option<RenderResources>>> {
+        match self.resources.try_lock() {
+            Ok(resources) => Some(resources),
+            Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// Takes the resources of the finished creation, without waiting for a pending one.
+    ///
+    /// Returns `Ready(None)` if the creation failed.
+    pub(crate) fn poll(&self) -> Poll<Option<RenderResources>> {
+        let Some(mut resources) = self.try_lock_resources() else {
+            return Poll::Pending;
+        };
+        match resources.take() {
+            Some(render_resources) => Poll::Ready(Some(render_resources)),
+            None if self.failed.load(Ordering::Acquire) => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
 
 /// A label for the rendering sub-app.
+///
+/// There is a single rendering sub-app per [`App`], with a single device that renders every
+/// window. Render plugins register their systems and resources with this label, so a second
+/// sub-app, e.g. to render some windows on another adapter, would have none of them.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, AppLabel)]
 pub struct RenderApp;
 
@@ -352,7 +559,10 @@ impl Plugin for RobinRenderPlugin {
     /// Initializes the renderer, sets up the [`RenderSystems`] and creates the rendering sub-app.
     fn build(&self, app: &mut App) {
         app.init_asset::<Shader>()
-            .init_asset_loader::<ShaderLoader>();
+            .init_asset_loader::<ShaderLoader>()
+            .add_message::<error_handler::RenderRecovered>()
+            .add_message::<error_handler::RenderErrorOccurred>()
+            .add_message::<error_handler::RenderDeviceRecreated>();
         load_shader_library!(app, "maths.wgsl");
         load_shader_library!(app, "color_operations.wgsl");
         load_shader_library!(app, "bindless.wgsl");
@@ -361,7 +571,7 @@ impl Plugin for RobinRenderPlugin {
             // We only create the render world and set up extraction if we
             // have a rendering backend available.
             app.add_plugins(ExtractPlugin {
-                pre_extract: error_handler::update_state,
+                pre_extract: vec![error_handler::update_state],
             });
         };
 
@@ -379,6 +589,10 @@ impl Plugin for RobinRenderPlugin {
             GpuReadbackPlugin::default(),
             OcclusionCullingPlugin,
             SparseBufferPlugin,
+            diagnostic::RenderFrameStatisticsPlugin::default(),
+            fullscreen_vertex_shader::FullscreenShaderPlugin,
+            frame_count::RenderFrameCountPlugin,
+            frame_pacing::FramePacingPlugin,
             #[cfg(feature = "tracing-tracy")]
             diagnostic::RenderDiagnosticsPlugin,
         ));
@@ -388,14 +602,24 @@ impl Plugin for RobinRenderPlugin {
 
         let asset_server = app.world().resource::<AssetServer>().clone();
         app.init_resource::<RenderAssetBytesPerFrame>()
-            .init_resource::<RenderErrorHandler>();
+            .init_resource::<RenderErrorHandler>()
+            .init_resource::<error_handler::RenderErrorPolicyMap>()
+            .init_resource::<error_handler::RecoveryLimits>()
+            .init_resource::<error_handler::RenderStatus>()
+            .init_resource::<error_handler::RenderErrorStats>()
+            .init_resource::<error_handler::RenderEnabled>();
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app.init_resource::<RenderScheduleOrder>();
             render_app.init_resource::<RenderAssetBytesPerFrameLimiter>();
             render_app.init_gpu_resource::<renderer::PendingCommandBuffers>();
+            render_app.init_gpu_resource::<renderer::RenderCapabilities>();
             render_app.insert_resource(sender);
             render_app.insert_resource(asset_server);
             render_app.insert_resource(RenderState::Initializing);
+            render_app.init_resource::<Messages<error_handler::RenderDeviceRecreated>>();
+            render_app
+                .world_mut()
+                .add_observer(PipelineCache::recreate_on_device_recreated);
             render_app.add_systems(
                 ExtractSchedule,
                 (
@@ -415,15 +639,18 @@ impl Plugin for RobinRenderPlugin {
 
             render_app.add_schedule(RenderGraph::base_schedule());
 
-            render_app.init_schedule(RenderStartup);
-            render_app
-                .get_schedule_mut(RenderStartup)
-                .unwrap()
-                .set_executor(bevy_ecs::schedule::SingleThreadedExecutor::new());
+            render_app.add_schedule(RenderStartup::base_schedule());
             render_app.update_schedule = Some(RenderRecovery.intern());
             render_app.add_systems(
                 RenderRecovery,
-                (run_render_schedule.run_if(renderer_is_ready), send_time).chain(),
+                (
+                    run_render_schedule.run_if(renderer_is_ready),
+                    poll_paused_device.run_if(renderer_is_paused),
+                    frame_count::count_render_frame,
+                    send_time,
+                    update_render_messages,
+                )
+                    .chain(),
             );
             render_app.add_systems(
                 Render,
@@ -450,7 +677,7 @@ impl Plugin for RobinRenderPlugin {
         // If the lock succeeds, we can straightforwardly check if it is populated. If it is not, then we're not ready.
         app.world()
             .get_resource::<FutureRenderResources>()
-            .and_then(|frr| frr.try_lock().map(|locked| locked.is_some()).ok())
+            .and_then(|frr| frr.try_lock_resources().map(|locked| locked.is_some()))
             .unwrap_or(true)
     }
 
@@ -460,7 +687,12 @@ impl Plugin for RobinRenderPlugin {
         {
             let bevy_app::SubApps { main, sub_apps } = app.sub_apps_mut();
             let render = sub_apps.get_mut(&RenderApp.intern()).unwrap();
-            let render_resources = future_render_resources.0.lock().unwrap().take().unwrap();
+            let render_resources = future_render_resources
+                .resources
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take()
+                .unwrap();
 
             render_resources.unpack_into(
                 main.world_mut(),
@@ -475,6 +707,15 @@ fn renderer_is_ready(state: Res<RenderState>) -> bool {
     matches!(*state, RenderState::Ready)
 }
 
+fn renderer_is_paused(state: Res<RenderState>) -> bool {
+    matches!(*state, RenderState::Paused)
+}
+
+/// Keeps mapping callbacks and resource cleanups going while the [`Render`] schedule is paused.
+fn poll_paused_device(render_device: Res<renderer::RenderDevice>) {
+    let _ = render_device.poll(wgpu::PollType::Poll);
+}
+
 fn run_render_schedule(world: &mut World) {
     world.resource_scope(|world, order: Mut<RenderScheduleOrder>| {
         for &label in &order.labels {
@@ -483,6 +724,15 @@ fn run_render_schedule(world: &mut World) {
     });
 }
 
+/// Updates the messages sent in the render world, as it has no [`First`](bevy_app::First) schedule.
+///
+/// This runs every frame, whether rendering or not.
+fn update_render_messages(
+    mut device_recreated: ResMut<Messages<error_handler::RenderDeviceRecreated>>,
+) {
+    device_recreated.update();
+}
+
 fn send_time(time_sender: Res<TimeSender>) {
     // update the time and send it to the app world regardless of whether we render
     if let Err(error) = time_sender.0.try_send(Instant::now()) {
@@ -500,29 +750,28 @@ fn send_time(time_sender: Res<TimeSender>) {
     }
 }
 
+/// Keeps [`LastWgpuSettings`](error_handler::LastWgpuSettings) in sync with the renderer being
+/// created, so the settings of a previous automatic creation don't apply to a manual one.
+fn remember_settings(render_creation: &RenderCreation, main_world: &mut World) {
+    match render_creation {
+        RenderCreation::Automatic(settings) => {
+            main_world.insert_resource(error_handler::LastWgpuSettings((**settings).clone()));
+        }
+        RenderCreation::Manual(_) => {
+            main_world.remove_resource::<error_handler::LastWgpuSettings>();
+        }
+    }
+}
+
 /// Inserts a [`FutureRenderResources`] created from this [`RenderCreation`].
 ///
-/// Returns true if creation was successful, false otherwise.
+/// Returns true if creation was successful, false otherwise. The renderer is created
+/// synchronously, except on the web.
 fn insert_future_resources(render_creation: &RenderCreation, main_world: &mut World) -> bool {
-    let primary_window = main_world
-        .query_filtered::<&RawHandleWrapperHolder, With<PrimaryWindow>>()
-        .single(main_world)
-        .ok()
-        .cloned();
-
-    #[cfg(feature = "raw_vulkan_init")]
-    let raw_vulkan_init_settings = main_world
-        .get_resource::<renderer::raw_vulkan_init::RawVulkanInitSettings>()
-        .cloned()
-        .unwrap_or_default();
-
+    let context = RenderCreationContext::from_main_world(main_world);
+    remember_settings(render_creation, main_world);
     let future_resources = FutureRenderResources::default();
-    let success = render_creation.create_render(
-        future_resources.clone(),
-        primary_window,
-        #[cfg(feature = "raw_vulkan_init")]
-        raw_vulkan_init_settings,
-    );
+    let success = render_creation.create_render(future_resources.clone(), context);
     if success {
         // Note that `future_resources` is not necessarily populated here yet.
         main_world.insert_resource(future_resources);
@@ -530,6 +779,103 @@ fn insert_future_resources(render_creation: &RenderCreation, main_world: &mut Wo
     success
 }
 
+/// Inserts a [`FutureRenderResources`] created from this [`RenderCreation`] in the background,
+/// so the app keeps updating while the renderer is recreated.
+///
+/// Returns true if creation started, false otherwise.
+fn spawn_future_resources(render_creation: &RenderCreation, main_world: &mut World) -> bool {
+    let context = RenderCreationContext::from_main_world(main_world);
+    remember_settings(render_creation, main_world);
+    let future_resources = FutureRenderResources::default();
+    let started = render_creation.spawn_render(future_resources.clone(), context);
+    if started {
+        main_world.insert_resource(future_resources);
+    }
+    started
+}
+
+/// Inserts a [`FutureRenderResources`] with a new device requested in the background from the
+/// adapter and instance currently used by the render world, falling back to creating a new
+/// renderer from `render_creation` if that fails.
+///
+/// Returns true if creation started, false otherwise. On the web, a new renderer is always
+/// created.
+#[cfg(target_arch = "wasm32")]
+fn spawn_future_device(
+    render_creation: &RenderCreation,
+    main_world: &mut World,
+    _render_world: &World,
+) -> bool {
+    spawn_future_resources(render_creation, main_world)
+}
+
+/// Inserts a [`FutureRenderResources`] with a new device requested in the background from the
+/// adapter and instance currently used by the render world, falling back to creating a new
+/// renderer from `render_creation` if that fails.
+///
+/// Returns true if creation started, false otherwise.
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_future_device(
+    render_creation: &RenderCreation,
+    main_world: &mut World,
+    render_world: &World,
+) -> bool {
+    let (Some(device), Some(queue), Some(adapter_info), Some(adapter), Some(instance)) = (
+        render_world.get_resource::<renderer::RenderDevice>(),
+        render_world.get_resource::<renderer::RenderQueue>(),
+        render_world.get_resource::<RenderAdapterInfo>(),
+        render_world.get_resource::<renderer::RenderAdapter>(),
+        render_world.get_resource::<renderer::RenderInstance>(),
+    ) else {
+        return spawn_future_resources(render_creation, main_world);
+    };
+    let previous = RenderResources(
+        device.clone(),
+        queue.clone(),
+        adapter_info.clone(),
+        adapter.clone(),
+        instance.clone(),
+        #[cfg(feature = "raw_vulkan_init")]
+        render_world
+            .get_resource::<renderer::raw_vulkan_init::AdditionalVulkanFeatures>()
+            .cloned()
+            .unwrap_or_default(),
+        #[cfg(feature = "raw_vulkan_init")]
+        Default::default(),
+    );
+
+    let context = RenderCreationContext::from_main_world(main_world);
+    #[cfg(feature = "raw_vulkan_init")]
+    let raw_vulkan_init_settings = context.raw_vulkan_init_settings.clone();
+    remember_settings(render_creation, main_world);
+    let future_resources = FutureRenderResources::default();
+    let fallback = render_creation.creation_task(future_resources.clone(), context);
+    main_world.insert_resource(future_resources.clone());
+
+    let recreation = async move {
+        if let Some(resources) = renderer::recreate_device(
+            previous,
+            #[cfg(feature = "raw_vulkan_init")]
+            raw_vulkan_init_settings,
+        )
+        .await
+        {
+            future_resources.set(resources);
+            return;
+        }
+        match fallback {
+            Some(creation) => {
+                creation.await;
+            }
+            None => future_resources.fail(),
+        }
+    };
+    bevy_tasks::AsyncComputeTaskPool::get_or_init(bevy_tasks::TaskPool::default)
+        .spawn(recreation)
+        .detach();
+    true
+}
+
 /// If the [`RenderAdapterInfo`] is a Qualcomm Adreno, returns its model number.
 ///
 /// This lets us work around hardware bugs.
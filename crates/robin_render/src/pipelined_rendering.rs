@@ -23,6 +23,7 @@ pub struct RenderAppChannels {
     app_to_render_sender: Sender<SubApp>,
     render_to_app_receiver: Receiver<SubApp>,
     render_app_in_render_thread: bool,
+    render_thread: Option<std::thread::JoinHandle<()>>,
 }
 
 impl RenderAppChannels {
@@ -35,6 +36,7 @@ impl RenderAppChannels {
             app_to_render_sender,
             render_to_app_receiver,
             render_app_in_render_thread: false,
+            render_thread: None,
         }
     }
 
@@ -62,6 +64,12 @@ impl Drop for RenderAppChannels {
             // drop methods to run on the correct thread.
             self.render_to_app_receiver.recv_blocking().ok();
         }
+        // Closing the channel stops the render thread, so it can be joined before the app ends.
+        self.app_to_render_sender.close();
+        if let Some(render_thread) = self.render_thread.take() {
+            // A panic of the render thread was already reported by `renderer_extract`.
+            let _ = render_thread.join();
+        }
     }
 }
 
@@ -79,7 +87,8 @@ impl Drop for RenderAppChannels {
 /// ```
 ///
 /// The plugin is dependent on the [`RenderApp`] added by [`crate::RenderPlugin`] and so must
-/// be added after that plugin. If it is not added after, the plugin will do nothing.
+/// be added after that plugin. If it is not added after, the plugin will do nothing. It also
+/// does nothing on wasm and without the `multi_threaded` feature, where there is no render thread.
 ///
 /// A single frame of execution looks something like below
 ///
@@ -114,6 +123,9 @@ impl Plugin for PipelinedRenderingPlugin {
         if app.get_sub_app(RenderApp).is_none() {
             return;
         }
+        if cfg!(any(target_arch = "wasm32", not(feature = "multi_threaded"))) {
+            return;
+        }
         app.insert_resource(MainThreadExecutor::new());
 
         let mut sub_app = SubApp::new();
@@ -141,12 +153,10 @@ impl Plugin for PipelinedRenderingPlugin {
 
         render_to_app_sender.send_blocking(render_app).unwrap();
 
-        app.insert_resource(RenderAppChannels::new(
-            app_to_render_sender,
-            render_to_app_receiver,
-        ));
+        let mut render_channels =
+            RenderAppChannels::new(app_to_render_sender, render_to_app_receiver);
 
-        std::thread::spawn(move || {
+        let render_thread = std::thread::spawn(move || {
             #[cfg(feature = "trace")]
             let _span = bevy_log::info_span!("render thread").entered();
 
@@ -176,6 +186,8 @@ impl Plugin for PipelinedRenderingPlugin {
 
             bevy_log::debug!("exiting pipelined rendering thread");
         });
+        render_channels.render_thread = Some(render_thread);
+        app.insert_resource(render_channels);
     }
 }
 
@@ -1,25 +1,65 @@
 mod plugin;
 
+use alloc::collections::BinaryHeap;
 use bevy_ecs::{
+    define_label,
     entity::Entity,
+    intern::Interned,
     query::{QueryItem, QueryState, ReadOnlyQueryData},
     resource::Resource,
     schedule::{InternedScheduleLabel, ScheduleLabel},
     world::{FromWorld, World},
 };
 use bevy_platform::collections::HashMap;
+use core::cmp::Reverse;
 use thiserror::Error;
 
 use crate::{frame_graph::FrameGraph, render_phase::DrawError};
 
+pub use bevy_render_macros::RenderLabel;
 pub use plugin::*;
 
+define_label!(
+    #[diagnostic::on_unimplemented(
+        note = "consider annotating `{Self}` with `#[derive(RenderLabel)]`"
+    )]
+    /// A strongly-typed class of labels used to identify a [`Node`] in a [`RenderPipeline`].
+    RenderLabel,
+    RENDER_LABEL_INTERNER
+);
+
+/// A shorthand for `Interned<dyn RenderLabel>`.
+pub type InternedRenderLabel = Interned<dyn RenderLabel>;
+
 #[derive(Error, Debug, Eq, PartialEq)]
 pub enum NodeRunError {
     #[error("encountered an error when executing draw command")]
     DrawError(#[from] DrawError),
 }
 
+/// A [`NodeRunError`], along with the [`Node`] that returned it.
+#[derive(Error, Debug, Eq, PartialEq)]
+#[error("render graph node `{node}` failed: {error}")]
+pub struct RenderGraphRunError {
+    pub node: &'static str,
+    #[source]
+    pub error: NodeRunError,
+}
+
+/// An error building a [`RenderPipeline`].
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum RenderGraphError {
+    #[error("node {0:?} does not exist")]
+    InvalidNode(InternedRenderLabel),
+    #[error("node {0:?} was already added")]
+    NodeAlreadyExists(InternedRenderLabel),
+    #[error("an edge from {before:?} to {after:?} would create a cycle")]
+    Cycle {
+        before: InternedRenderLabel,
+        after: InternedRenderLabel,
+    },
+}
+
 #[derive(Resource, Default)]
 pub struct RenderGraph {
     pipelines: HashMap<InternedScheduleLabel, RenderPipeline>,
@@ -29,7 +69,7 @@ impl RenderGraph {
     pub fn update(&mut self, world: &mut World) {
         for pipeline in self.pipelines.values_mut() {
             for node in &mut pipeline.nodes {
-                node.update(world);
+                node.node.update(world);
             }
         }
     }
@@ -43,27 +83,156 @@ impl RenderGraph {
         pipeline: &InternedScheduleLabel,
         graph: &mut RenderGraphContext,
         world: &World,
-    ) -> Result<(), NodeRunError> {
+    ) -> Result<(), RenderGraphRunError> {
         if let Some(render_pipeline) = self.pipelines.get(pipeline) {
-            for node in render_pipeline.nodes.iter() {
-                node.run(graph, world)?;
+            for node in render_pipeline.iter() {
+                node.run(graph, world)
+                    .map_err(|error| RenderGraphRunError {
+                        node: node.name(),
+                        error,
+                    })?;
             }
         }
         Ok(())
     }
 }
 
+/// The nodes run for a camera, in an order satisfying the edges between them.
+///
+/// Nodes without edges between them run in the order they were added.
 pub struct RenderPipeline {
-    nodes: Vec<Box<dyn Node>>,
+    nodes: Vec<NodeState>,
+    labels: HashMap<InternedRenderLabel, usize>,
+    /// Pairs of node indices, the first running before the second.
+    edges: Vec<(usize, usize)>,
+    /// The node indices in the order they run.
+    order: Vec<usize>,
+}
+
+struct NodeState {
+    label: Option<InternedRenderLabel>,
+    node: Box<dyn Node>,
 }
 
 impl RenderPipeline {
     pub fn empty() -> Self {
-        RenderPipeline { nodes: vec![] }
+        RenderPipeline {
+            nodes: vec![],
+            labels: HashMap::default(),
+            edges: vec![],
+            order: vec![],
+        }
     }
 
+    /// Adds an unlabeled node, which can't be ordered with edges.
     pub fn push(&mut self, node: impl Node) {
-        self.nodes.push(Box::new(node));
+        self.insert(None, node);
+    }
+
+    /// Adds a node that can be ordered with [`RenderPipeline::add_node_edge`].
+    pub fn add_node(
+        &mut self,
+        label: impl RenderLabel,
+        node: impl Node,
+    ) -> Result<(), RenderGraphError> {
+        let label = label.intern();
+        if self.labels.contains_key(&label) {
+            return Err(RenderGraphError::NodeAlreadyExists(label));
+        }
+        self.labels.insert(label, self.nodes.len());
+        self.insert(Some(label), node);
+        Ok(())
+    }
+
+    /// Makes the node `before` run before the node `after`.
+    ///
+    /// Fails if either node doesn't exist or if `after` already runs before `before`, leaving the
+    /// pipeline unchanged.
+    pub fn add_node_edge(
+        &mut self,
+        before: impl RenderLabel,
+        after: impl RenderLabel,
+    ) -> Result<(), RenderGraphError> {
+        let (before, after) = (before.intern(), after.intern());
+        let before_index = self.node_index(before)?;
+        let after_index = self.node_index(after)?;
+        if self.edges.contains(&(before_index, after_index)) {
+            return Ok(());
+        }
+        if self.reaches(after_index, before_index) {
+            return Err(RenderGraphError::Cycle { before, after });
+        }
+        self.edges.push((before_index, after_index));
+        self.sort();
+        Ok(())
+    }
+
+    /// Returns the labels of the nodes in the order they run, [`None`] for unlabeled nodes.
+    pub fn order(&self) -> impl Iterator<Item = Option<InternedRenderLabel>> + '_ {
+        self.order.iter().map(|&index| self.nodes[index].label)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &dyn Node> {
+        self.order.iter().map(|&index| &*self.nodes[index].node)
+    }
+
+    fn insert(&mut self, label: Option<InternedRenderLabel>, node: impl Node) {
+        self.order.push(self.nodes.len());
+        self.nodes.push(NodeState {
+            label,
+            node: Box::new(node),
+        });
+    }
+
+    fn node_index(&self, label: InternedRenderLabel) -> Result<usize, RenderGraphError> {
+        self.labels
+            .get(&label)
+            .copied()
+            .ok_or(RenderGraphError::InvalidNode(label))
+    }
+
+    /// Whether `to` runs after `from` because of the edges between nodes.
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut stack = vec![from];
+        let mut visited = vec![false; self.nodes.len()];
+        while let Some(index) = stack.pop() {
+            if index == to {
+                return true;
+            }
+            if !core::mem::replace(&mut visited[index], true) {
+                stack.extend(
+                    self.edges
+                        .iter()
+                        .filter(|(before, _)| *before == index)
+                        .map(|(_, after)| *after),
+                );
+            }
+        }
+        false
+    }
+
+    /// Sorts the nodes topologically, preferring the order they were added in. Edges never form
+    /// cycles, so every node is sorted.
+    fn sort(&mut self) {
+        let mut dependencies = vec![0usize; self.nodes.len()];
+        for (_, after) in &self.edges {
+            dependencies[*after] += 1;
+        }
+        let mut ready: BinaryHeap<_> = (0..self.nodes.len())
+            .filter(|&index| dependencies[index] == 0)
+            .map(Reverse)
+            .collect();
+
+        self.order.clear();
+        while let Some(Reverse(index)) = ready.pop() {
+            self.order.push(index);
+            for (_, after) in self.edges.iter().filter(|(before, _)| *before == index) {
+                dependencies[*after] -= 1;
+                if dependencies[*after] == 0 {
+                    ready.push(Reverse(*after));
+                }
+            }
+        }
     }
 }
 
@@ -79,6 +248,11 @@ impl<'a> RenderGraphContext<'a> {
 }
 
 pub trait Node: 'static + Send + Sync {
+    /// The name of the node, used when reporting errors.
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
     fn update(&mut self, _world: &mut World) {}
     fn run(&self, graph: &mut RenderGraphContext, world: &World) -> Result<(), NodeRunError>;
 }
@@ -120,6 +294,10 @@ impl<T> Node for ViewNodeRunner<T>
 where
     T: ViewNode + Send + Sync + 'static,
 {
+    fn name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
+
     fn update(&mut self, world: &mut World) {
         self.view_query.update_archetypes(world);
         self.node.update(world);
@@ -138,3 +316,119 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    struct TestPipeline;
+
+    struct FailingNode;
+
+    impl Node for FailingNode {
+        fn run(&self, _: &mut RenderGraphContext, _: &World) -> Result<(), NodeRunError> {
+            Err(NodeRunError::DrawError(DrawError::InvalidViewQuery))
+        }
+    }
+
+    struct LaterNode(&'static AtomicBool);
+
+    impl Node for LaterNode {
+        fn run(&self, _: &mut RenderGraphContext, _: &World) -> Result<(), NodeRunError> {
+            self.0.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[derive(RenderLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    enum TestNode {
+        A,
+        B,
+        C,
+    }
+
+    struct RecordingNode(&'static str, &'static Mutex<Vec<&'static str>>);
+
+    impl Node for RecordingNode {
+        fn run(&self, _: &mut RenderGraphContext, _: &World) -> Result<(), NodeRunError> {
+            self.1.lock().unwrap().push(self.0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn nodes_run_in_edge_order() {
+        static RAN: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+        let mut pipeline = RenderPipeline::empty();
+        pipeline
+            .add_node(TestNode::A, RecordingNode("a", &RAN))
+            .unwrap();
+        pipeline
+            .add_node(TestNode::B, RecordingNode("b", &RAN))
+            .unwrap();
+        pipeline
+            .add_node(TestNode::C, RecordingNode("c", &RAN))
+            .unwrap();
+        pipeline.add_node_edge(TestNode::C, TestNode::A).unwrap();
+        pipeline.add_node_edge(TestNode::B, TestNode::C).unwrap();
+
+        assert_eq!(
+            pipeline.add_node_edge(TestNode::A, TestNode::B),
+            Err(RenderGraphError::Cycle {
+                before: TestNode::A.intern(),
+                after: TestNode::B.intern(),
+            })
+        );
+        assert_eq!(
+            pipeline.add_node(TestNode::A, RecordingNode("a", &RAN)),
+            Err(RenderGraphError::NodeAlreadyExists(TestNode::A.intern()))
+        );
+
+        let mut render_graph = RenderGraph::default();
+        render_graph.add(TestPipeline, pipeline);
+        let world = World::new();
+        let mut frame_graph = FrameGraph::default();
+        let mut graph = RenderGraphContext {
+            frame_graph: &mut frame_graph,
+            view_entity: None,
+        };
+        render_graph
+            .run(&TestPipeline.intern(), &mut graph, &world)
+            .unwrap();
+
+        assert_eq!(*RAN.lock().unwrap(), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn run_reports_failing_node() {
+        static LATER_RAN: AtomicBool = AtomicBool::new(false);
+
+        let mut pipeline = RenderPipeline::empty();
+        pipeline.push(FailingNode);
+        pipeline.push(LaterNode(&LATER_RAN));
+        let mut render_graph = RenderGraph::default();
+        render_graph.add(TestPipeline, pipeline);
+
+        let world = World::new();
+        let mut frame_graph = FrameGraph::default();
+        let mut graph = RenderGraphContext {
+            frame_graph: &mut frame_graph,
+            view_entity: None,
+        };
+        let error = render_graph
+            .run(&TestPipeline.intern(), &mut graph, &world)
+            .unwrap_err();
+
+        assert_eq!(error.node, core::any::type_name::<FailingNode>());
+        assert_eq!(
+            error.error,
+            NodeRunError::DrawError(DrawError::InvalidViewQuery)
+        );
+        assert!(!LATER_RAN.load(Ordering::Relaxed));
+    }
+}
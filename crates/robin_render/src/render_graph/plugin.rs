@@ -2,6 +2,7 @@ use core::fmt::{self, Display, Formatter};
 
 use bevy_app::{App, Plugin};
 use bevy_camera::{ClearColor, NormalizedRenderTarget};
+use bevy_color::LinearRgba;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
     entity::{Entity, EntityHashMap, EntityHashSet},
@@ -21,7 +22,7 @@ use crate::{
     render_graph::{RenderGraph, RenderGraphContext},
     render_resource::*,
     renderer::{RenderDevice, RenderGraph as RenderGraphSchedule, RenderQueue},
-    view::ExtractedWindows,
+    view::{ExtractedWindows, ViewTarget},
 };
 
 pub struct RenderGraphPlugin;
@@ -48,7 +49,36 @@ impl Plugin for RenderGraphPlugin {
                 camera_driver.in_set(FrameGraphSystems::Setup),
             )
             .init_resource::<FrameGraphs>()
-            .init_resource::<RenderGraph>();
+            .init_resource::<RenderGraph>()
+            .init_resource::<RenderGraphErrorPolicy>();
+    }
+}
+
+/// What to do with a view whose [`RenderGraph`] pipeline failed, see
+/// [`RenderGraphRunError`](super::RenderGraphRunError).
+///
+/// The failing node and its error are always logged. Whatever the pipeline recorded for the view
+/// before failing is discarded, as it may leave its target partially rendered.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub enum RenderGraphErrorPolicy {
+    /// Skips the view, leaving its target as it was.
+    #[default]
+    Skip,
+    /// Skips the view and clears its output texture to the given color, so the failure is
+    /// obvious instead of presenting garbage.
+    ClearToError(LinearRgba),
+    /// Skips the remaining views, discarding everything recorded this frame. Windows are cleared
+    /// to the [`ClearColor`] as if no camera rendered to them.
+    AbortFrame,
+}
+
+impl RenderGraphErrorPolicy {
+    /// The default color of [`RenderGraphErrorPolicy::ClearToError`].
+    pub const ERROR_COLOR: LinearRgba = LinearRgba::rgb(1.0, 0.0, 1.0);
+
+    /// [`RenderGraphErrorPolicy::ClearToError`] with the default [`ERROR_COLOR`](Self::ERROR_COLOR).
+    pub const fn clear_to_error() -> Self {
+        Self::ClearToError(Self::ERROR_COLOR)
     }
 }
 
@@ -125,6 +155,8 @@ pub fn camera_driver(world: &mut World) {
     };
 
     let mut camera_windows = EntityHashSet::default();
+    let error_policy = *world.resource::<RenderGraphErrorPolicy>();
+    let mut views_to_clear = Vec::new();
 
     for root_view in root_views {
         let mut run_pipeline = true;
@@ -180,11 +212,30 @@ pub fn camera_driver(world: &mut World) {
             };
 
             if let Err(e) = render_graph.run(&schedule, &mut graph, world) {
-                bevy_log::error!("Render pipeline run error: {}", e);
+                bevy_log::error!("Render pipeline run error for {}: {}", root_view, e);
+                frame_graph.reset();
+
+                match error_policy {
+                    RenderGraphErrorPolicy::Skip => {}
+                    RenderGraphErrorPolicy::ClearToError(color) => {
+                        if let Some(view_target) = world.get::<ViewTarget>(view_entity) {
+                            views_to_clear.push((view_target.out_texture().clone(), color));
+                        }
+                    }
+                    RenderGraphErrorPolicy::AbortFrame => {
+                        for frame_graph in frame_graphs.values_mut() {
+                            frame_graph.reset();
+                        }
+                        // Nothing is rendered to the windows of the discarded views anymore.
+                        camera_windows.clear();
+                        break;
+                    }
+                }
             }
         }
     }
 
+    clear_texture_views(world, "render_graph_error_clear_pass", &views_to_clear);
     handle_uncovered_swap_chains(world, &camera_windows);
 
     world.insert_resource(frame_graphs);
@@ -207,7 +258,16 @@ fn handle_uncovered_swap_chains(world: &mut World, camera_windows: &EntityHashSe
             .collect()
     };
 
-    if windows_to_clear.is_empty() {
+    clear_texture_views(world, "no_camera_clear_pass", &windows_to_clear);
+}
+
+/// Clears each texture view to its color.
+fn clear_texture_views(
+    world: &World,
+    label: &'static str,
+    texture_views: &[(TextureView, LinearRgba)],
+) {
+    if texture_views.is_empty() {
         return;
     }
 
@@ -216,14 +276,14 @@ fn handle_uncovered_swap_chains(world: &mut World, camera_windows: &EntityHashSe
 
     let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor::default());
 
-    for (swap_chain_texture, clear_color) in &windows_to_clear {
+    for (texture_view, clear_color) in texture_views {
         #[cfg(feature = "trace")]
-        let _span = bevy_log::info_span!("no_camera_clear_pass").entered();
+        let _span = bevy_log::info_span!("clear_texture_view", label).entered();
 
         let pass_descriptor = RenderPassDescriptor {
-            label: Some("no_camera_clear_pass"),
+            label: Some(label),
             color_attachments: &[Some(RenderPassColorAttachment {
-                view: swap_chain_texture,
+                view: texture_view,
                 depth_slice: None,
                 resolve_target: None,
                 ops: Operations {
@@ -1,5 +1,8 @@
 use crate::{
-    diagnostic::internal::{Pass, PassKind, WritePipelineStatistics, WriteTimestamp},
+    diagnostic::{
+        FrameStatisticsCounters, RenderFrameStatistics,
+        internal::{Pass, PassKind, WritePipelineStatistics, WriteTimestamp},
+    },
     render_resource::{
         BindGroup, BindGroupId, Buffer, BufferId, BufferSlice, RenderPipeline, RenderPipelineId,
     },
@@ -132,6 +135,8 @@ impl DrawState {
 pub struct TrackedRenderPass<'a> {
     pass: RenderPass<'a>,
     state: DrawState,
+    statistics: RenderFrameStatistics,
+    frame_statistics: FrameStatisticsCounters,
 }
 
 impl<'a> TrackedRenderPass<'a> {
@@ -147,9 +152,20 @@ impl<'a> TrackedRenderPass<'a> {
                 ..default()
             },
             pass,
+            statistics: default(),
+            frame_statistics: device.frame_statistics().clone(),
         }
     }
 
+    /// Returns the work recorded through this pass so far, e.g. the number of draw calls and of
+    /// state changes that were skipped.
+    ///
+    /// The statistics are added to the [`RenderDevice::frame_statistics`] of the device the pass
+    /// was created with when it is dropped.
+    pub fn statistics(&self) -> &RenderFrameStatistics {
+        &self.statistics
+    }
+
     /// Returns the wgpu [`RenderPass`].
     ///
     /// Function invalidates internal tracking state,
@@ -168,6 +184,7 @@ impl<'a> TrackedRenderPass<'a> {
         if self.state.is_pipeline_set(pipeline.id()) {
             return;
         }
+        self.statistics.pipeline_switches += 1;
         self.pass.set_pipeline(pipeline);
         self.state.set_pipeline(pipeline.id());
     }
@@ -194,6 +211,7 @@ impl<'a> TrackedRenderPass<'a> {
                 "set bind_group {} (already set): {:?} ({:?})",
                 index, bind_group, dynamic_uniform_indices
             );
+            self.statistics.bind_group_sets_elided += 1;
             return;
         }
         #[cfg(feature = "detailed_trace")]
@@ -202,6 +220,7 @@ impl<'a> TrackedRenderPass<'a> {
             index, bind_group, dynamic_uniform_indices
         );
 
+        self.statistics.bind_group_sets += 1;
         self.pass
             .set_bind_group(index as u32, bind_group, dynamic_uniform_indices);
         self.state
@@ -271,6 +290,8 @@ impl<'a> TrackedRenderPass<'a> {
     pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
         #[cfg(feature = "detailed_trace")]
         trace!("draw: {:?} {:?}", vertices, instances);
+        self.statistics.draw_calls += 1;
+        self.statistics.instances += instances.len() as u64;
         self.pass.draw(vertices, instances);
     }
 
@@ -284,6 +305,8 @@ impl<'a> TrackedRenderPass<'a> {
             "draw indexed: {:?} {} {:?}",
             indices, base_vertex, instances
         );
+        self.statistics.draw_calls += 1;
+        self.statistics.instances += instances.len() as u64;
         self.pass.draw_indexed(indices, base_vertex, instances);
     }
 
@@ -307,6 +330,7 @@ impl<'a> TrackedRenderPass<'a> {
     pub fn draw_indirect(&mut self, indirect_buffer: &'a Buffer, indirect_offset: u64) {
         #[cfg(feature = "detailed_trace")]
         trace!("draw indirect: {:?} {}", indirect_buffer, indirect_offset);
+        self.statistics.draw_calls += 1;
         self.pass.draw_indirect(indirect_buffer, indirect_offset);
     }
 
@@ -335,6 +359,7 @@ impl<'a> TrackedRenderPass<'a> {
             "draw indexed indirect: {:?} {}",
             indirect_buffer, indirect_offset
         );
+        self.statistics.draw_calls += 1;
         self.pass
             .draw_indexed_indirect(indirect_buffer, indirect_offset);
     }
@@ -367,6 +392,7 @@ impl<'a> TrackedRenderPass<'a> {
             "multi draw indirect: {:?} {}, {}x",
             indirect_buffer, indirect_offset, count
         );
+        self.statistics.draw_calls += u64::from(count);
         self.pass
             .multi_draw_indirect(indirect_buffer, indirect_offset, count);
     }
@@ -406,6 +432,8 @@ impl<'a> TrackedRenderPass<'a> {
             "multi draw indirect count: {:?} {}, ({:?} {})x, max {}x",
             indirect_buffer, indirect_offset, count_buffer, count_offset, max_count
         );
+        // The actual count is only known on the GPU.
+        self.statistics.draw_calls += 1;
         self.pass.multi_draw_indirect_count(
             indirect_buffer,
             indirect_offset,
@@ -445,6 +473,7 @@ impl<'a> TrackedRenderPass<'a> {
             "multi draw indexed indirect: {:?} {}, {}x",
             indirect_buffer, indirect_offset, count
         );
+        self.statistics.draw_calls += u64::from(count);
         self.pass
             .multi_draw_indexed_indirect(indirect_buffer, indirect_offset, count);
     }
@@ -486,6 +515,8 @@ impl<'a> TrackedRenderPass<'a> {
             "multi draw indexed indirect count: {:?} {}, ({:?} {})x, max {}x",
             indirect_buffer, indirect_offset, count_buffer, count_offset, max_count
         );
+        // The actual count is only known on the GPU.
+        self.statistics.draw_calls += 1;
         self.pass.multi_draw_indexed_indirect_count(
             indirect_buffer,
             indirect_offset,
@@ -620,6 +651,12 @@ impl<'a> TrackedRenderPass<'a> {
     }
 }
 
+impl Drop for TrackedRenderPass<'_> {
+    fn drop(&mut self) {
+        self.frame_statistics.add(&self.statistics);
+    }
+}
+
 impl WriteTimestamp for TrackedRenderPass<'_> {
     fn write_timestamp(&mut self, query_set: &QuerySet, index: u32) {
         self.pass.write_timestamp(query_set, index);
@@ -639,3 +676,128 @@ impl WritePipelineStatistics for TrackedRenderPass<'_> {
 impl Pass for TrackedRenderPass<'_> {
     const KIND: PassKind = PassKind::Render;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DrawState, TrackedRenderPass};
+    use crate::{
+        diagnostic::RenderFrameStatistics,
+        render_resource::{BindGroupId, RenderPipelineId},
+        renderer::noop_render_resources,
+    };
+    use wgpu::{
+        BufferDescriptor, BufferUsages, Extent3d, Operations, RenderPassColorAttachment,
+        RenderPassDescriptor, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    };
+
+    #[test]
+    fn redundant_state_is_detected() {
+        let mut state = DrawState {
+            bind_groups: vec![(None, Vec::new()); 2],
+            ..Default::default()
+        };
+        let pipeline = RenderPipelineId::new();
+        let bind_group = BindGroupId::new();
+
+        assert!(!state.is_pipeline_set(pipeline));
+        state.set_pipeline(pipeline);
+        assert!(state.is_pipeline_set(pipeline));
+        assert!(!state.is_pipeline_set(RenderPipelineId::new()));
+
+        state.set_bind_group(1, bind_group, &[256]);
+        assert!(state.is_bind_group_set(1, bind_group, &[256]));
+        // A different dynamic offset or slot must be set again.
+        assert!(!state.is_bind_group_set(1, bind_group, &[512]));
+        assert!(!state.is_bind_group_set(0, bind_group, &[256]));
+        assert!(!state.is_bind_group_set(2, bind_group, &[256]));
+        assert!(!state.is_bind_group_set(1, bind_group, &[]));
+        assert!(!state.is_bind_group_set(1, bind_group, &[256, 0]));
+
+        state.set_bind_group(1, bind_group, &[]);
+        assert!(state.is_bind_group_set(1, bind_group, &[]));
+        assert!(!state.is_bind_group_set(1, bind_group, &[256]));
+
+        state.reset_tracking();
+        assert!(!state.is_pipeline_set(pipeline));
+        assert!(!state.is_bind_group_set(1, bind_group, &[256]));
+    }
+
+    #[test]
+    fn passes_count_into_the_statistics_of_their_device() {
+        let resources = noop_render_resources(wgpu::Features::empty());
+        let other = noop_render_resources(wgpu::Features::empty());
+        let (device, queue) = (&resources.0, &resources.1);
+
+        let layout = device.create_bind_group_layout("empty", &[]);
+        let bind_group = device.create_bind_group("empty", &layout, &[]);
+        let texture = device.create_texture(&TextureDescriptor {
+            label: None,
+            size: Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Drawing without a pipeline is invalid, which is only reported when the commands are
+        // finished.
+        let scope = device
+            .wgpu_device()
+            .push_error_scope(wgpu::ErrorFilter::Validation);
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+            let mut pass = TrackedRenderPass::new(device, pass);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..2);
+            assert_eq!(pass.statistics().draw_calls, 1);
+        }
+        drop(encoder.finish());
+        drop(scope.pop());
+        queue.write_buffer_counted(&buffer, 0, &[0; 16]);
+
+        assert_eq!(
+            device.frame_statistics().take(),
+            RenderFrameStatistics {
+                draw_calls: 1,
+                instances: 2,
+                bind_group_sets: 1,
+                bind_group_sets_elided: 1,
+                buffer_writes: 1,
+                bytes_uploaded: 16,
+                ..Default::default()
+            }
+        );
+        // Every renderer counts its own work.
+        assert_eq!(
+            other.0.frame_statistics().take(),
+            RenderFrameStatistics::default()
+        );
+    }
+}
@@ -73,6 +73,7 @@ pub use bevy_material_macros::DrawFunctionLabel;
 pub use bevy_material_macros::ShaderLabel;
 use bevy_render::renderer::RenderAdapterInfo;
 use core::{
+    cmp::Ordering,
     fmt::Debug,
     hash::Hash,
     iter,
@@ -1848,6 +1849,14 @@ where
         I::sort(&mut self.items);
     }
 
+    /// Sorts all of its [`PhaseItem`]s with the given comparator instead of
+    /// [`SortedPhaseItem::sort`].
+    ///
+    /// The sort is stable, so items that compare equal keep their insertion order.
+    pub fn sort_with(&mut self, mut compare: impl FnMut(&I, &I) -> Ordering) {
+        self.items.sort_by(|_, a, _, b| compare(a, b));
+    }
+
     /// An [`Iterator`] through the associated [`Entity`] for each [`PhaseItem`] in order.
     #[inline]
     pub fn iter_entities(&'_ self) -> impl Iterator<Item = Entity> + '_ {
@@ -2089,11 +2098,10 @@ pub trait SortedPhaseItem: PhaseItem {
     /// Determines the order in which the items are drawn.
     fn sort_key(&self) -> Self::SortKey;
 
-    /// Sorts a slice of phase items into render order. Generally if the same type
-    /// is batched this should use a stable sort like [`slice::sort_by_key`].
-    /// In almost all other cases, this should not be altered from the default,
-    /// which uses an unstable sort, as this provides the best balance of CPU and GPU
-    /// performance.
+    /// Sorts a slice of phase items into render order. The default uses a stable
+    /// sort, so items with equal sort keys are drawn in the order they were queued
+    /// and don't flicker between frames. Implementers that don't need this, e.g.
+    /// because equal keys can't overlap on screen, can use an unstable sort instead.
     ///
     /// Implementers can optionally not sort the list at all. This is generally advisable if and
     /// only if the renderer supports a depth prepass, which is by default not supported by
@@ -2103,7 +2111,7 @@ pub trait SortedPhaseItem: PhaseItem {
     /// It's advised to always profile for performance changes when changing this implementation.
     #[inline]
     fn sort(items: &mut IndexMap<(Entity, MainEntity), Self, EntityHash>) {
-        items.sort_unstable_by_key(|_, value| Self::sort_key(value));
+        items.sort_by(|_, a, _, b| a.sort_key().cmp(&b.sort_key()));
     }
 
     /// Populates whatever internal fields are necessary in order to perform the
@@ -2167,11 +2175,41 @@ impl<P: CachedRenderPipelinePhaseItem> RenderCommand<P> for SetItemPipeline {
     }
 }
 
+/// A comparator that replaces [`SortedPhaseItem::sort`] for all
+/// [`SortedRenderPhase`]s of `I` in [`sort_phase_system`].
+///
+/// Insert this as a render world resource, e.g. to sort transparent items back
+/// to front and then by pipeline to reduce state changes.
+#[derive(Resource)]
+pub struct SortedPhaseComparator<I>(Box<dyn Fn(&I, &I) -> Ordering + Send + Sync>)
+where
+    I: SortedPhaseItem;
+
+impl<I> SortedPhaseComparator<I>
+where
+    I: SortedPhaseItem,
+{
+    /// Creates a comparator from a function ordering two phase items. Items it considers equal
+    /// keep their previous order.
+    pub fn new(compare: impl Fn(&I, &I) -> Ordering + Send + Sync + 'static) -> Self {
+        Self(Box::new(compare))
+    }
+
+    /// Compares two phase items.
+    pub fn compare(&self, a: &I, b: &I) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
 /// This system sorts the [`PhaseItem`]s of all [`SortedRenderPhase`]s of this
 /// type.
+///
+/// If a [`SortedPhaseComparator`] for this type exists, it is used instead of
+/// [`SortedPhaseItem::sort`].
 pub fn sort_phase_system<I>(
     views: Query<&ExtractedView>,
     mut render_phases: ResMut<ViewSortedRenderPhases<I>>,
+    comparator: Option<Res<SortedPhaseComparator<I>>>,
 ) where
     I: SortedPhaseItem,
 {
@@ -2180,7 +2218,10 @@ pub fn sort_phase_system<I>(
             continue;
         };
         phase.recalculate_sort_keys(view);
-        phase.sort();
+        match &comparator {
+            Some(comparator) => phase.sort_with(|a, b| comparator.compare(a, b)),
+            None => phase.sort(),
+        }
     }
 }
 
@@ -2230,9 +2271,192 @@ impl RenderBin {
 
 #[cfg(test)]
 mod tests {
+    use core::ops::Range;
+
+    use bevy_ecs::{
+        entity::{Entity, EntityHash, EntityIndex},
+        system::RunSystemOnce,
+        world::World,
+    };
+    use bevy_material::labels::DrawFunctionId;
+    use indexmap::IndexMap;
     use proptest_derive::Arbitrary;
 
-    use crate::render_phase::GpuRenderBinnedMeshInstance;
+    use super::{
+        PhaseItem, PhaseItemExtraIndex, SortedPhaseComparator, SortedPhaseItem,
+        ViewSortedRenderPhases, sort_phase_system,
+    };
+    use crate::{
+        render_phase::GpuRenderBinnedMeshInstance,
+        sync_world::MainEntity,
+        view::{ExtractedView, RetainedViewEntity},
+    };
+
+    struct MockSortedPhaseItem {
+        entity: Entity,
+        distance: u32,
+        pipeline: u32,
+        batch_range: Range<u32>,
+        extra_index: PhaseItemExtraIndex,
+    }
+
+    impl PhaseItem for MockSortedPhaseItem {
+        fn entity(&self) -> Entity {
+            self.entity
+        }
+
+        fn main_entity(&self) -> MainEntity {
+            MainEntity::from(self.entity)
+        }
+
+        fn draw_function(&self) -> DrawFunctionId {
+            unimplemented!()
+        }
+
+        fn batch_range(&self) -> &Range<u32> {
+            &self.batch_range
+        }
+
+        fn batch_range_mut(&mut self) -> &mut Range<u32> {
+            &mut self.batch_range
+        }
+
+        fn extra_index(&self) -> PhaseItemExtraIndex {
+            self.extra_index.clone()
+        }
+
+        fn batch_range_and_extra_index_mut(
+            &mut self,
+        ) -> (&mut Range<u32>, &mut PhaseItemExtraIndex) {
+            (&mut self.batch_range, &mut self.extra_index)
+        }
+    }
+
+    impl SortedPhaseItem for MockSortedPhaseItem {
+        type SortKey = u32;
+
+        fn sort_key(&self) -> Self::SortKey {
+            self.distance
+        }
+
+        fn recalculate_sort_keys(
+            _: &mut IndexMap<(Entity, MainEntity), Self, EntityHash>,
+            _: &ExtractedView,
+        ) {
+        }
+
+        fn indexed(&self) -> bool {
+            false
+        }
+    }
+
+    fn mock_entities(count: u32) -> Vec<Entity> {
+        (0..count)
+            .map(|index| Entity::from_index(EntityIndex::from_raw_u32(index).unwrap()))
+            .collect()
+    }
+
+    /// A world with a view whose phase has an item of the given distance and pipeline for each
+    /// entity, in that order.
+    fn sorted_phase_world(
+        entities: &[Entity],
+        keys: impl IntoIterator<Item = (u32, u32)>,
+    ) -> World {
+        let view = RetainedViewEntity::new(MainEntity::from(entities[0]), None, 0);
+        let mut phases = ViewSortedRenderPhases::<MockSortedPhaseItem>::default();
+        phases.prepare_for_new_frame(view);
+        let phase = phases.get_mut(&view).unwrap();
+        for (entity, (distance, pipeline)) in entities.iter().zip(keys) {
+            phase.add(MockSortedPhaseItem {
+                entity: *entity,
+                distance,
+                pipeline,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+            });
+        }
+
+        let mut world = World::new();
+        world.insert_resource(phases);
+        world.spawn(ExtractedView {
+            retained_view_entity: view,
+            clip_from_view: Default::default(),
+            world_from_view: Default::default(),
+            clip_from_world: None,
+            target_format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            viewport: Default::default(),
+            color_grading: Default::default(),
+            invert_culling: false,
+        });
+        world
+    }
+
+    /// The entities of the phase of the only view in `world`, in order.
+    fn sorted_phase_order(world: &World) -> Vec<Entity> {
+        let phases = world.resource::<ViewSortedRenderPhases<MockSortedPhaseItem>>();
+        phases
+            .values()
+            .flat_map(|phase| phase.iter_entities())
+            .collect()
+    }
+
+    #[test]
+    fn sort_phase_system_keeps_the_order_of_equal_keys() {
+        let entities = mock_entities(6);
+        // Only two distinct keys, queued interleaved.
+        let mut world = sorted_phase_world(&entities, (0..6).map(|index| (index % 2, 0)));
+        let order = sorted_phase_order;
+
+        world
+            .run_system_once(sort_phase_system::<MockSortedPhaseItem>)
+            .unwrap();
+        let expected = [0, 2, 4, 1, 3, 5].map(|index| entities[index]);
+        assert_eq!(order(&world), expected);
+
+        // Sorting again doesn't reorder the items either.
+        world
+            .run_system_once(sort_phase_system::<MockSortedPhaseItem>)
+            .unwrap();
+        assert_eq!(order(&world), expected);
+    }
+
+    #[test]
+    fn sorted_phase_custom_comparator() {
+        let entities = mock_entities(4);
+        let mut world = sorted_phase_world(&entities, [(1, 0), (3, 1), (3, 0), (2, 1)]);
+        let sort = |world: &mut World| {
+            world
+                .run_system_once(sort_phase_system::<MockSortedPhaseItem>)
+                .unwrap();
+            sorted_phase_order(world)
+        };
+
+        // Back to front, then by pipeline.
+        world.insert_resource(SortedPhaseComparator::new(
+            |a: &MockSortedPhaseItem, b: &MockSortedPhaseItem| {
+                b.distance
+                    .cmp(&a.distance)
+                    .then(a.pipeline.cmp(&b.pipeline))
+            },
+        ));
+        assert_eq!(
+            sort(&mut world),
+            [entities[2], entities[1], entities[3], entities[0]]
+        );
+
+        // Without a comparator, the sort key is used again.
+        world.remove_resource::<SortedPhaseComparator<MockSortedPhaseItem>>();
+        let order = sort(&mut world);
+        assert_eq!(order[..2], [entities[0], entities[3]]);
+        // Equal keys keep the order of the previous sort.
+        assert_eq!(order[2..], [entities[2], entities[1]]);
+
+        // Sorting with a comparator is stable, items with the same key keep their order.
+        world.insert_resource(SortedPhaseComparator::new(
+            |a: &MockSortedPhaseItem, b: &MockSortedPhaseItem| a.sort_key().cmp(&b.sort_key()),
+        ));
+        assert_eq!(sort(&mut world), order);
+    }
 
     /// A `proptest`-based randomized test for `RenderMultidrawableBatchSet`.
     ///
@@ -180,7 +180,7 @@ impl<T: NoUninit> RawBufferVec<T> {
         if let Some(buffer) = &self.buffer {
             let range = 0..self.item_size * self.values.len();
             let bytes: &[u8] = must_cast_slice(&self.values);
-            queue.write_buffer(buffer, 0, &bytes[range]);
+            queue.write_buffer_counted(buffer, 0, &bytes[range]);
         }
     }
 
@@ -207,7 +207,7 @@ impl<T: NoUninit> RawBufferVec<T> {
         if let Some(buffer) = &self.buffer {
             // Cast only the bytes we need to write
             let bytes: &[u8] = must_cast_slice(&self.values[range.start..range.end]);
-            render_queue.write_buffer(buffer, (range.start * self.item_size) as u64, bytes);
+            render_queue.write_buffer_counted(buffer, (range.start * self.item_size) as u64, bytes);
             Ok(())
         } else {
             Err(WriteBufferRangeError::BufferNotInitialized)
@@ -439,7 +439,7 @@ where
                 (range.end - range.start) * size_of::<T::Blob>(),
             );
             let start_offset = range.start as u64 * size_of::<T::Blob>() as u64;
-            queue.write_buffer(buffer, start_offset, bytes);
+            queue.write_buffer_counted(buffer, start_offset, bytes);
         }
     }
 
@@ -634,7 +634,7 @@ where
         self.reserve(self.data.len() / u64::from(T::min_size()) as usize, device);
 
         let Some(buffer) = &self.buffer else { return };
-        queue.write_buffer(buffer, 0, &self.data);
+        queue.write_buffer_counted(buffer, 0, &self.data);
     }
 
     /// Queues writing of data from system RAM to VRAM using the [`RenderDevice`]
@@ -660,7 +660,7 @@ where
         }
         if let Some(buffer) = &self.buffer {
             let bytes = &self.data[range.start..range.end];
-            render_queue.write_buffer(buffer, (range.start * item_size) as u64, bytes);
+            render_queue.write_buffer_counted(buffer, (range.start * item_size) as u64, bytes);
             Ok(())
         } else {
             Err(WriteBufferRangeError::BufferNotInitialized)
@@ -909,7 +909,7 @@ where
         let Some(ref buffer) = self.buffer else {
             return;
         };
-        render_queue.write_buffer(buffer, 0, must_cast_slice(&self.values[..]));
+        render_queue.write_buffer_counted(buffer, 0, must_cast_slice(&self.values[..]));
     }
 
     /// Returns true if this buffer is empty: i.e. if [`Self::len`] would return
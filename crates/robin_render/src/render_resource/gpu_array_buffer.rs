@@ -43,12 +43,21 @@ pub enum GpuArrayBuffer<T: GpuArrayBufferable> {
 impl<T: GpuArrayBufferable> GpuArrayBuffer<T> {
     pub fn new(limits: &Limits) -> Self {
         if limits.max_storage_buffers_per_shader_stage == 0 {
-            GpuArrayBuffer::Uniform(BatchedUniformBuffer::new(limits))
+            Self::new_uniform(limits)
         } else {
             GpuArrayBuffer::Storage(BufferVec::new(BufferUsages::STORAGE))
         }
     }
 
+    /// Creates a buffer using the uniform buffer fallback even if storage buffers are supported,
+    /// e.g. to test the WebGL2 path on other platforms.
+    ///
+    /// The bind group layout must then use a dynamic offset uniform buffer as well, unlike the one
+    /// returned by [`GpuArrayBuffer::binding_layout`].
+    pub fn new_uniform(limits: &Limits) -> Self {
+        GpuArrayBuffer::Uniform(BatchedUniformBuffer::new(limits))
+    }
+
     pub fn clear(&mut self) {
         match self {
             GpuArrayBuffer::Uniform(buffer) => buffer.clear(),
@@ -116,3 +125,63 @@ pub struct GpuArrayBufferIndex<T: GpuArrayBufferable> {
     pub dynamic_offset: Option<NonMaxU32>,
     pub element_type: PhantomData<T>,
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::Vec4;
+    use wgpu::Limits;
+
+    use super::GpuArrayBuffer;
+
+    fn pushed_indices(buffer: &mut GpuArrayBuffer<Vec4>) -> Vec<(u32, Option<u32>)> {
+        (0..5)
+            .map(|_| {
+                let index = buffer.push(Vec4::ONE);
+                (index.index, index.dynamic_offset.map(|offset| offset.get()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn storage_indices_are_contiguous() {
+        let mut buffer = GpuArrayBuffer::new(&Limits::default());
+        assert!(matches!(buffer, GpuArrayBuffer::Storage(_)));
+        assert_eq!(
+            pushed_indices(&mut buffer),
+            [(0, None), (1, None), (2, None), (3, None), (4, None)]
+        );
+    }
+
+    #[test]
+    fn uniform_fallback_is_batched() {
+        // Room for 4 elements per batch, and batches bound at 256 byte offsets.
+        let limits = Limits {
+            max_uniform_buffer_binding_size: 64,
+            min_uniform_buffer_offset_alignment: 256,
+            ..Limits::default()
+        };
+        assert_eq!(GpuArrayBuffer::<Vec4>::batch_size(&limits), None);
+
+        let mut buffer = GpuArrayBuffer::new_uniform(&limits);
+        assert_eq!(
+            pushed_indices(&mut buffer),
+            [
+                (0, Some(0)),
+                (1, Some(0)),
+                (2, Some(0)),
+                (3, Some(0)),
+                (0, Some(256))
+            ]
+        );
+
+        let limits = Limits {
+            max_storage_buffers_per_shader_stage: 0,
+            ..limits
+        };
+        assert_eq!(GpuArrayBuffer::<Vec4>::batch_size(&limits), Some(4));
+        assert!(matches!(
+            GpuArrayBuffer::<Vec4>::new(&limits),
+            GpuArrayBuffer::Uniform(_)
+        ));
+    }
+}
@@ -10,6 +10,7 @@ mod gpu_array_buffer;
 mod pipeline;
 mod pipeline_cache;
 mod pipeline_specializer;
+mod shader_validation;
 mod sparse_buffer_vec;
 mod specializer;
 mod storage_buffer;
@@ -27,6 +28,7 @@ pub use gpu_array_buffer::*;
 pub use pipeline::*;
 pub use pipeline_cache::*;
 pub use pipeline_specializer::*;
+pub use shader_validation::*;
 pub use sparse_buffer_vec::*;
 pub use specializer::*;
 pub use storage_buffer::*;
@@ -4,28 +4,38 @@ use bevy_material::descriptor::{
 };
 
 use crate::{
-    Extract,
+    Extract, Render, RenderApp, RenderSystems,
+    error_handler::RenderDeviceRecreated,
     render_resource::*,
-    renderer::{RenderAdapter, RenderDevice, WgpuWrapper},
+    renderer::{DeviceGeneration, RenderAdapter, RenderDevice, WgpuWrapper},
 };
 use alloc::{borrow::Cow, sync::Arc};
+use bevy_app::{App, Plugin, PreUpdate};
 use bevy_asset::{AssetEvent, AssetId, Assets, Handle};
 use bevy_ecs::{
     message::MessageReader,
+    observer::On,
     resource::Resource,
+    schedule::IntoScheduleConfigs,
     system::{Res, ResMut},
 };
-use bevy_log::error;
-use bevy_platform::collections::{HashMap, HashSet};
+use bevy_log::{error, warn};
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    hash::FixedHasher,
+};
 use bevy_shader::{
     CachedPipelineId, Shader, ShaderCache, ShaderCacheError, ShaderCacheSource, ShaderDefVal,
     ValidateShader,
 };
-use bevy_tasks::Task;
+use bevy_tasks::{IoTaskPool, Task, TaskPool};
 use bevy_utils::default;
-use core::{future::Future, mem};
-use std::sync::{Mutex, PoisonError};
-use wgpu::{PipelineCompilationOptions, VertexBufferLayout as RawVertexBufferLayout};
+use core::{future::Future, hash::BuildHasher, mem};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+use wgpu::{AdapterInfo, PipelineCompilationOptions, VertexBufferLayout as RawVertexBufferLayout};
 
 /// A pipeline defining the data layout and shader logic for a specific GPU task.
 ///
@@ -39,6 +49,23 @@ pub enum Pipeline {
 pub struct CachedPipeline {
     pub descriptor: PipelineDescriptor,
     pub state: CachedPipelineState,
+    /// The pipeline created before one of its shaders changed, used until the pipeline is
+    /// recreated successfully with the new shader.
+    previous: Option<Pipeline>,
+}
+
+impl CachedPipeline {
+    /// The pipeline GPU object to use, if any.
+    ///
+    /// This is the one in [`CachedPipeline::state`] once it's created. While the pipeline is being
+    /// recreated after a shader changed, or if the changed shader failed to compile, this is the
+    /// pipeline created with the previous version of the shader instead.
+    pub fn pipeline(&self) -> Option<&Pipeline> {
+        match &self.state {
+            CachedPipelineState::Ok(pipeline) => Some(pipeline),
+            _ => self.previous.as_ref(),
+        }
+    }
 }
 
 /// State of a cached pipeline inserted into a [`PipelineCache`].
@@ -166,6 +193,11 @@ fn load_module(
     Ok(shader_module)
 }
 
+/// Deduplicates [`BindGroupLayout`]s, see [`PipelineCache::get_bind_group_layout()`].
+///
+/// [`BindGroupLayoutDescriptor`] is hashable, so it is used as the key directly. The cache is
+/// shared behind a [`Mutex`], so layouts can be requested from parallel systems and pipeline
+/// creation tasks.
 #[derive(Default)]
 struct BindGroupLayoutCache {
     bgls: HashMap<BindGroupLayoutDescriptor, BindGroupLayout>,
@@ -195,8 +227,8 @@ impl BindGroupLayoutCache {
 /// pipeline object is deferred to the [`RenderSystems::Render`] step, just before the render
 /// graph starts being processed, as this requires access to the GPU.
 ///
-/// Note that the cache does not perform automatic deduplication of identical pipelines. It is
-/// up to the user not to insert the same pipeline twice to avoid wasting GPU resources.
+/// Queueing a pipeline identical to one already in the cache returns the ID of the cached one,
+/// so the same pipeline is never created twice.
 ///
 /// [`RenderSystems::Render`]: crate::RenderSystems::Render
 #[derive(Resource)]
@@ -205,15 +237,25 @@ pub struct PipelineCache {
     bindgroup_layout_cache: Arc<Mutex<BindGroupLayoutCache>>,
     shader_cache: Arc<Mutex<ShaderCache<WgpuWrapper<ShaderModule>, RenderDevice>>>,
     device: RenderDevice,
+    render_adapter: RenderAdapter,
     pipelines: Vec<CachedPipeline>,
     waiting_pipelines: HashSet<CachedPipelineId>,
     new_pipelines: Mutex<Vec<CachedPipeline>>,
+    /// The IDs of the cached and queued pipelines, by [`descriptor_hash`] of their descriptor.
+    ///
+    /// Locked while `new_pipelines` is locked, never on its own.
+    pipeline_ids: Mutex<HashMap<u64, Vec<CachedPipelineId>>>,
     global_shader_defs: Vec<ShaderDefVal>,
     /// If `true`, disables asynchronous pipeline compilation.
     /// This has no effect on macOS, wasm, or without the `multi_threaded` feature.
     pub(crate) synchronous_pipeline_compilation: bool,
     /// If `true`, the shader cache needs to be repopulated from the main world's `Assets<Shader>`.
     needs_shader_reload: bool,
+    disk_cache: Option<PipelineDiskCache>,
+    /// The directory the [`PipelineDiskCache`] is stored in, to load it again for a new device.
+    cache_dir: Option<PathBuf>,
+    /// The generation of the device the pipelines are created on.
+    pub(crate) device_generation: DeviceGeneration,
 }
 
 impl PipelineCache {
@@ -222,11 +264,84 @@ impl PipelineCache {
         self.pipelines.iter()
     }
 
+    /// The [`DeviceGeneration`] of the [`RenderDevice`] the pipelines are created on.
+    ///
+    /// The cache is recreated for every new [`RenderDevice`], which creates its pipelines again.
+    /// Caches of pipeline ids, like
+    /// [`SpecializedRenderPipelines`](crate::render_resource::SpecializedRenderPipelines), can
+    /// compare this to know when to specialize their pipelines again. A cache created outside of
+    /// the renderer, e.g. in tests, has the default generation.
+    pub fn generation(&self) -> DeviceGeneration {
+        self.device_generation
+    }
+
     /// Returns a iterator of the IDs of all currently waiting pipelines.
     pub fn waiting_pipelines(&self) -> impl Iterator<Item = CachedPipelineId> + '_ {
         self.waiting_pipelines.iter().copied()
     }
 
+    /// Get the state of a cached render or compute pipeline.
+    #[inline]
+    pub fn pipeline_state(&self, id: CachedPipelineId) -> &CachedPipelineState {
+        // If the pipeline id isn't in `pipelines`, it's queued in `new_pipelines`
+        self.pipelines
+            .get(id)
+            .map_or(&CachedPipelineState::Queued, |pipeline| &pipeline.state)
+    }
+
+    /// Returns `true` if no pipeline is queued or compiling anymore.
+    ///
+    /// Pipelines that failed to compile count as finished, they can be found with
+    /// [`PipelineCache::pipeline_errors()`]. Pipelines waiting for one of their shaders to load
+    /// count as pending.
+    pub fn all_ready(&self) -> bool {
+        self.pending_pipelines() == 0
+    }
+
+    /// Returns the number of pipelines queued or compiling, see [`PipelineCache::all_ready()`].
+    pub fn pending_pipelines(&self) -> usize {
+        self.waiting_pipelines.len()
+            + self
+                .new_pipelines
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .len()
+    }
+
+    /// Returns an iterator over the pipelines that failed to compile, and why.
+    ///
+    /// Pipelines waiting for one of their shaders to load aren't included, as they are retried.
+    pub fn pipeline_errors(
+        &self,
+    ) -> impl Iterator<Item = (CachedPipelineId, &ShaderCacheError)> + '_ {
+        self.pipelines
+            .iter()
+            .enumerate()
+            .filter_map(|(id, pipeline)| match &pipeline.state {
+                CachedPipelineState::Err(
+                    ShaderCacheError::ShaderNotLoaded(_)
+                    | ShaderCacheError::ShaderImportNotYetAvailable,
+                ) => None,
+                CachedPipelineState::Err(err) => Some((id, err)),
+                _ => None,
+            })
+    }
+
+    /// Wait for every queued pipeline to finish compiling.
+    ///
+    /// Pipelines waiting for one of their shaders to load can't be created yet and stay pending.
+    /// Shaders are added to the cache during extraction, so on the first frame this only covers
+    /// pipelines queued in the [`ExtractSchedule`](crate::ExtractSchedule) or later.
+    pub fn block_on_all_pipelines(&mut self) {
+        self.process_queue();
+        let waiting = self.waiting_pipelines.iter().copied().collect::<Vec<_>>();
+        for id in waiting {
+            self.block_on_pipeline(id);
+        }
+        // Moves the finished pipelines out of the waiting set.
+        self.process_queue();
+    }
+
     /// Create a new pipeline cache associated with the given render device.
     pub fn new(
         device: RenderDevice,
@@ -258,15 +373,80 @@ impl PipelineCache {
                 load_module,
             ))),
             device,
+            render_adapter,
             layout_cache: default(),
             bindgroup_layout_cache: default(),
             waiting_pipelines: default(),
             new_pipelines: default(),
+            pipeline_ids: default(),
             pipelines: default(),
             global_shader_defs,
             synchronous_pipeline_compilation,
             needs_shader_reload: true,
+            disk_cache: None,
+            cache_dir: None,
+            device_generation: DeviceGeneration::default(),
+        }
+    }
+
+    /// Loads the compiled pipelines from and writes them to `cache_dir`, see
+    /// [`WgpuSettings::pipeline_cache_dir`](crate::settings::WgpuSettings::pipeline_cache_dir).
+    ///
+    /// This has no effect if the device doesn't have [`wgpu::Features::PIPELINE_CACHE`] enabled,
+    /// or if `wgpu` doesn't support caching pipelines for its backend.
+    pub fn with_disk_cache(mut self, cache_dir: &Path) -> Self {
+        self.disk_cache =
+            PipelineDiskCache::load(&self.device, &self.render_adapter.get_info(), cache_dir);
+        self.cache_dir = Some(cache_dir.to_path_buf());
+        self
+    }
+
+    /// Moves the cache to a [`RenderDevice`] replacing a lost one.
+    ///
+    /// The shaders, layouts and pipelines created on the lost device are dropped. Every pipeline
+    /// is queued to be created again on the new device, keeping its ID, once its shaders are
+    /// reloaded from the main world.
+    fn recreate(
+        &mut self,
+        device: RenderDevice,
+        render_adapter: RenderAdapter,
+        device_generation: DeviceGeneration,
+    ) {
+        let mut cache = Self::new(
+            device,
+            render_adapter,
+            self.synchronous_pipeline_compilation,
+        );
+        if let Some(cache_dir) = &self.cache_dir {
+            cache = cache.with_disk_cache(cache_dir);
+        }
+        let previous = mem::replace(self, cache);
+        let mut pipelines = previous.pipelines;
+        pipelines.extend(
+            previous
+                .new_pipelines
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        for cached_pipeline in &mut pipelines {
+            cached_pipeline.state = CachedPipelineState::Queued;
+            cached_pipeline.previous = None;
         }
+        self.waiting_pipelines = (0..pipelines.len()).collect();
+        self.pipelines = pipelines;
+        self.pipeline_ids = previous.pipeline_ids;
+        self.device_generation = device_generation;
+    }
+
+    /// Recreates the [`PipelineCache`] for the new device when [`RenderDeviceRecreated`] is
+    /// triggered.
+    pub(crate) fn recreate_on_device_recreated(
+        recreated: On<RenderDeviceRecreated>,
+        mut cache: ResMut<Self>,
+        device: Res<RenderDevice>,
+        render_adapter: Res<RenderAdapter>,
+    ) {
+        cache.recreate(device.clone(), render_adapter.clone(), recreated.generation);
     }
 
     /// Get the state of a cached render pipeline.
@@ -334,9 +514,7 @@ impl PipelineCache {
     /// state with [`PipelineCache::get_render_pipeline_state()`].
     #[inline]
     pub fn get_render_pipeline(&self, id: CachedRenderPipelineId) -> Option<&RenderPipeline> {
-        if let CachedPipelineState::Ok(Pipeline::RenderPipeline(pipeline)) =
-            &self.pipelines.get(id.id())?.state
-        {
+        if let Some(Pipeline::RenderPipeline(pipeline)) = self.pipelines.get(id.id())?.pipeline() {
             Some(pipeline)
         } else {
             None
@@ -346,14 +524,30 @@ impl PipelineCache {
     /// Wait for a render pipeline to finish compiling.
     #[inline]
     pub fn block_on_render_pipeline(&mut self, id: CachedRenderPipelineId) {
-        if self.pipelines.len() <= id.id() {
+        self.block_on_pipeline(id.id());
+    }
+
+    /// Wait for a compute pipeline to finish compiling.
+    ///
+    /// Compilation errors are not raised here, check [`PipelineCache::get_compute_pipeline_state()`]
+    /// if [`PipelineCache::get_compute_pipeline()`] returns `None` afterwards.
+    #[inline]
+    pub fn block_on_compute_pipeline(&mut self, id: CachedComputePipelineId) {
+        self.block_on_pipeline(id.id());
+    }
+
+    fn block_on_pipeline(&mut self, id: CachedPipelineId) {
+        if self.pipelines.len() <= id {
             self.process_queue();
         }
 
-        let state = &mut self.pipelines[id.id()].state;
-        if let CachedPipelineState::Creating(task) = state {
-            *state = match bevy_tasks::block_on(task) {
-                Ok(p) => CachedPipelineState::Ok(p),
+        let cached_pipeline = &mut self.pipelines[id];
+        if let CachedPipelineState::Creating(task) = &mut cached_pipeline.state {
+            cached_pipeline.state = match bevy_tasks::block_on(task) {
+                Ok(p) => {
+                    cached_pipeline.previous = None;
+                    CachedPipelineState::Ok(p)
+                }
                 Err(e) => CachedPipelineState::Err(e),
             };
         }
@@ -368,9 +562,7 @@ impl PipelineCache {
     /// state with [`PipelineCache::get_compute_pipeline_state()`].
     #[inline]
     pub fn get_compute_pipeline(&self, id: CachedComputePipelineId) -> Option<&ComputePipeline> {
-        if let CachedPipelineState::Ok(Pipeline::ComputePipeline(pipeline)) =
-            &self.pipelines.get(id.id())?.state
-        {
+        if let Some(Pipeline::ComputePipeline(pipeline)) = self.pipelines.get(id.id())?.pipeline() {
             Some(pipeline)
         } else {
             None
@@ -379,8 +571,9 @@ impl PipelineCache {
 
     /// Insert a render pipeline into the cache, and queue its creation.
     ///
-    /// The pipeline is always inserted and queued for creation. There is no attempt to deduplicate it with
-    /// an already cached pipeline.
+    /// If an identical pipeline was already queued, its ID is returned instead. The descriptor is
+    /// only compared with the cached ones with the same hash, but is still hashed on every call, so
+    /// prefer caching IDs with a specializer over queueing descriptors every frame.
     ///
     /// # Returns
     ///
@@ -394,22 +587,16 @@ impl PipelineCache {
         &self,
         descriptor: RenderPipelineDescriptor,
     ) -> CachedRenderPipelineId {
-        let mut new_pipelines = self
-            .new_pipelines
-            .lock()
-            .unwrap_or_else(PoisonError::into_inner);
-        let id = CachedRenderPipelineId::new(self.pipelines.len() + new_pipelines.len());
-        new_pipelines.push(CachedPipeline {
-            descriptor: PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
-            state: CachedPipelineState::Queued,
-        });
-        id
+        CachedRenderPipelineId::new(self.queue_pipeline(
+            PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
+        ))
     }
 
     /// Insert a compute pipeline into the cache, and queue its creation.
     ///
-    /// The pipeline is always inserted and queued for creation. There is no attempt to deduplicate it with
-    /// an already cached pipeline.
+    /// If an identical pipeline was already queued, its ID is returned instead. The descriptor is
+    /// only compared with the cached ones with the same hash, but is still hashed on every call, so
+    /// prefer caching IDs with a specializer over queueing descriptors every frame.
     ///
     /// # Returns
     ///
@@ -423,18 +610,54 @@ impl PipelineCache {
         &self,
         descriptor: ComputePipelineDescriptor,
     ) -> CachedComputePipelineId {
+        CachedComputePipelineId::new(self.queue_pipeline(
+            PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor)),
+        ))
+    }
+
+    /// Queues the creation of the pipeline, or returns the ID of the cached or queued pipeline
+    /// with the same descriptor.
+    ///
+    /// Only the pipelines whose descriptor has the same [`descriptor_hash`] are compared.
+    fn queue_pipeline(&self, descriptor: PipelineDescriptor) -> CachedPipelineId {
         let mut new_pipelines = self
             .new_pipelines
             .lock()
             .unwrap_or_else(PoisonError::into_inner);
-        let id = CachedComputePipelineId::new(self.pipelines.len() + new_pipelines.len());
+        let mut pipeline_ids = self
+            .pipeline_ids
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let ids = pipeline_ids
+            .entry(descriptor_hash(&descriptor))
+            .or_default();
+        let cached = ids.iter().copied().find(|&id| {
+            let pipeline = match id.checked_sub(self.pipelines.len()) {
+                Some(new_id) => &new_pipelines[new_id],
+                None => &self.pipelines[id],
+            };
+            same_descriptor(&pipeline.descriptor, &descriptor)
+        });
+        if let Some(id) = cached {
+            return id;
+        }
+        let id = self.pipelines.len() + new_pipelines.len();
+        ids.push(id);
         new_pipelines.push(CachedPipeline {
-            descriptor: PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor)),
+            descriptor,
             state: CachedPipelineState::Queued,
+            previous: None,
         });
         id
     }
 
+    /// Returns the [`BindGroupLayout`] described by `bind_group_layout_descriptor`, creating it
+    /// on first use.
+    ///
+    /// Layouts are cached by their descriptor, so requesting an identical layout again returns a
+    /// clone of the same handle instead of creating a new one. The cache belongs to the current
+    /// [`RenderDevice`], and is dropped along with this [`PipelineCache`] when the renderer is
+    /// reinitialized with a new device.
     pub fn get_bind_group_layout(
         &self,
         bind_group_layout_descriptor: &BindGroupLayoutDescriptor,
@@ -446,11 +669,21 @@ impl PipelineCache {
     }
 
     /// Inserts a [`Shader`] into this cache with the provided [`AssetId`].
+    ///
+    /// Pipelines using a previous version of the shader are recreated on the next
+    /// [`PipelineCache::process_queue()`], keeping their ID. Until then, or if the new version
+    /// fails to compile, the pipeline created with the previous version keeps being returned.
     pub fn set_shader(&mut self, id: AssetId<Shader>, shader: Shader) {
         let mut shader_cache = self.shader_cache.lock().unwrap();
         let pipelines_to_queue = shader_cache.set_shader(id, shader);
         for cached_pipeline in pipelines_to_queue {
-            self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
+            let cached_pipeline_state = &mut self.pipelines[cached_pipeline];
+            if let CachedPipelineState::Ok(pipeline) = mem::replace(
+                &mut cached_pipeline_state.state,
+                CachedPipelineState::Queued,
+            ) {
+                cached_pipeline_state.previous = Some(pipeline);
+            }
             self.waiting_pipelines.insert(cached_pipeline);
         }
     }
@@ -461,6 +694,7 @@ impl PipelineCache {
         let pipelines_to_queue = shader_cache.remove(shader);
         for cached_pipeline in pipelines_to_queue {
             self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
+            self.pipelines[cached_pipeline].previous = None;
             self.waiting_pipelines.insert(cached_pipeline);
         }
     }
@@ -473,6 +707,7 @@ impl PipelineCache {
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
+        let pipeline_cache = self.disk_cache.as_ref().map(|cache| cache.cache.clone());
         let mut bindgroup_layout_cache = self.bindgroup_layout_cache.lock().unwrap();
         let bind_group_layout = descriptor
             .layout
@@ -562,7 +797,7 @@ impl PipelineCache {
                             // TODO: Should this be the same as the vertex compilation options?
                             compilation_options,
                         }),
-                    cache: None,
+                    cache: pipeline_cache.as_deref(),
                 };
 
                 Ok(Pipeline::RenderPipeline(
@@ -581,6 +816,7 @@ impl PipelineCache {
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
+        let pipeline_cache = self.disk_cache.as_ref().map(|cache| cache.cache.clone());
         let mut bindgroup_layout_cache = self.bindgroup_layout_cache.lock().unwrap();
         let bind_group_layout = descriptor
             .layout
@@ -620,7 +856,7 @@ impl PipelineCache {
                         zero_initialize_workgroup_memory: descriptor
                             .zero_initialize_workgroup_memory,
                     },
-                    cache: None,
+                    cache: pipeline_cache.as_deref(),
                 };
 
                 Ok(Pipeline::ComputePipeline(
@@ -658,6 +894,14 @@ impl PipelineCache {
         }
 
         self.pipelines = pipelines;
+
+        // Write the compiled pipelines once a batch finished compiling, rather than every frame.
+        if self.waiting_pipelines.is_empty()
+            && let Some(disk_cache) = &mut self.disk_cache
+            && disk_cache.dirty
+        {
+            disk_cache.save_in_background();
+        }
     }
 
     fn process_pipeline(&mut self, cached_pipeline: &mut CachedPipeline, id: usize) {
@@ -676,6 +920,10 @@ impl PipelineCache {
             CachedPipelineState::Creating(task) => match bevy_tasks::futures::check_ready(task) {
                 Some(Ok(pipeline)) => {
                     cached_pipeline.state = CachedPipelineState::Ok(pipeline);
+                    cached_pipeline.previous = None;
+                    if let Some(disk_cache) = &mut self.disk_cache {
+                        disk_cache.dirty = true;
+                    }
                     return;
                 }
                 Some(Err(err)) => cached_pipeline.state = CachedPipelineState::Err(err),
@@ -698,11 +946,17 @@ impl PipelineCache {
                     {
                         error!("{}", pipeline_error_context(cached_pipeline));
                     }
-                    error!("failed to process shader error:\n{}", error_detail);
+                    error!(
+                        "failed to process shader of pipeline {:?}:\n{}",
+                        pipeline_label(cached_pipeline),
+                        error_detail
+                    );
+                    warn_keeping_previous(cached_pipeline);
                     return;
                 }
                 ShaderCacheError::CreateShaderModule(description) => {
                     error!("failed to create shader module: {}", description);
+                    warn_keeping_previous(cached_pipeline);
                     return;
                 }
             },
@@ -714,6 +968,12 @@ impl PipelineCache {
         self.waiting_pipelines.insert(id);
     }
 
+    /// Runs [`PipelineCache::process_queue`] in [`RenderSystems::Render`], right before the render
+    /// graph, rather than in [`RenderSystems::Prepare`]. Pipelines queued while queueing and
+    /// preparing the frame can then be created, or finish compiling, in time to be used by it.
+    ///
+    /// [`RenderSystems::Render`]: crate::RenderSystems::Render
+    /// [`RenderSystems::Prepare`]: crate::RenderSystems::Prepare
     pub(crate) fn process_pipeline_queue_system(mut cache: ResMut<Self>) {
         cache.process_queue();
     }
@@ -760,6 +1020,54 @@ impl PipelineCache {
     }
 }
 
+/// Hashes the parts of a descriptor that usually tell pipelines apart, to find identical
+/// descriptors without comparing every cached one. Descriptors with the same hash still need
+/// to be compared with [`same_descriptor`].
+fn descriptor_hash(descriptor: &PipelineDescriptor) -> u64 {
+    match descriptor {
+        PipelineDescriptor::RenderPipelineDescriptor(descriptor) => FixedHasher.hash_one((
+            0u8,
+            &descriptor.label,
+            &descriptor.layout,
+            descriptor.immediate_size,
+            descriptor.vertex.shader.id(),
+            &descriptor.vertex.shader_defs,
+            &descriptor.vertex.entry_point,
+            descriptor.fragment.as_ref().map(|fragment| {
+                (
+                    fragment.shader.id(),
+                    &fragment.shader_defs,
+                    &fragment.entry_point,
+                )
+            }),
+        )),
+        PipelineDescriptor::ComputePipelineDescriptor(descriptor) => FixedHasher.hash_one((
+            1u8,
+            &descriptor.label,
+            &descriptor.layout,
+            descriptor.immediate_size,
+            descriptor.shader.id(),
+            &descriptor.shader_defs,
+            &descriptor.entry_point,
+        )),
+    }
+}
+
+/// Whether two descriptors describe the same pipeline.
+fn same_descriptor(a: &PipelineDescriptor, b: &PipelineDescriptor) -> bool {
+    match (a, b) {
+        (
+            PipelineDescriptor::RenderPipelineDescriptor(a),
+            PipelineDescriptor::RenderPipelineDescriptor(b),
+        ) => a == b,
+        (
+            PipelineDescriptor::ComputePipelineDescriptor(a),
+            PipelineDescriptor::ComputePipelineDescriptor(b),
+        ) => a == b,
+        _ => false,
+    }
+}
+
 fn pipeline_error_context(cached_pipeline: &CachedPipeline) -> String {
     fn format(
         shader: &Handle<Shader>,
@@ -835,3 +1143,594 @@ fn create_pipeline_task(
         Err(err) => CachedPipelineState::Err(err),
     }
 }
+
+fn pipeline_label(cached_pipeline: &CachedPipeline) -> &str {
+    let label = match &cached_pipeline.descriptor {
+        PipelineDescriptor::RenderPipelineDescriptor(desc) => &desc.label,
+        PipelineDescriptor::ComputePipelineDescriptor(desc) => &desc.label,
+    };
+    label.as_deref().unwrap_or("unlabeled")
+}
+
+fn warn_keeping_previous(cached_pipeline: &CachedPipeline) {
+    if cached_pipeline.previous.is_none() {
+        return;
+    }
+    warn!(
+        "keeping the previous version of pipeline {:?} until its shaders compile",
+        pipeline_label(cached_pipeline)
+    );
+}
+
+/// A system waiting for every queued pipeline to finish compiling, see
+/// [`PipelineCache::block_on_all_pipelines()`].
+///
+/// Add it to [`RenderStartup`](crate::RenderStartup) or [`Render`](crate::Render) to trade a longer
+/// startup for not rendering with missing pipelines, e.g. in shipping builds.
+pub fn block_on_pipeline_compilation(mut cache: ResMut<PipelineCache>) {
+    cache.block_on_all_pipelines();
+}
+
+/// Whether the [`PipelineCache`] finished compiling every queued pipeline, e.g. to keep a loading
+/// screen up until then.
+///
+/// This is a main world resource, added by [`PipelinesReadyPlugin`]. It's updated once per frame
+/// after the pipelines were processed, and mirrored to the main world at the start of the next
+/// frame.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelinesReady {
+    /// The number of pipelines queued or compiling.
+    pub pending: usize,
+    /// The number of pipelines that failed to compile. See [`PipelineCache::pipeline_errors()`].
+    pub failed: usize,
+    ready: bool,
+}
+
+impl PipelinesReady {
+    /// Returns `true` once the render world reported that no pipeline is pending.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+}
+
+/// Shares the latest [`PipelinesReady`] of the render world with the main world.
+#[derive(Resource, Clone, Default)]
+struct PipelinesReadyMutex(Arc<Mutex<Option<PipelinesReady>>>);
+
+/// Maintains the [`PipelinesReady`] resource of the main world.
+pub struct PipelinesReadyPlugin;
+
+impl Plugin for PipelinesReadyPlugin {
+    fn build(&self, app: &mut App) {
+        let mutex = PipelinesReadyMutex::default();
+        app.init_resource::<PipelinesReady>()
+            .insert_resource(mutex.clone())
+            .add_systems(PreUpdate, sync_pipelines_ready);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.insert_resource(mutex).add_systems(
+                Render,
+                report_pipelines_ready
+                    .after(PipelineCache::process_pipeline_queue_system)
+                    .in_set(RenderSystems::Render),
+            );
+        }
+    }
+}
+
+fn report_pipelines_ready(cache: Res<PipelineCache>, mutex: Res<PipelinesReadyMutex>) {
+    let ready = PipelinesReady {
+        pending: cache.pending_pipelines(),
+        failed: cache.pipeline_errors().count(),
+        ready: cache.all_ready(),
+    };
+    *mutex.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(ready);
+}
+
+fn sync_pipelines_ready(mutex: Res<PipelinesReadyMutex>, mut ready: ResMut<PipelinesReady>) {
+    if let Some(received) = mutex.0.lock().ok().and_then(|mut v| v.take()) {
+        *ready = received;
+    }
+}
+
+/// A driver-level [`wgpu::PipelineCache`] persisted to a file, so pipelines compiled in a
+/// previous run don't need to be compiled again.
+struct PipelineDiskCache {
+    cache: WgpuWrapper<wgpu::PipelineCache>,
+    path: PathBuf,
+    /// Whether pipelines were created since the cache was last written.
+    dirty: bool,
+}
+
+impl PipelineDiskCache {
+    /// Loads the cache of the adapter from `dir`, or returns `None` if the device doesn't have
+    /// [`wgpu::Features::PIPELINE_CACHE`] enabled or `wgpu` can't cache pipelines of the adapter.
+    fn load(device: &RenderDevice, adapter_info: &AdapterInfo, dir: &Path) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::PIPELINE_CACHE) {
+            return None;
+        }
+
+        let path = dir.join(wgpu::util::pipeline_cache_key(adapter_info)?);
+        let data = match std::fs::read(&path) {
+            Ok(data) => Some(data),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => {
+                warn!("failed to read pipeline cache {}: {}", path.display(), err);
+                None
+            }
+        };
+
+        // SAFETY: The data was returned by `get_data` of a cache created for the same adapter and
+        // driver, as both are part of the cache key. With `fallback`, wgpu validates its header and
+        // starts with an empty cache if it is incompatible or corrupted anyway.
+        let cache = unsafe {
+            device
+                .wgpu_device()
+                .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("pipeline_cache"),
+                    data: data.as_deref(),
+                    fallback: true,
+                })
+        };
+
+        Some(Self {
+            cache: WgpuWrapper::new(cache),
+            path,
+            dirty: false,
+        })
+    }
+
+    /// Writes the cache on the [`IoTaskPool`], so rendering doesn't wait for the file system.
+    fn save_in_background(&mut self) {
+        self.dirty = false;
+        let Some(data) = self.cache.get_data() else {
+            return;
+        };
+        let path = self.path.clone();
+        IoTaskPool::get_or_init(TaskPool::default)
+            .spawn(async move { write_pipeline_cache(&path, &data) })
+            .detach();
+    }
+
+    fn save(&mut self) {
+        self.dirty = false;
+        if let Some(data) = self.cache.get_data() {
+            write_pipeline_cache(&self.path, &data);
+        }
+    }
+}
+
+impl Drop for PipelineDiskCache {
+    fn drop(&mut self) {
+        if self.dirty {
+            self.save();
+        }
+    }
+}
+
+fn write_pipeline_cache(path: &Path, data: &[u8]) {
+    // Write to a temporary file first, so a crash can't leave a truncated cache behind.
+    let temp_path = path.with_extension("tmp");
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&temp_path, data))
+        .and_then(|()| std::fs::rename(&temp_path, path));
+    if let Err(err) = result {
+        warn!("failed to write pipeline cache {}: {}", path.display(), err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::uuid_handle;
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+
+    use super::*;
+    #[cfg(feature = "shader_format_glsl")]
+    use crate::renderer::gpu_render_resources;
+    use crate::{renderer::noop_render_resources, settings::RenderResources};
+
+    #[test]
+    fn disk_cache_is_only_loaded_for_supported_adapters() {
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::PIPELINE_CACHE);
+        let dir = std::env::temp_dir().join("robin_render_disk_cache_test");
+
+        // `wgpu` has no pipeline cache key for the noop backend.
+        let cache = PipelineCache::new(device.clone(), adapter.clone(), true).with_disk_cache(&dir);
+        assert!(cache.disk_cache.is_none());
+        assert_eq!(cache.cache_dir.as_deref(), Some(dir.as_path()));
+
+        let mut vulkan_info = adapter.get_info();
+        vulkan_info.backend = wgpu::Backend::Vulkan;
+        let key = wgpu::util::pipeline_cache_key(&vulkan_info).unwrap();
+        let disk_cache = PipelineDiskCache::load(&device, &vulkan_info, &dir).unwrap();
+        assert_eq!(disk_cache.path, dir.join(key));
+    }
+
+    #[test]
+    fn pipelines_using_missing_features_stay_errored() {
+        const F16_SHADER: Handle<Shader> = uuid_handle!("5f5bd1a4-4bb5-4c2c-9b8f-46d6a4a0e1c2");
+        const F32_SHADER: Handle<Shader> = uuid_handle!("0d0f8a58-2a47-4d1f-8a43-1b8ce1b8f1a7");
+        // Like a device recovered with `SHADER_F16` dropped.
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::empty());
+        let mut cache = PipelineCache::new(device, adapter, true);
+        let shader = |ty: &str| {
+            let enable = if ty == "f16" { "enable f16;\n" } else { "" };
+            Shader::from_wgsl(
+                format!(
+                    "{enable}@compute @workgroup_size(1)\n\
+                    fn main() {{\n    var x = {ty}(1.0);\n    x = x * {ty}(2.0);\n}}\n"
+                ),
+                format!("{ty}.wgsl"),
+            )
+        };
+        cache.set_shader(F16_SHADER.id(), shader("f16"));
+        cache.set_shader(F32_SHADER.id(), shader("f32"));
+        let f16 = cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("f16".into()),
+            shader: F16_SHADER,
+            ..Default::default()
+        });
+        let f32 = cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("f32".into()),
+            shader: F32_SHADER,
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            cache.process_queue();
+        }
+
+        assert!(matches!(
+            cache.get_compute_pipeline_state(f32),
+            CachedPipelineState::Ok(_)
+        ));
+        assert!(matches!(
+            cache.get_compute_pipeline_state(f16),
+            CachedPipelineState::Err(
+                ShaderCacheError::ProcessShaderError(_) | ShaderCacheError::CreateShaderModule(_)
+            )
+        ));
+        // It isn't retried.
+        assert_eq!(cache.waiting_pipelines().count(), 0);
+        assert_eq!(cache.pipeline_errors().count(), 1);
+    }
+
+    #[test]
+    fn imported_modules_are_composed() {
+        const MATH_SHADER: Handle<Shader> = uuid_handle!("7a1d3c5e-2b4f-4e68-9c0a-5d7f1e3b9a26");
+        const MAIN_SHADER: Handle<Shader> = uuid_handle!("e4c2a8f6-1d3b-4a59-8e7c-0b2d6f4a1c83");
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::empty());
+        let mut cache = PipelineCache::new(device, adapter, true);
+        let math = |body: &str| {
+            Shader::from_wgsl(
+                format!(
+                    "#define_import_path robin_test::math
+
+                    fn scale(x: f32) -> f32 {{
+    return {body};
+}}
+"
+                ),
+                "math.wgsl",
+            )
+        };
+        cache.set_shader(
+            MAIN_SHADER.id(),
+            Shader::from_wgsl(
+                "#import robin_test::math::scale
+
+                @compute @workgroup_size(1)
+                fn main() {
+    var x = scale(1.0);
+    x = x + 1.0;
+}
+",
+                "main.wgsl",
+            ),
+        );
+        let id = cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("importer".into()),
+            shader: MAIN_SHADER,
+            ..Default::default()
+        });
+        let process = |cache: &mut PipelineCache| {
+            for _ in 0..3 {
+                cache.process_queue();
+            }
+        };
+
+        // The pipeline waits for the module it imports.
+        process(&mut cache);
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Queued
+                | CachedPipelineState::Err(ShaderCacheError::ShaderImportNotYetAvailable)
+        ));
+        assert_eq!(cache.pending_pipelines(), 1);
+
+        cache.set_shader(MATH_SHADER.id(), math("x * 2.0"));
+        process(&mut cache);
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Ok(_)
+        ));
+
+        // Editing the imported module recompiles the importer.
+        cache.set_shader(MATH_SHADER.id(), math("x * 3.0"));
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Queued
+        ));
+        process(&mut cache);
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Ok(_)
+        ));
+
+        // Errors in the imported module name it.
+        cache.set_shader(MATH_SHADER.id(), math("missing(x)"));
+        process(&mut cache);
+        let CachedPipelineState::Err(ShaderCacheError::ProcessShaderError(error)) =
+            cache.get_compute_pipeline_state(id)
+        else {
+            panic!("the importer should fail to compose");
+        };
+        let error = error.emit_to_string(&cache.shader_cache.lock().unwrap().composer);
+        assert!(error.contains("math.wgsl"), "{error}");
+    }
+
+    #[test]
+    fn hot_reloaded_shaders_swap_the_pipeline() {
+        const SHADER: Handle<Shader> = uuid_handle!("3b9e5d1f-7c2a-4f80-a6d4-8e1c3a5f7b92");
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::empty());
+        let mut cache = PipelineCache::new(device, adapter, true);
+        let shader = |value: &str| {
+            Shader::from_wgsl(
+                format!(
+                    "@compute @workgroup_size(1)
+fn main() {{
+    var x = {value};
+}}
+"
+                ),
+                "hot_reload.wgsl",
+            )
+        };
+        let process = |cache: &mut PipelineCache| {
+            for _ in 0..3 {
+                cache.process_queue();
+            }
+        };
+        let pipeline = |cache: &PipelineCache, id| {
+            cache
+                .get_compute_pipeline(id)
+                .map(ComputePipeline::id)
+                .expect("a pipeline should be available")
+        };
+
+        cache.set_shader(SHADER.id(), shader("1.0"));
+        let id = cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("hot_reload".into()),
+            shader: SHADER,
+            ..Default::default()
+        });
+        process(&mut cache);
+        let first = pipeline(&cache, id);
+
+        // The previous pipeline is used until the new one is created.
+        cache.set_shader(SHADER.id(), shader("2.0"));
+        assert_eq!(pipeline(&cache, id), first);
+        process(&mut cache);
+        let second = pipeline(&cache, id);
+        assert_ne!(second, first);
+
+        // A shader that doesn't compile keeps the previous pipeline.
+        cache.set_shader(SHADER.id(), shader("undefined_value"));
+        process(&mut cache);
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Err(_)
+        ));
+        assert_eq!(pipeline(&cache, id), second);
+    }
+
+    #[cfg(feature = "shader_format_glsl")]
+    #[test]
+    fn glsl_shaders_compile_into_a_render_pipeline() {
+        const VERTEX_SHADER: Handle<Shader> = uuid_handle!("9d4f2b7a-6c1e-4a83-b5d0-2e8c7f1a3b64");
+        const FRAGMENT_SHADER: Handle<Shader> =
+            uuid_handle!("1e7a5c3b-8f2d-4b96-a0c4-7d3e9b2f6a15");
+        let Some(RenderResources(device, _, _, adapter, ..)) = gpu_render_resources() else {
+            return;
+        };
+        let mut cache = PipelineCache::new(device, adapter, true);
+        cache.set_shader(
+            VERTEX_SHADER.id(),
+            Shader::from_glsl(
+                "#version 450
+void main() {
+    vec2 uv = vec2(float((gl_VertexIndex << 1) & 2), float(gl_VertexIndex & 2));
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+",
+                naga::ShaderStage::Vertex,
+                "fullscreen.vert",
+            ),
+        );
+        cache.set_shader(
+            FRAGMENT_SHADER.id(),
+            Shader::from_glsl(
+                "#version 450
+layout(location = 0) out vec4 out_color;
+void main() {
+#ifdef TINT
+    out_color = vec4(1.0, 0.0, 1.0, 1.0);
+#else
+    out_color = undefined_color;
+#endif
+}
+",
+                naga::ShaderStage::Fragment,
+                "solid.frag",
+            ),
+        );
+
+        // GLSL entry points are always named `main`, so none is set.
+        let id = cache.queue_render_pipeline(RenderPipelineDescriptor {
+            label: Some("glsl".into()),
+            vertex: VertexState {
+                shader: VERTEX_SHADER,
+                ..Default::default()
+            },
+            fragment: Some(FragmentState {
+                shader: FRAGMENT_SHADER,
+                shader_defs: vec!["TINT".into()],
+                targets: vec![Some(ColorTargetState {
+                    format: TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            cache.process_queue();
+        }
+
+        match cache.get_render_pipeline_state(id) {
+            CachedPipelineState::Ok(Pipeline::RenderPipeline(_)) => {}
+            CachedPipelineState::Err(err) => panic!("{err}"),
+            _ => panic!("the GLSL pipeline should be created"),
+        }
+    }
+
+    #[test]
+    fn pipelines_are_ready_once_compiled() {
+        const SHADER: Handle<Shader> = uuid_handle!("c5b6e0c2-5d0e-4f5c-9a57-3e7f3c1f6a41");
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::empty());
+        let mut world = World::new();
+        world.insert_resource(PipelineCache::new(device, adapter, true));
+        world.init_resource::<PipelinesReadyMutex>();
+        let report = |world: &mut World| {
+            world.run_system_once(report_pipelines_ready).unwrap();
+            world
+                .resource::<PipelinesReadyMutex>()
+                .0
+                .lock()
+                .unwrap()
+                .take()
+                .unwrap()
+        };
+        let descriptor = |label: &str| ComputePipelineDescriptor {
+            label: Some(label.to_owned().into()),
+            shader: SHADER,
+            ..Default::default()
+        };
+
+        assert!(report(&mut world).is_ready());
+
+        // Queued pipelines are pending before they are processed.
+        let mut cache = world.resource_mut::<PipelineCache>();
+        let first = cache.queue_compute_pipeline(descriptor("first"));
+        assert!(!cache.all_ready());
+        let ready = report(&mut world);
+        assert_eq!((ready.pending, ready.failed), (1, 0));
+        assert!(!ready.is_ready());
+
+        // And while their shader isn't loaded.
+        let mut cache = world.resource_mut::<PipelineCache>();
+        cache.process_queue();
+        assert!(!cache.all_ready());
+        assert_eq!(cache.pending_pipelines(), 1);
+
+        cache.set_shader(
+            SHADER.id(),
+            Shader::from_wgsl("@compute @workgroup_size(1)\nfn main() {}\n", "ready.wgsl"),
+        );
+        for _ in 0..3 {
+            cache.process_queue();
+        }
+        assert!(matches!(
+            cache.get_compute_pipeline_state(first),
+            CachedPipelineState::Ok(_)
+        ));
+        assert!(cache.all_ready());
+        assert!(report(&mut world).is_ready());
+
+        let mut cache = world.resource_mut::<PipelineCache>();
+        let second = cache.queue_compute_pipeline(descriptor("second"));
+        assert!(!cache.all_ready());
+        cache.block_on_all_pipelines();
+        assert!(matches!(
+            cache.get_compute_pipeline_state(second),
+            CachedPipelineState::Ok(_)
+        ));
+        assert!(cache.all_ready());
+        assert_eq!(
+            report(&mut world),
+            PipelinesReady {
+                pending: 0,
+                failed: 0,
+                ready: true,
+            }
+        );
+    }
+
+    #[test]
+    fn identical_descriptors_are_deduplicated() {
+        let render = |label: &'static str| {
+            PipelineDescriptor::RenderPipelineDescriptor(Box::new(RenderPipelineDescriptor {
+                label: Some(label.into()),
+                ..Default::default()
+            }))
+        };
+        let compute = |label: &'static str| {
+            PipelineDescriptor::ComputePipelineDescriptor(Box::new(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                ..Default::default()
+            }))
+        };
+
+        assert!(same_descriptor(&render("a"), &render("a")));
+        assert!(!same_descriptor(&render("a"), &render("b")));
+        assert!(same_descriptor(&compute("a"), &compute("a")));
+        assert!(!same_descriptor(&render("a"), &compute("a")));
+    }
+
+    #[test]
+    fn queued_pipelines_are_found_by_their_descriptor() {
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::empty());
+        let mut cache = PipelineCache::new(device, adapter, true);
+        let render = |label: &str| RenderPipelineDescriptor {
+            label: Some(label.to_owned().into()),
+            ..Default::default()
+        };
+        let compute = |label: &str| ComputePipelineDescriptor {
+            label: Some(label.to_owned().into()),
+            ..Default::default()
+        };
+
+        let a = cache.queue_render_pipeline(render("a"));
+        let b = cache.queue_render_pipeline(render("b"));
+        let c = cache.queue_compute_pipeline(compute("a"));
+        assert_ne!(a.id(), b.id());
+        assert_ne!(a.id(), c.id());
+        assert_eq!(cache.queue_render_pipeline(render("a")), a);
+        // Still found once moved out of the queue.
+        cache.process_queue();
+        assert_eq!(cache.queue_render_pipeline(render("b")), b);
+        assert_eq!(cache.queue_compute_pipeline(compute("a")), c);
+        let d = cache.queue_render_pipeline(render("d"));
+        assert_eq!(d.id(), 3);
+        assert_eq!(cache.queue_render_pipeline(render("d")), d);
+    }
+}
@@ -3,7 +3,7 @@ use bevy_material::descriptor::{
     RenderPipelineDescriptor,
 };
 
-use crate::render_resource::PipelineCache;
+use crate::{render_resource::PipelineCache, renderer::DeviceGeneration};
 use bevy_ecs::resource::Resource;
 use bevy_log::error;
 use bevy_material::specialize::SpecializedMeshPipelineError;
@@ -18,6 +18,15 @@ use bevy_platform::{
 use bevy_utils::default;
 use core::hash::Hash;
 
+/// Returns `true` if the ids cached for `generation` were created by a [`PipelineCache`] of
+/// another device than the `current` one, updating `generation`. See
+/// [`PipelineCache::generation`].
+fn is_stale(generation: &mut Option<DeviceGeneration>, current: DeviceGeneration) -> bool {
+    let stale = generation.is_some_and(|generation| generation != current);
+    *generation = Some(current);
+    stale
+}
+
 /// A trait that allows constructing different variants of a render pipeline from a key.
 ///
 /// Note: This is intended for modifying your pipeline descriptor on the basis of a key. If your key
@@ -47,14 +56,21 @@ pub trait SpecializedRenderPipeline {
 /// [`AsBindGroup`](crate::render_resource::AsBindGroup) without the `#[bind_group_data]` attribute,
 /// you don't need to specialize. Instead, create the pipeline directly from [`PipelineCache`] and
 /// store its ID.
+///
+/// The cached ids are forgotten when the [`PipelineCache`] is recreated for a new device, so the
+/// pipelines are queued again.
 #[derive(Resource)]
 pub struct SpecializedRenderPipelines<S: SpecializedRenderPipeline> {
     cache: HashMap<S::Key, CachedRenderPipelineId>,
+    generation: Option<DeviceGeneration>,
 }
 
 impl<S: SpecializedRenderPipeline> Default for SpecializedRenderPipelines<S> {
     fn default() -> Self {
-        Self { cache: default() }
+        Self {
+            cache: default(),
+            generation: None,
+        }
     }
 }
 
@@ -66,6 +82,9 @@ impl<S: SpecializedRenderPipeline> SpecializedRenderPipelines<S> {
         pipeline_specializer: &S,
         key: S::Key,
     ) -> CachedRenderPipelineId {
+        if is_stale(&mut self.generation, cache.generation()) {
+            self.cache.clear();
+        }
         *self.cache.entry(key.clone()).or_insert_with(|| {
             let descriptor = pipeline_specializer.specialize(key);
             cache.queue_render_pipeline(descriptor)
@@ -102,14 +121,21 @@ pub trait SpecializedComputePipeline {
 /// [`AsBindGroup`](crate::render_resource::AsBindGroup) without the `#[bind_group_data]` attribute,
 /// you don't need to specialize. Instead, create the pipeline directly from [`PipelineCache`] and
 /// store its ID.
+///
+/// The cached ids are forgotten when the [`PipelineCache`] is recreated for a new device, so the
+/// pipelines are queued again.
 #[derive(Resource)]
 pub struct SpecializedComputePipelines<S: SpecializedComputePipeline> {
     cache: HashMap<S::Key, CachedComputePipelineId>,
+    generation: Option<DeviceGeneration>,
 }
 
 impl<S: SpecializedComputePipeline> Default for SpecializedComputePipelines<S> {
     fn default() -> Self {
-        Self { cache: default() }
+        Self {
+            cache: default(),
+            generation: None,
+        }
     }
 }
 
@@ -121,6 +147,9 @@ impl<S: SpecializedComputePipeline> SpecializedComputePipelines<S> {
         specialize_pipeline: &S,
         key: S::Key,
     ) -> CachedComputePipelineId {
+        if is_stale(&mut self.generation, cache.generation()) {
+            self.cache.clear();
+        }
         *self.cache.entry(key.clone()).or_insert_with(|| {
             let descriptor = specialize_pipeline.specialize(key);
             cache.queue_compute_pipeline(descriptor)
@@ -149,10 +178,14 @@ pub trait SpecializedMeshPipeline {
 
 /// A cache of different variants of a render pipeline based on a key and the particular mesh's
 /// vertex buffer layout.
+///
+/// Like [`SpecializedRenderPipelines`], it forgets the cached ids when the [`PipelineCache`] is
+/// recreated.
 #[derive(Resource)]
 pub struct SpecializedMeshPipelines<S: SpecializedMeshPipeline> {
     mesh_layout_cache: HashMap<(MeshVertexBufferLayoutRef, S::Key), CachedRenderPipelineId>,
     vertex_layout_cache: VertexLayoutCache<S>,
+    generation: Option<DeviceGeneration>,
 }
 
 type VertexLayoutCache<S> = HashMap<
@@ -165,6 +198,7 @@ impl<S: SpecializedMeshPipeline> Default for SpecializedMeshPipelines<S> {
         Self {
             mesh_layout_cache: Default::default(),
             vertex_layout_cache: Default::default(),
+            generation: None,
         }
     }
 }
@@ -180,6 +214,10 @@ impl<S: SpecializedMeshPipeline> SpecializedMeshPipelines<S> {
         key: S::Key,
         layout: &MeshVertexBufferLayoutRef,
     ) -> Result<CachedRenderPipelineId, SpecializedMeshPipelineError> {
+        if is_stale(&mut self.generation, cache.generation()) {
+            self.mesh_layout_cache.clear();
+            self.vertex_layout_cache.clear();
+        }
         return match self.mesh_layout_cache.entry((layout.clone(), key.clone())) {
             Entry::Occupied(entry) => Ok(*entry.into_mut()),
             Entry::Vacant(entry) => specialize_slow(
@@ -253,3 +291,94 @@ impl<S: SpecializedMeshPipeline> SpecializedMeshPipelines<S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use bevy_ecs::prelude::*;
+
+    use super::{SpecializedRenderPipeline, SpecializedRenderPipelines};
+    use crate::{
+        Render, RenderSystems,
+        error_handler::RenderDeviceRecreated,
+        render_resource::{CachedRenderPipelineId, PipelineCache, RenderPipelineDescriptor},
+        renderer::{DeviceGeneration, noop_render_resources},
+        settings::RenderResources,
+    };
+
+    #[derive(Resource, Default)]
+    struct TestPipeline {
+        specializations: AtomicUsize,
+    }
+
+    impl SpecializedRenderPipeline for TestPipeline {
+        type Key = bool;
+
+        fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+            self.specializations.fetch_add(1, Ordering::Relaxed);
+            RenderPipelineDescriptor {
+                label: Some(if key { "on" } else { "off" }.into()),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct SpecializedIds(Vec<CachedRenderPipelineId>);
+
+    fn specialize_test_pipelines(
+        pipeline_cache: Res<PipelineCache>,
+        pipeline: Res<TestPipeline>,
+        mut pipelines: ResMut<SpecializedRenderPipelines<TestPipeline>>,
+        mut ids: ResMut<SpecializedIds>,
+    ) {
+        ids.0 = [false, true, false]
+            .into_iter()
+            .map(|key| pipelines.specialize(&pipeline_cache, &pipeline, key))
+            .collect();
+    }
+
+    #[test]
+    fn pipelines_are_specialized_again_for_a_new_device() {
+        let RenderResources(device, _, _, adapter, ..) =
+            noop_render_resources(wgpu::Features::empty());
+        let mut world = World::new();
+        world.insert_resource(PipelineCache::new(device.clone(), adapter.clone(), true));
+        world.insert_resource(device);
+        world.insert_resource(adapter);
+        world.init_resource::<TestPipeline>();
+        world.init_resource::<SpecializedRenderPipelines<TestPipeline>>();
+        world.init_resource::<SpecializedIds>();
+        world.add_observer(PipelineCache::recreate_on_device_recreated);
+        let mut schedule = Render::base_schedule();
+        schedule.add_systems(specialize_test_pipelines.in_set(RenderSystems::Specialize));
+        let specializations = |world: &World| {
+            world
+                .resource::<TestPipeline>()
+                .specializations
+                .load(Ordering::Relaxed)
+        };
+
+        schedule.run(&mut world);
+        let ids = world.resource::<SpecializedIds>().0.clone();
+        assert_eq!(ids[0], ids[2]);
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(specializations(&world), 2);
+
+        schedule.run(&mut world);
+        assert_eq!(specializations(&world), 2);
+
+        world.trigger(RenderDeviceRecreated {
+            generation: DeviceGeneration(2),
+        });
+        assert_eq!(
+            world.resource::<PipelineCache>().generation(),
+            DeviceGeneration(2)
+        );
+        schedule.run(&mut world);
+        assert_eq!(specializations(&world), 4);
+        // The recreated cache keeps the IDs of its pipelines.
+        assert_eq!(world.resource::<SpecializedIds>().0, ids);
+    }
+}
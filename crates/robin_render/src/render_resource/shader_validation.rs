@@ -0,0 +1,538 @@
+use bevy_shader::ShaderDefVal;
+use naga::{
+    AddressSpace, ImageClass, ImageDimension, StorageAccess, TypeInner,
+    valid::{Capabilities, ValidationFlags, Validator},
+};
+use naga_oil::compose::{
+    Composer, ComposerError, ComposerErrorInner, NagaModuleDescriptor, ShaderDefValue, ShaderType,
+};
+use thiserror::Error;
+use wgpu::{ShaderStages, StorageTextureAccess, TextureViewDimension};
+
+/// A location in the source of a shader, as reported by [`ShaderValidationError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShaderSpan {
+    /// 1-based line number.
+    pub line: u32,
+    /// 1-based column, in UTF-8 code units.
+    pub column: u32,
+    /// Byte offset of the span in the source.
+    pub offset: u32,
+    /// Length of the span in bytes.
+    pub length: u32,
+}
+
+impl From<naga::SourceLocation> for ShaderSpan {
+    fn from(location: naga::SourceLocation) -> Self {
+        Self {
+            line: location.line_number,
+            column: location.line_position,
+            offset: location.offset,
+            length: location.length,
+        }
+    }
+}
+
+/// An error returned by [`validate_wgsl`] and `validate_glsl`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ShaderValidationError {
+    #[error("failed to preprocess shader at line {line}: {message}")]
+    Preprocessor { message: String, line: u32 },
+    #[error("failed to parse shader: {message}")]
+    Parse {
+        message: String,
+        span: Option<ShaderSpan>,
+    },
+    #[error("shader is invalid: {message}")]
+    Validation {
+        message: String,
+        span: Option<ShaderSpan>,
+    },
+}
+
+impl ShaderValidationError {
+    /// Returns where in the source the error occurred, if known.
+    pub fn span(&self) -> Option<ShaderSpan> {
+        match self {
+            Self::Preprocessor { line, .. } => Some(ShaderSpan {
+                line: *line,
+                column: 1,
+                offset: 0,
+                length: 0,
+            }),
+            Self::Parse { span, .. } | Self::Validation { span, .. } => *span,
+        }
+    }
+}
+
+/// An entry point declared by a shader module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderEntryPoint {
+    pub name: String,
+    pub stage: ShaderStages,
+    pub workgroup_size: [u32; 3],
+}
+
+/// The kind of resource a shader expects at a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderBindingKind {
+    UniformBuffer,
+    StorageBuffer {
+        read_only: bool,
+    },
+    Texture {
+        dimension: TextureViewDimension,
+        multisampled: bool,
+        depth: bool,
+    },
+    StorageTexture {
+        dimension: TextureViewDimension,
+        access: StorageTextureAccess,
+    },
+    Sampler {
+        comparison: bool,
+    },
+    AccelerationStructure,
+    /// A binding this API does not describe in detail, such as external textures.
+    Other,
+}
+
+/// A resource binding required by a shader module, inferred from its global variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderBinding {
+    pub group: u32,
+    pub binding: u32,
+    pub name: Option<String>,
+    pub kind: ShaderBindingKind,
+    /// The number of elements if the binding is a fixed-size binding array.
+    pub count: Option<u32>,
+    /// The stages of the entry points that use this binding.
+    pub visibility: ShaderStages,
+}
+
+/// Reflection information about a validated shader module.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShaderModuleInfo {
+    pub entry_points: Vec<ShaderEntryPoint>,
+    /// The bindings of the module, sorted by group and then binding index.
+    pub bindings: Vec<ShaderBinding>,
+}
+
+impl ShaderModuleInfo {
+    /// Returns the entry point with the given name.
+    pub fn entry_point(&self, name: &str) -> Option<&ShaderEntryPoint> {
+        self.entry_points.iter().find(|entry| entry.name == name)
+    }
+
+    /// Returns the bindings of the given bind group.
+    pub fn group(&self, group: u32) -> impl Iterator<Item = &ShaderBinding> {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.group == group)
+    }
+}
+
+/// Validates WGSL source without creating any GPU objects.
+///
+/// The source goes through the same naga_oil composer as in the
+/// [`PipelineCache`](super::PipelineCache), so the shader def directives are evaluated against
+/// `defs` exactly like they are when creating a pipeline, before running the naga validator.
+///
+/// Imports are not resolved, as they require the other shader assets. Modules that only declare
+/// a `#define_import_path` can still be validated on their own.
+pub fn validate_wgsl(
+    source: &str,
+    defs: &[ShaderDefVal],
+) -> Result<ShaderModuleInfo, ShaderValidationError> {
+    let mut composer = Composer::default().with_capabilities(Capabilities::all());
+    let module = composer
+        .make_naga_module(NagaModuleDescriptor {
+            source,
+            file_path: "shader.wgsl",
+            shader_type: ShaderType::Wgsl,
+            shader_defs: defs
+                .iter()
+                .map(|def| match def {
+                    ShaderDefVal::Bool(name, value) => (name.clone(), ShaderDefValue::Bool(*value)),
+                    ShaderDefVal::Int(name, value) => (name.clone(), ShaderDefValue::Int(*value)),
+                    ShaderDefVal::UInt(name, value) => (name.clone(), ShaderDefValue::UInt(*value)),
+                })
+                .collect(),
+            additional_imports: &[],
+        })
+        .map_err(|err| composer_error(err, source))?;
+
+    reflect_module(&module, source)
+}
+
+/// Converts an error of the composer, whose offsets point into `source` as the directive lines
+/// are blanked rather than removed while preprocessing.
+fn composer_error(err: ComposerError, source: &str) -> ShaderValidationError {
+    let line = |offset: usize| {
+        source[..offset.min(source.len())]
+            .bytes()
+            .filter(|&byte| byte == b'\n')
+            .count() as u32
+            + 1
+    };
+    let offset = match &err.inner {
+        ComposerErrorInner::ImportNotFound(_, offset)
+        | ComposerErrorInner::NotEnoughEndIfs(offset)
+        | ComposerErrorInner::TooManyEndIfs(offset)
+        | ComposerErrorInner::ElseWithoutCondition(offset)
+        | ComposerErrorInner::UnknownShaderDef { pos: offset, .. }
+        | ComposerErrorInner::UnknownShaderDefOperator { pos: offset, .. } => Some(*offset),
+        _ => None,
+    };
+    match err.inner {
+        ComposerErrorInner::WgslParseError(err) => ShaderValidationError::Parse {
+            message: err.message().to_string(),
+            span: err.location(source).map(ShaderSpan::from),
+        },
+        ComposerErrorInner::ShaderValidationError(err) => ShaderValidationError::Validation {
+            message: err.as_inner().to_string(),
+            span: err.location(source).map(ShaderSpan::from),
+        },
+        inner => ShaderValidationError::Preprocessor {
+            message: inner.to_string(),
+            line: offset.map_or(1, line),
+        },
+    }
+}
+
+/// Validates a GLSL shader of the given `stage` without creating any GPU objects.
+///
+/// Unlike WGSL, the directives are evaluated by the GLSL front-end itself: `defs` are injected as
+/// `#define NAME VALUE`, with `false` booleans left undefined. GLSL shaders have a single entry
+/// point named `main`, which is picked automatically when a pipeline descriptor doesn't set an
+/// entry point.
+#[cfg(feature = "shader_format_glsl")]
+pub fn validate_glsl(
+    source: &str,
+    stage: naga::ShaderStage,
+    defs: &[ShaderDefVal],
+) -> Result<ShaderModuleInfo, ShaderValidationError> {
+    let mut options = naga::front::glsl::Options::from(stage);
+    for def in defs {
+        let (name, value) = match def {
+            ShaderDefVal::Bool(_, false) => continue,
+            ShaderDefVal::Bool(name, true) => (name, String::new()),
+            ShaderDefVal::Int(name, value) => (name, value.to_string()),
+            ShaderDefVal::UInt(name, value) => (name, value.to_string()),
+        };
+        options.defines.insert(name.clone(), value);
+    }
+
+    let module = naga::front::glsl::Frontend::default()
+        .parse(&options, source)
+        .map_err(|errors| {
+            let error = errors.errors.first();
+            ShaderValidationError::Parse {
+                message: error.map_or_else(|| errors.to_string(), |error| error.kind.to_string()),
+                span: error.map(|error| ShaderSpan::from(error.meta.location(source))),
+            }
+        })?;
+
+    reflect_module(&module, source)
+}
+
+/// Validates a parsed `module` and reflects its entry points and bindings.
+fn reflect_module(
+    module: &naga::Module,
+    source: &str,
+) -> Result<ShaderModuleInfo, ShaderValidationError> {
+    let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(module)
+        .map_err(|err| ShaderValidationError::Validation {
+            message: err.as_inner().to_string(),
+            span: err.location(source).map(ShaderSpan::from),
+        })?;
+
+    let entry_points = module
+        .entry_points
+        .iter()
+        .map(|entry_point| ShaderEntryPoint {
+            name: entry_point.name.clone(),
+            stage: stage_to_wgpu(entry_point.stage),
+            workgroup_size: entry_point.workgroup_size,
+        })
+        .collect::<Vec<_>>();
+
+    let mut bindings = Vec::new();
+    for (handle, global) in module.global_variables.iter() {
+        let Some(binding) = &global.binding else {
+            continue;
+        };
+
+        let (inner, count) = match &module.types[global.ty].inner {
+            TypeInner::BindingArray { base, size } => (
+                &module.types[*base].inner,
+                match size {
+                    naga::ArraySize::Constant(size) => Some(size.get()),
+                    _ => None,
+                },
+            ),
+            inner => (inner, None),
+        };
+
+        let visibility = module
+            .entry_points
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !info.get_entry_point(*index)[handle].is_empty())
+            .fold(ShaderStages::empty(), |stages, (_, entry_point)| {
+                stages | stage_to_wgpu(entry_point.stage)
+            });
+
+        bindings.push(ShaderBinding {
+            group: binding.group,
+            binding: binding.binding,
+            name: global.name.clone(),
+            kind: binding_kind(global.space, inner),
+            count,
+            visibility,
+        });
+    }
+    bindings.sort_by_key(|binding| (binding.group, binding.binding));
+
+    Ok(ShaderModuleInfo {
+        entry_points,
+        bindings,
+    })
+}
+
+fn stage_to_wgpu(stage: naga::ShaderStage) -> ShaderStages {
+    match stage {
+        naga::ShaderStage::Vertex => ShaderStages::VERTEX,
+        naga::ShaderStage::Fragment => ShaderStages::FRAGMENT,
+        naga::ShaderStage::Compute => ShaderStages::COMPUTE,
+        _ => ShaderStages::empty(),
+    }
+}
+
+fn view_dimension(dim: ImageDimension, arrayed: bool) -> TextureViewDimension {
+    match (dim, arrayed) {
+        (ImageDimension::D1, _) => TextureViewDimension::D1,
+        (ImageDimension::D2, false) => TextureViewDimension::D2,
+        (ImageDimension::D2, true) => TextureViewDimension::D2Array,
+        (ImageDimension::D3, _) => TextureViewDimension::D3,
+        (ImageDimension::Cube, false) => TextureViewDimension::Cube,
+        (ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
+    }
+}
+
+fn binding_kind(space: AddressSpace, inner: &TypeInner) -> ShaderBindingKind {
+    match (space, inner) {
+        (AddressSpace::Uniform, _) => ShaderBindingKind::UniformBuffer,
+        (AddressSpace::Storage { access }, _) => ShaderBindingKind::StorageBuffer {
+            read_only: !access.contains(StorageAccess::STORE),
+        },
+        (
+            _,
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            },
+        ) => match class {
+            ImageClass::Sampled { multi, .. } => ShaderBindingKind::Texture {
+                dimension: view_dimension(*dim, *arrayed),
+                multisampled: *multi,
+                depth: false,
+            },
+            ImageClass::Depth { multi } => ShaderBindingKind::Texture {
+                dimension: view_dimension(*dim, *arrayed),
+                multisampled: *multi,
+                depth: true,
+            },
+            ImageClass::Storage { access, .. } => ShaderBindingKind::StorageTexture {
+                dimension: view_dimension(*dim, *arrayed),
+                access: if access.contains(StorageAccess::LOAD | StorageAccess::STORE) {
+                    StorageTextureAccess::ReadWrite
+                } else if access.contains(StorageAccess::STORE) {
+                    StorageTextureAccess::WriteOnly
+                } else {
+                    StorageTextureAccess::ReadOnly
+                },
+            },
+            _ => ShaderBindingKind::Other,
+        },
+        (_, TypeInner::Sampler { comparison }) => ShaderBindingKind::Sampler {
+            comparison: *comparison,
+        },
+        (_, TypeInner::AccelerationStructure { .. }) => ShaderBindingKind::AccelerationStructure,
+        _ => ShaderBindingKind::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADER: &str = r#"
+struct Params {
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var color_texture: texture_2d<f32>;
+@group(0) @binding(2) var color_sampler: sampler;
+@group(1) @binding(0) var<storage, read> unused: array<f32>;
+
+@vertex
+fn vertex(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32(index >> 1u), f32(index & 1u)) * 2.0;
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+#ifdef BROKEN
+    return undefined_value;
+#else
+    return params.color * textureSample(color_texture, color_sampler, position.xy);
+#endif
+}
+"#;
+
+    #[test]
+    fn validate_known_good_shader() {
+        let info = validate_wgsl(SHADER, &[]).unwrap();
+
+        assert_eq!(
+            info.entry_point("vertex").map(|entry| entry.stage),
+            Some(ShaderStages::VERTEX)
+        );
+        assert_eq!(
+            info.entry_point("fragment").map(|entry| entry.stage),
+            Some(ShaderStages::FRAGMENT)
+        );
+
+        let group_0: Vec<_> = info
+            .group(0)
+            .map(|binding| (binding.binding, binding.kind, binding.visibility))
+            .collect();
+        assert_eq!(
+            group_0,
+            [
+                (0, ShaderBindingKind::UniformBuffer, ShaderStages::FRAGMENT),
+                (
+                    1,
+                    ShaderBindingKind::Texture {
+                        dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                        depth: false,
+                    },
+                    ShaderStages::FRAGMENT
+                ),
+                (
+                    2,
+                    ShaderBindingKind::Sampler { comparison: false },
+                    ShaderStages::FRAGMENT
+                ),
+            ]
+        );
+
+        let unused = info.group(1).next().unwrap();
+        assert_eq!(
+            unused.kind,
+            ShaderBindingKind::StorageBuffer { read_only: true }
+        );
+        assert_eq!(unused.visibility, ShaderStages::empty());
+    }
+
+    #[test]
+    fn validate_broken_shader_reports_line() {
+        let error =
+            validate_wgsl(SHADER, &[ShaderDefVal::Bool("BROKEN".into(), true)]).unwrap_err();
+
+        let broken_line = SHADER
+            .lines()
+            .position(|line| line.contains("undefined_value"))
+            .unwrap() as u32
+            + 1;
+        assert!(matches!(error, ShaderValidationError::Parse { .. }));
+        assert_eq!(error.span().map(|span| span.line), Some(broken_line));
+    }
+
+    #[test]
+    fn preprocess_unbalanced_directives() {
+        let error = validate_wgsl("#ifdef A\nfn f() {}\n", &[]).unwrap_err();
+        assert!(matches!(error, ShaderValidationError::Preprocessor { .. }));
+
+        let error = validate_wgsl("fn f() {}\n#endif\n", &[]).unwrap_err();
+        assert_eq!(error.span().map(|span| span.line), Some(2));
+    }
+
+    #[test]
+    fn validate_wgsl_with_composer_directives() {
+        let source = r#"
+#ifdef A
+@compute @workgroup_size(1)
+fn a() {}
+#else ifdef B
+@compute @workgroup_size(#{SIZE})
+fn b() {}
+#else
+@compute @workgroup_size(1)
+fn c() {}
+#endif
+"#;
+        let info = validate_wgsl(
+            source,
+            &[
+                ShaderDefVal::Bool("B".into(), true),
+                ShaderDefVal::UInt("SIZE".into(), 8),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(info.entry_points.len(), 1);
+        assert_eq!(
+            info.entry_point("b").map(|entry| entry.workgroup_size),
+            Some([8, 1, 1])
+        );
+
+        let error = validate_wgsl("#import missing::module\nfn f() {}\n", &[]).unwrap_err();
+        assert!(matches!(error, ShaderValidationError::Preprocessor { .. }));
+    }
+
+    #[cfg(feature = "shader_format_glsl")]
+    #[test]
+    fn validate_glsl_with_defines() {
+        let source = r#"#version 450
+layout(set = 0, binding = 1) uniform Globals {
+    vec4 color;
+} globals;
+layout(location = 0) out vec4 out_color;
+void main() {
+#ifdef TINT
+    out_color = globals.color * float(TINT_STRENGTH);
+#else
+    out_color = globals.color;
+#endif
+}
+"#;
+        let info = validate_glsl(
+            source,
+            naga::ShaderStage::Fragment,
+            &[
+                ShaderDefVal::Bool("TINT".into(), true),
+                ShaderDefVal::Int("TINT_STRENGTH".into(), 2),
+            ],
+        )
+        .unwrap();
+        assert_eq!(info.entry_points[0].name, "main");
+        assert_eq!(info.entry_points[0].stage, ShaderStages::FRAGMENT);
+        assert_eq!(info.bindings[0].binding, 1);
+        assert_eq!(info.bindings[0].kind, ShaderBindingKind::UniformBuffer);
+
+        let error = validate_glsl(
+            "#version 450\nvoid main() {\n    undefined_function();\n}\n",
+            naga::ShaderStage::Vertex,
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(error.span().map(|span| span.line), Some(3));
+    }
+}
@@ -716,7 +716,7 @@ where
         // can happen is that we race with somebody, which is unfortunate
         // but not memory-unsafe.
         unsafe {
-            render_queue.write_buffer(
+            render_queue.write_buffer_counted(
                 data_buffer,
                 0,
                 slice::from_raw_parts(
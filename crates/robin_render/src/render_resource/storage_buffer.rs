@@ -142,7 +142,7 @@ impl<T: ShaderType + WriteInto> StorageBuffer<T> {
             }));
             self.changed = false;
         } else if let Some(buffer) = &self.buffer {
-            queue.write_buffer(buffer, 0, self.scratch.as_ref());
+            queue.write_buffer_counted(buffer, 0, self.scratch.as_ref());
         }
 
         self.last_written_size = BufferSize::new(size);
@@ -267,7 +267,7 @@ impl<T: ShaderType + WriteInto> DynamicStorageBuffer<T> {
             }));
             self.changed = false;
         } else if let Some(buffer) = &self.buffer {
-            queue.write_buffer(buffer, 0, self.scratch.as_ref());
+            queue.write_buffer_counted(buffer, 0, self.scratch.as_ref());
         }
 
         self.last_written_size = BufferSize::new(size);
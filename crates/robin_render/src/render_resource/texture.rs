@@ -7,6 +7,8 @@ use bevy_ecs::{
 use bevy_image::ImageSamplerDescriptor;
 use bevy_utils::define_atomic_id;
 use core::ops::Deref;
+use thiserror::Error;
+use wgpu::{TextureDimension, TextureViewDimension};
 
 define_atomic_id!(TextureId);
 
@@ -40,6 +42,144 @@ impl Texture {
     pub fn create_view(&self, desc: &wgpu::TextureViewDescriptor) -> TextureView {
         TextureView::from(self.value.create_view(desc))
     }
+
+    /// Creates a view of this texture, returning an error instead of raising a wgpu validation
+    /// error if `desc` doesn't fit the dimension or array layers of the texture.
+    pub fn try_create_view(
+        &self,
+        desc: &wgpu::TextureViewDescriptor,
+    ) -> Result<TextureView, TextureValidationError> {
+        validate_texture_view_descriptor(
+            self.value.dimension(),
+            self.value.depth_or_array_layers(),
+            desc,
+        )?;
+        Ok(self.create_view(desc))
+    }
+}
+
+/// An error found by [`validate_texture_descriptor`] or [`validate_texture_view_descriptor`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TextureValidationError {
+    #[error("texture size {size:?} exceeds the {dimension:?} size limit of {limit}")]
+    SizeExceedsLimit {
+        dimension: TextureDimension,
+        size: wgpu::Extent3d,
+        limit: u32,
+    },
+    #[error("{layers} array layers exceed the limit of {limit}")]
+    TooManyArrayLayers { layers: u32, limit: u32 },
+    #[error("a {view:?} view can't be created for a {texture:?} texture")]
+    IncompatibleViewDimension {
+        texture: TextureDimension,
+        view: TextureViewDimension,
+    },
+    #[error("array layers {start}..{end} are out of range for a texture with {layers} layers")]
+    ArrayLayersOutOfRange { start: u32, end: u32, layers: u32 },
+    #[error("a {view:?} view can't have {layers} array layers")]
+    InvalidViewLayerCount {
+        view: TextureViewDimension,
+        layers: u32,
+    },
+}
+
+/// Checks the size and array layers of `desc` against `limits`.
+///
+/// For [`TextureDimension::D2`] textures, `depth_or_array_layers` is the number of array layers,
+/// for [`TextureDimension::D3`] textures it is the depth.
+pub fn validate_texture_descriptor(
+    desc: &wgpu::TextureDescriptor,
+    limits: &wgpu::Limits,
+) -> Result<(), TextureValidationError> {
+    let size = desc.size;
+    let (limit, extents) = match desc.dimension {
+        TextureDimension::D1 => (limits.max_texture_dimension_1d, [size.width, 1, 1]),
+        TextureDimension::D2 => (
+            limits.max_texture_dimension_2d,
+            [size.width, size.height, 1],
+        ),
+        TextureDimension::D3 => (
+            limits.max_texture_dimension_3d,
+            [size.width, size.height, size.depth_or_array_layers],
+        ),
+    };
+    if extents.iter().any(|&extent| extent > limit) {
+        return Err(TextureValidationError::SizeExceedsLimit {
+            dimension: desc.dimension,
+            size,
+            limit,
+        });
+    }
+
+    if desc.dimension != TextureDimension::D3
+        && size.depth_or_array_layers > limits.max_texture_array_layers
+    {
+        return Err(TextureValidationError::TooManyArrayLayers {
+            layers: size.depth_or_array_layers,
+            limit: limits.max_texture_array_layers,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `desc` describes a valid view of a texture with the given `dimension` and
+/// `depth_or_array_layers`.
+pub fn validate_texture_view_descriptor(
+    dimension: TextureDimension,
+    depth_or_array_layers: u32,
+    desc: &wgpu::TextureViewDescriptor,
+) -> Result<(), TextureValidationError> {
+    let view = desc.dimension.unwrap_or(match dimension {
+        TextureDimension::D1 => TextureViewDimension::D1,
+        TextureDimension::D2 if depth_or_array_layers > 1 => TextureViewDimension::D2Array,
+        TextureDimension::D2 => TextureViewDimension::D2,
+        TextureDimension::D3 => TextureViewDimension::D3,
+    });
+    let compatible = match view {
+        TextureViewDimension::D1 => dimension == TextureDimension::D1,
+        TextureViewDimension::D3 => dimension == TextureDimension::D3,
+        TextureViewDimension::D2
+        | TextureViewDimension::D2Array
+        | TextureViewDimension::Cube
+        | TextureViewDimension::CubeArray => dimension == TextureDimension::D2,
+    };
+    if !compatible {
+        return Err(TextureValidationError::IncompatibleViewDimension {
+            texture: dimension,
+            view,
+        });
+    }
+
+    // Only array layers of 1D and 2D textures can be selected.
+    let layers = if dimension == TextureDimension::D3 {
+        1
+    } else {
+        depth_or_array_layers
+    };
+    let start = desc.base_array_layer;
+    let count = desc
+        .array_layer_count
+        .unwrap_or(layers.saturating_sub(start));
+    let end = start.saturating_add(count);
+    if count == 0 || end > layers {
+        return Err(TextureValidationError::ArrayLayersOutOfRange { start, end, layers });
+    }
+
+    let valid_count = match view {
+        TextureViewDimension::D1 | TextureViewDimension::D2 | TextureViewDimension::D3 => {
+            count == 1
+        }
+        TextureViewDimension::Cube => count == 6,
+        TextureViewDimension::CubeArray => count % 6 == 0,
+        TextureViewDimension::D2Array => true,
+    };
+    if !valid_count {
+        return Err(TextureValidationError::InvalidViewLayerCount {
+            view,
+            layers: count,
+        });
+    }
+    Ok(())
 }
 
 impl From<wgpu::Texture> for Texture {
@@ -184,3 +324,126 @@ impl FromWorld for DefaultImageSampler {
         Self(sampler)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use wgpu::{
+        Extent3d, Limits, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        TextureViewDescriptor, TextureViewDimension,
+    };
+
+    use super::*;
+
+    fn descriptor(dimension: TextureDimension, size: Extent3d) -> TextureDescriptor<'static> {
+        TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        }
+    }
+
+    #[test]
+    fn texture_array_and_layered_views() {
+        let limits = Limits::downlevel_defaults();
+        let size = Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 4,
+        };
+        let array = descriptor(TextureDimension::D2, size);
+        assert_eq!(validate_texture_descriptor(&array, &limits), Ok(()));
+
+        let too_many_layers = Extent3d {
+            depth_or_array_layers: limits.max_texture_array_layers + 1,
+            ..size
+        };
+        assert!(matches!(
+            validate_texture_descriptor(
+                &descriptor(TextureDimension::D2, too_many_layers),
+                &limits
+            ),
+            Err(TextureValidationError::TooManyArrayLayers { .. })
+        ));
+
+        // The whole array, and a single layer of it.
+        assert_eq!(
+            validate_texture_view_descriptor(
+                TextureDimension::D2,
+                4,
+                &TextureViewDescriptor::default()
+            ),
+            Ok(())
+        );
+        let layer = TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2),
+            base_array_layer: 3,
+            array_layer_count: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_texture_view_descriptor(TextureDimension::D2, 4, &layer),
+            Ok(())
+        );
+        let out_of_range = TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2Array),
+            base_array_layer: 2,
+            array_layer_count: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_texture_view_descriptor(TextureDimension::D2, 4, &out_of_range),
+            Err(TextureValidationError::ArrayLayersOutOfRange {
+                start: 2,
+                end: 5,
+                layers: 4
+            })
+        );
+    }
+
+    #[test]
+    fn texture_3d_and_views() {
+        let limits = Limits::downlevel_defaults();
+        let size = Extent3d {
+            width: 32,
+            height: 32,
+            depth_or_array_layers: 32,
+        };
+        let volume = descriptor(TextureDimension::D3, size);
+        assert_eq!(validate_texture_descriptor(&volume, &limits), Ok(()));
+
+        let too_deep = Extent3d {
+            depth_or_array_layers: limits.max_texture_dimension_3d + 1,
+            ..size
+        };
+        assert!(matches!(
+            validate_texture_descriptor(&descriptor(TextureDimension::D3, too_deep), &limits),
+            Err(TextureValidationError::SizeExceedsLimit { .. })
+        ));
+
+        // The depth of a 3D texture isn't an array layer count.
+        assert_eq!(
+            validate_texture_view_descriptor(
+                TextureDimension::D3,
+                32,
+                &TextureViewDescriptor::default()
+            ),
+            Ok(())
+        );
+        let array_view = TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2Array),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_texture_view_descriptor(TextureDimension::D3, 32, &array_view),
+            Err(TextureValidationError::IncompatibleViewDimension {
+                texture: TextureDimension::D3,
+                view: TextureViewDimension::D2Array,
+            })
+        );
+    }
+}
@@ -137,7 +137,7 @@ impl<T: ShaderType + WriteInto> UniformBuffer<T> {
             }));
             self.changed = false;
         } else if let Some(buffer) = &self.buffer {
-            queue.write_buffer(buffer, 0, self.scratch.as_ref());
+            queue.write_buffer_counted(buffer, 0, self.scratch.as_ref());
         }
     }
 }
@@ -196,6 +196,13 @@ impl<T: ShaderType> Default for DynamicUniformBuffer<T> {
 }
 
 impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
+    /// Creates a buffer whose elements are aligned to the
+    /// [`min_uniform_buffer_offset_alignment`](wgpu::Limits::min_uniform_buffer_offset_alignment)
+    /// of `device`, rather than the 256 bytes guaranteed to be valid on every device.
+    pub fn new(device: &RenderDevice) -> Self {
+        Self::new_with_alignment(uniform_offset_alignment(device).get())
+    }
+
     pub fn new_with_alignment(alignment: u64) -> Self {
         Self {
             scratch: DynamicUniformBufferWrapper::new_with_alignment(Vec::new(), alignment),
@@ -227,6 +234,8 @@ impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
     }
 
     /// Push data into the `DynamicUniformBuffer`'s internal vector (residing on system RAM).
+    ///
+    /// Returns the dynamic offset of the data, to be passed when binding the buffer.
     #[inline]
     pub fn push(&mut self, value: &T) -> u32 {
         self.scratch.write(value).unwrap() as u32
@@ -278,15 +287,7 @@ impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
         device: &RenderDevice,
         queue: &'a RenderQueue,
     ) -> Option<DynamicUniformBufferWriter<T>> {
-        let alignment = if cfg!(target_abi = "sim") {
-            // On iOS simulator on silicon macs, metal validation check that the host OS alignment
-            // is respected, but the device reports the correct value for iOS, which is smaller.
-            // Use the larger value.
-            // See https://github.com/gfx-rs/wgpu/issues/7057 - remove if it's not needed anymore.
-            AlignmentValue::new(256)
-        } else {
-            AlignmentValue::new(device.limits().min_uniform_buffer_offset_alignment as u64)
-        };
+        let alignment = uniform_offset_alignment(device);
 
         let mut capacity = self.buffer.as_deref().map(wgpu::Buffer::size).unwrap_or(0);
         let size = alignment
@@ -294,7 +295,7 @@ impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
             .checked_mul(max_count as u64)
             .unwrap();
 
-        if capacity < size || (self.changed && size > 0) {
+        if needs_new_buffer(capacity, size, self.changed) {
             let buffer = device.create_buffer(&BufferDescriptor {
                 label: make_buffer_label::<Self>(&self.label),
                 usage: self.buffer_usage,
@@ -334,7 +335,7 @@ impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
         let capacity = self.buffer.as_deref().map(wgpu::Buffer::size).unwrap_or(0);
         let size = self.scratch.as_ref().len() as u64;
 
-        if capacity < size || (self.changed && size > 0) {
+        if needs_new_buffer(capacity, size, self.changed) {
             self.buffer = Some(device.create_buffer_with_data(&BufferInitDescriptor {
                 label: self.label.as_deref(),
                 usage: self.buffer_usage,
@@ -342,7 +343,7 @@ impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
             }));
             self.changed = false;
         } else if let Some(buffer) = &self.buffer {
-            queue.write_buffer(buffer, 0, self.scratch.as_ref());
+            queue.write_buffer_counted(buffer, 0, self.scratch.as_ref());
         }
     }
 
@@ -353,6 +354,24 @@ impl<T: ShaderType + WriteInto> DynamicUniformBuffer<T> {
     }
 }
 
+/// The alignment of dynamic offsets into uniform buffers on `device`.
+fn uniform_offset_alignment(device: &RenderDevice) -> AlignmentValue {
+    if cfg!(target_abi = "sim") {
+        // On iOS simulator on silicon macs, metal validation check that the host OS alignment
+        // is respected, but the device reports the correct value for iOS, which is smaller.
+        // Use the larger value.
+        // See https://github.com/gfx-rs/wgpu/issues/7057 - remove if it's not needed anymore.
+        AlignmentValue::new(256)
+    } else {
+        AlignmentValue::new(device.limits().min_uniform_buffer_offset_alignment as u64)
+    }
+}
+
+/// Whether the GPU-side buffer must be recreated to hold `size` bytes, rather than written to.
+fn needs_new_buffer(capacity: u64, size: u64, changed: bool) -> bool {
+    capacity < size || (changed && size > 0)
+}
+
 /// A writer that can be used to directly write elements into the target buffer.
 ///
 /// For more information, see [`DynamicUniformBuffer::get_writer`].
@@ -403,3 +422,35 @@ impl<'a, T: ShaderType + WriteInto> IntoBinding<'a> for &'a DynamicUniformBuffer
         self.binding().unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dynamic_offsets_respect_alignment() {
+        for alignment in [64, 256] {
+            let mut buffer = DynamicUniformBuffer::<u32>::new_with_alignment(alignment);
+            let offsets = [buffer.push(&1), buffer.push(&2), buffer.push(&3)];
+
+            assert_eq!(offsets, [0, alignment as u32, 2 * alignment as u32]);
+
+            buffer.clear();
+            assert!(buffer.is_empty());
+            assert_eq!(buffer.push(&4), 0);
+        }
+    }
+
+    #[test]
+    fn buffer_is_reused_when_large_enough() {
+        // The first write creates the buffer.
+        assert!(needs_new_buffer(0, 512, false));
+        // Later writes of the same or less data reuse it.
+        assert!(!needs_new_buffer(512, 512, false));
+        assert!(!needs_new_buffer(512, 256, false));
+        // It grows when the data outgrows it, or its label or usages changed.
+        assert!(needs_new_buffer(512, 768, false));
+        assert!(needs_new_buffer(512, 256, true));
+        assert!(!needs_new_buffer(512, 0, true));
+    }
+}
@@ -0,0 +1,178 @@
+use bevy_ecs::{
+    resource::Resource,
+    world::{FromWorld, World},
+};
+use bevy_platform::collections::HashMap;
+use wgpu::{
+    Backend, DeviceType, DownlevelCapabilities, Features, Limits, TextureFormat,
+    TextureFormatFeatures,
+};
+
+use super::{RenderAdapter, RenderAdapterInfo, RenderDevice};
+
+/// A summary of what the current renderer can do, so plugins can make one decision instead of
+/// querying the adapter and device separately.
+///
+/// This is built in the render world during [`RenderStartup`](crate::RenderStartup), and rebuilt
+/// whenever the renderer is reinitialized with a new device.
+#[derive(Resource, Clone, Debug)]
+pub struct RenderCapabilities {
+    pub backend: Backend,
+    pub device_type: DeviceType,
+    pub adapter_name: String,
+    /// The features of the [`RenderDevice`], which can be fewer than the adapter supports.
+    pub features: Features,
+    /// The limits of the [`RenderDevice`].
+    pub limits: Limits,
+    pub downlevel: DownlevelCapabilities,
+    /// The MSAA sample counts usable with each of [`RenderCapabilities::SAMPLE_COUNT_FORMATS`].
+    pub sample_counts: HashMap<TextureFormat, Vec<u32>>,
+}
+
+impl RenderCapabilities {
+    /// The formats [`RenderCapabilities::sample_counts`] are collected for.
+    pub const SAMPLE_COUNT_FORMATS: [TextureFormat; 7] = [
+        TextureFormat::Rgba8UnormSrgb,
+        TextureFormat::Bgra8UnormSrgb,
+        TextureFormat::Rgba16Float,
+        TextureFormat::Rg11b10Ufloat,
+        TextureFormat::Depth32Float,
+        TextureFormat::Depth24PlusStencil8,
+        TextureFormat::Depth16Unorm,
+    ];
+
+    /// Creates the summary, looking up the features of each format with `format_features`.
+    pub fn new(
+        backend: Backend,
+        device_type: DeviceType,
+        adapter_name: String,
+        features: Features,
+        limits: Limits,
+        downlevel: DownlevelCapabilities,
+        format_features: impl Fn(TextureFormat) -> TextureFormatFeatures,
+    ) -> Self {
+        let sample_counts = Self::SAMPLE_COUNT_FORMATS
+            .into_iter()
+            .map(|format| {
+                (
+                    format,
+                    format_features(format).flags.supported_sample_counts(),
+                )
+            })
+            .collect();
+        Self {
+            backend,
+            device_type,
+            adapter_name,
+            features,
+            limits,
+            downlevel,
+            sample_counts,
+        }
+    }
+
+    /// The MSAA sample counts usable with `format`, if it is one of
+    /// [`RenderCapabilities::SAMPLE_COUNT_FORMATS`].
+    pub fn sample_counts(&self, format: TextureFormat) -> Option<&[u32]> {
+        self.sample_counts.get(&format).map(Vec::as_slice)
+    }
+
+    /// Whether `format` supports `samples` MSAA samples.
+    pub fn supports_sample_count(&self, format: TextureFormat, samples: u32) -> bool {
+        self.sample_counts(format)
+            .is_some_and(|counts| counts.contains(&samples))
+    }
+
+    /// The largest width and height of a 2D texture.
+    pub fn max_texture_size_2d(&self) -> u32 {
+        self.limits.max_texture_dimension_2d
+    }
+
+    /// The largest width, height and depth of a 3D texture.
+    pub fn max_texture_size_3d(&self) -> u32 {
+        self.limits.max_texture_dimension_3d
+    }
+
+    /// The largest number of layers of a texture array.
+    pub fn max_texture_array_layers(&self) -> u32 {
+        self.limits.max_texture_array_layers
+    }
+}
+
+impl FromWorld for RenderCapabilities {
+    fn from_world(world: &mut World) -> Self {
+        let adapter = world.resource::<RenderAdapter>();
+        let adapter_info = world.resource::<RenderAdapterInfo>();
+        let device = world.resource::<RenderDevice>();
+        let features = device.features();
+
+        // Adapter specific format features can only be used if the device enabled them.
+        let adapter_specific =
+            features.contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+        Self::new(
+            adapter_info.backend,
+            adapter_info.device_type,
+            adapter_info.name.clone(),
+            features,
+            device.limits(),
+            adapter.get_downlevel_capabilities(),
+            |format| {
+                if adapter_specific {
+                    adapter.get_texture_format_features(format)
+                } else {
+                    format.guaranteed_format_features(features)
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::{
+        Backend, DeviceType, DownlevelCapabilities, Features, Limits, TextureFormat,
+        TextureFormatFeatureFlags, TextureFormatFeatures, TextureUsages,
+    };
+
+    use super::RenderCapabilities;
+
+    #[test]
+    fn capabilities_match_device_values() {
+        let limits = Limits::downlevel_webgl2_defaults();
+        let capabilities = RenderCapabilities::new(
+            Backend::Gl,
+            DeviceType::IntegratedGpu,
+            "test adapter".into(),
+            Features::DEPTH_CLIP_CONTROL,
+            limits.clone(),
+            DownlevelCapabilities::default(),
+            |format| TextureFormatFeatures {
+                allowed_usages: TextureUsages::RENDER_ATTACHMENT,
+                flags: if format.is_depth_stencil_format() {
+                    TextureFormatFeatureFlags::MULTISAMPLE_X4
+                } else {
+                    TextureFormatFeatureFlags::MULTISAMPLE_X2
+                        | TextureFormatFeatureFlags::MULTISAMPLE_X4
+                },
+            },
+        );
+
+        assert_eq!(capabilities.backend, Backend::Gl);
+        assert_eq!(capabilities.features, Features::DEPTH_CLIP_CONTROL);
+        assert_eq!(
+            capabilities.max_texture_size_2d(),
+            limits.max_texture_dimension_2d
+        );
+        assert_eq!(
+            capabilities.max_texture_array_layers(),
+            limits.max_texture_array_layers
+        );
+        assert_eq!(
+            capabilities.sample_counts(TextureFormat::Rgba16Float),
+            Some(&[1, 2, 4][..])
+        );
+        assert!(capabilities.supports_sample_count(TextureFormat::Depth32Float, 4));
+        assert!(!capabilities.supports_sample_count(TextureFormat::Depth32Float, 2));
+        assert_eq!(capabilities.sample_counts(TextureFormat::R8Unorm), None);
+    }
+}
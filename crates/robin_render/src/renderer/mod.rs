@@ -1,18 +1,25 @@
+mod capabilities;
 #[cfg(feature = "raw_vulkan_init")]
 pub mod raw_vulkan_init;
 mod render_context;
 mod render_device;
+mod texture_data;
 mod wgpu_wrapper;
 
+pub use capabilities::RenderCapabilities;
 pub use render_context::{
     CurrentView, FlushCommands, PendingCommandBuffers, RenderContext, RenderContextState, ViewQuery,
 };
 pub use render_device::*;
+pub use texture_data::{TextureDataError, texture_data_layout};
 pub use wgpu_wrapper::WgpuWrapper;
 
 use crate::{
-    settings::{RenderResources, WgpuSettings, WgpuSettingsPriority},
-    view::{ExtractedWindows, ViewTarget},
+    diagnostic::FrameStatisticsCounters,
+    settings::{
+        RenderInitProgress, RenderInitStage, RenderResources, WgpuSettings, WgpuSettingsPriority,
+    },
+    view::{ExtractedWindows, ViewTarget, display::PresentStatistics},
 };
 use alloc::sync::Arc;
 use bevy_camera::NormalizedRenderTarget;
@@ -20,8 +27,10 @@ use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::schedule::ScheduleLabel;
 use bevy_ecs::{prelude::*, system::SystemState};
 use bevy_log::{debug, info, info_span, warn};
+use bevy_platform::time::Instant;
 use bevy_render::camera::ExtractedCamera;
 use bevy_window::RawHandleWrapperHolder;
+use thiserror::Error;
 use wgpu::{
     Adapter, AdapterInfo, Backends, DeviceType, ForceShaderModelToken, Instance, Queue,
     RequestAdapterOptions, Trace,
@@ -88,8 +97,10 @@ pub fn render_system(
 
     {
         let _span = info_span!("present_frames").entered();
+        let mut presented_windows = Vec::new();
 
         world.resource_scope(|world, mut windows: Mut<ExtractedWindows>| {
+            let frame_statistics = world.resource::<FrameStatisticsCounters>().clone();
             let views = state.get(world).unwrap();
             for window in windows.values_mut() {
                 let view_needs_present = views.iter().any(|(view_target, camera)| {
@@ -100,12 +111,27 @@ pub fn render_system(
                 });
 
                 if view_needs_present || window.needs_initial_present {
-                    window.present();
+                    let ((), present_time) = crate::diagnostic::timed(|| {
+                        let _span = info_span!("present_window").entered();
+                        window.present();
+                    });
+                    frame_statistics.add(&crate::diagnostic::RenderFrameStatistics {
+                        present_time,
+                        ..Default::default()
+                    });
                     window.needs_initial_present = false;
+                    presented_windows.push(window.entity);
                 }
             }
         });
 
+        if let Some(mut statistics) = world.get_resource_mut::<PresentStatistics>() {
+            let now = Instant::now();
+            for window in presented_windows {
+                statistics.record(window, now);
+            }
+        }
+
         #[cfg(feature = "tracing-tracy")]
         bevy_log::event!(
             bevy_log::Level::INFO,
@@ -119,13 +145,104 @@ pub fn render_system(
 
 /// This queue is used to enqueue tasks for the GPU to execute asynchronously.
 #[derive(Resource, Clone, Deref, DerefMut)]
-pub struct RenderQueue(pub Arc<WgpuWrapper<Queue>>);
+pub struct RenderQueue {
+    #[deref]
+    queue: Arc<WgpuWrapper<Queue>>,
+    frame_statistics: FrameStatisticsCounters,
+}
+
+impl RenderQueue {
+    /// Wraps `queue`, counting its work in the [`RenderDevice::frame_statistics`] of `device`.
+    pub fn new(queue: Queue, device: &RenderDevice) -> Self {
+        Self {
+            queue: Arc::new(WgpuWrapper::new(queue)),
+            frame_statistics: device.frame_statistics().clone(),
+        }
+    }
+
+    /// Schedules a write of `data` into `buffer` at `offset`, counting it in the
+    /// [`RenderFrameStatistics`](crate::diagnostic::RenderFrameStatistics) of the frame.
+    ///
+    /// See [`Queue::write_buffer`] for more information.
+    pub fn write_buffer_counted(
+        &self,
+        buffer: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: &[u8],
+    ) {
+        self.frame_statistics
+            .add(&crate::diagnostic::RenderFrameStatistics {
+                buffer_writes: 1,
+                bytes_uploaded: data.len() as u64,
+                ..Default::default()
+            });
+        self.queue.write_buffer(buffer, offset, data);
+    }
+
+    /// Schedules a write of `data` into `texture`, counting the uploaded bytes in the
+    /// [`RenderFrameStatistics`](crate::diagnostic::RenderFrameStatistics) of the frame.
+    ///
+    /// See [`Queue::write_texture`] for more information.
+    pub fn write_texture_counted(
+        &self,
+        texture: wgpu::TexelCopyTextureInfo,
+        data: &[u8],
+        data_layout: wgpu::TexelCopyBufferLayout,
+        size: wgpu::Extent3d,
+    ) {
+        self.frame_statistics
+            .add(&crate::diagnostic::RenderFrameStatistics {
+                texture_writes: 1,
+                bytes_uploaded: data.len() as u64,
+                ..Default::default()
+            });
+        self.queue.write_texture(texture, data, data_layout, size);
+    }
+
+    /// Submits `command_buffers` for execution, counting the time the submission takes in the
+    /// [`RenderFrameStatistics`](crate::diagnostic::RenderFrameStatistics) of the frame.
+    ///
+    /// Pass the returned index to [`RenderDevice::poll_for`] to wait until the GPU finished
+    /// executing exactly these command buffers.
+    pub fn submit<I: IntoIterator<Item = wgpu::CommandBuffer>>(
+        &self,
+        command_buffers: I,
+    ) -> wgpu::SubmissionIndex {
+        let (index, submit_time) = crate::diagnostic::timed(|| {
+            let _span = info_span!("queue_submit").entered();
+            #[cfg(test)]
+            std::thread::sleep(SUBMIT_DELAY.get());
+            self.queue.submit(command_buffers)
+        });
+        self.frame_statistics
+            .add(&crate::diagnostic::RenderFrameStatistics {
+                submit_time,
+                ..Default::default()
+            });
+        index
+    }
+}
+
+#[cfg(test)]
+std::thread_local! {
+    /// The time [`RenderQueue::submit`] additionally takes on this thread, to test slow
+    /// submissions.
+    pub(crate) static SUBMIT_DELAY: core::cell::Cell<core::time::Duration> =
+        const { core::cell::Cell::new(core::time::Duration::ZERO) };
+}
 
 /// The handle to the physical device being used for rendering.
 /// See [`Adapter`] for more info.
 #[derive(Resource, Clone, Debug, Deref, DerefMut)]
 pub struct RenderAdapter(pub Arc<WgpuWrapper<Adapter>>);
 
+/// The number of [`RenderDevice`]s the renderer installed so far, in both worlds.
+///
+/// It is 1 for the first device and incremented every time a new device replaces a lost one, so
+/// resources created on an older generation can be told apart.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DeviceGeneration(pub u64);
+
 /// The GPU instance is used to initialize the [`RenderQueue`] and [`RenderDevice`],
 /// as well as to create [`WindowSurfaces`](crate::view::window::WindowSurfaces).
 #[derive(Resource, Clone, Deref, DerefMut)]
@@ -141,12 +258,27 @@ const GPU_NOT_FOUND_ERROR_MESSAGE: &str = if cfg!(target_os = "linux") {
     "Unable to find a GPU! Make sure you have installed required drivers!"
 };
 
+/// Whether an adapter with the given `name` and `backend` matches the optional filters of
+/// [`WgpuSettings::adapter_name`] and [`WgpuSettings::preferred_backend`].
+#[cfg(any(test, not(target_family = "wasm")))]
+fn adapter_matches(
+    name: &str,
+    backend: wgpu::Backend,
+    name_filter: Option<&str>,
+    backend_filter: Option<wgpu::Backend>,
+) -> bool {
+    name_filter.is_none_or(|filter| name.to_lowercase().contains(&filter.to_lowercase()))
+        && backend_filter.is_none_or(|filter| backend == filter)
+}
+
+/// Returns the first enumerated adapter matching the filters, see [`adapter_matches`].
 #[cfg(not(target_family = "wasm"))]
-async fn find_adapter_by_name(
+async fn find_adapter(
     instance: &Instance,
     options: &WgpuSettings,
     compatible_surface: Option<&wgpu::Surface<'_>>,
-    adapter_name: &str,
+    name_filter: Option<&str>,
+    backend_filter: Option<wgpu::Backend>,
 ) -> Option<Adapter> {
     for adapter in instance
         .enumerate_adapters(options.backends.expect(
@@ -162,26 +294,47 @@ async fn find_adapter_by_name(
             continue;
         }
 
-        if info
-            .name
-            .to_lowercase()
-            .contains(&adapter_name.to_lowercase())
-        {
+        if adapter_matches(&info.name, info.backend, name_filter, backend_filter) {
             return Some(adapter);
         }
     }
     None
 }
 
+/// An error creating the renderer in [`initialize_renderer`].
+#[derive(Error, Debug)]
+pub enum RenderCreationError {
+    #[error("{}", GPU_NOT_FOUND_ERROR_MESSAGE)]
+    AdapterNotFound,
+    /// No adapter matches [`WgpuSettings::adapter_name`].
+    #[error("no adapter named like \"{0}\" was found")]
+    NamedAdapterNotFound(String),
+    /// The adapter doesn't support some of [`WgpuSettings::features`].
+    #[error(transparent)]
+    UnsupportedFeatures(#[from] UnsupportedFeatureError),
+    #[error("failed to request a device: {0}")]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+    /// Opening the Vulkan device with the callbacks of
+    /// [`RawVulkanInitSettings`](raw_vulkan_init::RawVulkanInitSettings) failed.
+    #[cfg(feature = "raw_vulkan_init")]
+    #[error("failed to create the raw Vulkan device: {0}")]
+    RawVulkanDevice(#[from] raw_vulkan_init::CreateRawVulkanDeviceError),
+}
+
 /// Initializes the renderer by retrieving and preparing the GPU instance, device and queue
 /// for the specified backend.
+///
+/// Fails if no adapter is found, or if the adapter doesn't support all of
+/// [`WgpuSettings::features`].
 pub async fn initialize_renderer(
     backends: Backends,
     primary_window: Option<RawHandleWrapperHolder>,
     options: &WgpuSettings,
+    progress: &RenderInitProgress,
     #[cfg(feature = "raw_vulkan_init")]
     raw_vulkan_init_settings: raw_vulkan_init::RawVulkanInitSettings,
-) -> RenderResources {
+) -> Result<RenderResources, RenderCreationError> {
+    progress.set(RenderInitStage::CreatingInstance);
     let instance_descriptor = wgpu::InstanceDescriptor {
         backends,
         flags: options.instance_flags,
@@ -216,23 +369,26 @@ pub async fn initialize_renderer(
         &mut additional_vulkan_features,
     );
 
-    let surface = primary_window.and_then(|wrapper| {
-        let maybe_handle = wrapper
-            .0
-            .lock()
-            .expect("Couldn't get the window handle in time for renderer initialization");
-        if let Some(wrapper) = maybe_handle.as_ref() {
-            // SAFETY: Plugins should be set up on the main thread.
-            let handle = unsafe { wrapper.get_handle() };
-            Some(
-                instance
-                    .create_surface(handle)
-                    .expect("Failed to create wgpu surface"),
-            )
-        } else {
-            None
-        }
-    });
+    // Without a surface, adapters are selected regardless of their presentation support.
+    let surface = primary_window
+        .filter(|_| !options.headless)
+        .and_then(|wrapper| {
+            let maybe_handle = wrapper
+                .0
+                .lock()
+                .expect("Couldn't get the window handle in time for renderer initialization");
+            if let Some(wrapper) = maybe_handle.as_ref() {
+                // SAFETY: Plugins should be set up on the main thread.
+                let handle = unsafe { wrapper.get_handle() };
+                Some(
+                    instance
+                        .create_surface(handle)
+                        .expect("Failed to create wgpu surface"),
+                )
+            } else {
+                None
+            }
+        });
 
     let force_fallback_adapter = std::env::var("WGPU_FORCE_FALLBACK_ADAPTER")
         .map_or(options.force_fallback_adapter, |v| {
@@ -250,26 +406,37 @@ pub async fn initialize_renderer(
     };
 
     #[cfg(not(target_family = "wasm"))]
-    let mut selected_adapter = if let Some(adapter_name) = desired_adapter_name {
-        find_adapter_by_name(
-            &instance,
-            options,
-            request_adapter_options.compatible_surface,
-            &adapter_name,
-        )
-        .await
-    } else {
-        None
-    };
+    let mut selected_adapter =
+        if desired_adapter_name.is_some() || options.preferred_backend.is_some() {
+            progress.set(RenderInitStage::EnumeratingAdapters);
+            let adapter = find_adapter(
+                &instance,
+                options,
+                request_adapter_options.compatible_surface,
+                desired_adapter_name.as_deref(),
+                options.preferred_backend,
+            )
+            .await;
+            // Silently using another GPU than the one asked for is worse than failing.
+            if adapter.is_none()
+                && let Some(adapter_name) = desired_adapter_name
+            {
+                return Err(RenderCreationError::NamedAdapterNotFound(adapter_name));
+            }
+            adapter
+        } else {
+            None
+        };
     #[cfg(target_family = "wasm")]
     let mut selected_adapter = None;
 
     #[cfg(target_family = "wasm")]
-    if desired_adapter_name.is_some() {
+    if desired_adapter_name.is_some() || options.preferred_backend.is_some() {
         warn!("Choosing an adapter is not supported on wasm.");
     }
 
     if selected_adapter.is_none() {
+        progress.set(RenderInitStage::RequestingAdapter);
         debug!(
             "Searching for adapter with options: {:?}",
             request_adapter_options
@@ -280,9 +447,24 @@ pub async fn initialize_renderer(
             .ok();
     }
 
-    let adapter = selected_adapter.expect(GPU_NOT_FOUND_ERROR_MESSAGE);
+    // Servers and CI machines often have no GPU, but a software adapter is good enough there.
+    if selected_adapter.is_none() && options.headless && !force_fallback_adapter {
+        debug!("No hardware adapter found, searching for a software adapter");
+        selected_adapter = instance
+            .request_adapter(&RequestAdapterOptions {
+                force_fallback_adapter: true,
+                ..request_adapter_options
+            })
+            .await
+            .ok();
+    }
+
+    let adapter = selected_adapter.ok_or(RenderCreationError::AdapterNotFound)?;
     let adapter_info = adapter.get_info();
-    info!("{:?}", adapter_info);
+    info!(
+        "Using adapter \"{}\": {:?}",
+        adapter_info.name, adapter_info
+    );
 
     if adapter_info.device_type == DeviceType::Cpu {
         warn!(
@@ -291,9 +473,11 @@ pub async fn initialize_renderer(
         );
     }
 
+    // Fail before requesting the device, which would only report one missing feature.
+    UnsupportedFeatureError::check(adapter.features(), options.features)?;
+
     // Maybe get features and limits based on what is supported by the adapter/backend
     let mut features = wgpu::Features::empty();
-    let mut limits = options.limits.clone();
     if matches!(options.priority, WgpuSettingsPriority::Functionality) {
         features = adapter.features();
         if adapter_info.device_type == DeviceType::DiscreteGpu {
@@ -303,8 +487,18 @@ pub async fn initialize_renderer(
             // integrated GPUs.
             features.remove(wgpu::Features::MAPPABLE_PRIMARY_BUFFERS);
         }
+    }
 
-        limits = adapter.limits();
+    let ungranted_features = options.optional_features - adapter.features();
+    if !ungranted_features.is_empty() {
+        warn!(
+            "The adapter does not support the optional features {:?}, they will not be enabled",
+            ungranted_features
+        );
+    }
+    features |= options.optional_features & adapter.features();
+    if options.pipeline_cache_dir.is_some() {
+        features |= wgpu::Features::PIPELINE_CACHE & adapter.features();
     }
 
     // Enforce the disabled features
@@ -314,15 +508,7 @@ pub async fn initialize_renderer(
     // NOTE: |= is used here to ensure that any explicitly-enabled features are respected.
     features |= options.features;
 
-    // Enforce the limit constraints
-    if let Some(constrained_limits) = options.constrained_limits.as_ref() {
-        // NOTE: Respect the configured limits as an 'upper bound'. This means for 'max' limits, we
-        // take the minimum of the calculated limits according to the adapter/backend and the
-        // specified max_limits. For 'min' limits, take the maximum instead. This is intended to
-        // err on the side of being conservative. We can't claim 'higher' limits that are supported
-        // but we can constrain to 'lower' limits.
-        limits = limits.or_worse_values_from(constrained_limits);
-    }
+    let limits = device_limits(options, adapter.limits());
 
     let device_descriptor = wgpu::DeviceDescriptor {
         label: options.device_label.as_ref().map(AsRef::as_ref),
@@ -335,29 +521,274 @@ pub async fn initialize_renderer(
         trace: Trace::Off,
     };
 
+    progress.set(RenderInitStage::RequestingDevice);
+
     #[cfg(not(feature = "raw_vulkan_init"))]
-    let (device, queue) = adapter.request_device(&device_descriptor).await.unwrap();
+    let (device, queue) = adapter.request_device(&device_descriptor).await?;
 
     #[cfg(feature = "raw_vulkan_init")]
-    let (device, queue) = raw_vulkan_init::create_raw_device(
+    let (device, queue, additional_vulkan_queues) = raw_vulkan_init::create_raw_device(
         &adapter,
         &device_descriptor,
         &raw_vulkan_init_settings,
         &mut additional_vulkan_features,
     )
-    .await
-    .unwrap();
+    .await?;
 
     debug!("Configured wgpu adapter Limits: {:#?}", device.limits());
     debug!("Configured wgpu adapter Features: {:#?}", device.features());
 
-    RenderResources(
-        RenderDevice::from(device),
-        RenderQueue(Arc::new(WgpuWrapper::new(queue))),
+    let render_device = RenderDevice::from(device);
+    let render_queue = RenderQueue::new(queue, &render_device);
+    Ok(RenderResources(
+        render_device,
+        render_queue,
         RenderAdapterInfo(WgpuWrapper::new(adapter_info)),
         RenderAdapter(Arc::new(WgpuWrapper::new(adapter))),
         RenderInstance(Arc::new(WgpuWrapper::new(instance))),
         #[cfg(feature = "raw_vulkan_init")]
         additional_vulkan_features,
+        #[cfg(feature = "raw_vulkan_init")]
+        additional_vulkan_queues,
+    ))
+}
+
+/// The limits to request the device with: the adapter's with
+/// [`WgpuSettingsPriority::Functionality`], the requested ones otherwise, constrained by
+/// [`WgpuSettings::constrained_limits`].
+fn device_limits(options: &WgpuSettings, adapter_limits: wgpu::Limits) -> wgpu::Limits {
+    let limits = if matches!(options.priority, WgpuSettingsPriority::Functionality) {
+        adapter_limits
+    } else {
+        options.limits.clone()
+    };
+
+    // Enforce the limit constraints
+    match options.constrained_limits.as_ref() {
+        // NOTE: Respect the configured limits as an 'upper bound'. This means for 'max' limits, we
+        // take the minimum of the calculated limits according to the adapter/backend and the
+        // specified max_limits. For 'min' limits, take the maximum instead. This is intended to
+        // err on the side of being conservative. We can't claim 'higher' limits that are supported
+        // but we can constrain to 'lower' limits.
+        Some(constrained_limits) => limits.or_worse_values_from(constrained_limits),
+        None => limits,
+    }
+}
+
+/// Requests a new device and queue from the adapter of `previous`, with the same features and
+/// limits as its device. The instance and adapter are kept, so their identity is preserved.
+///
+/// This is used to recover from a lost device without recreating the instance and adapter.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn recreate_device(
+    previous: RenderResources,
+    #[cfg(feature = "raw_vulkan_init")]
+    raw_vulkan_init_settings: raw_vulkan_init::RawVulkanInitSettings,
+) -> Option<RenderResources> {
+    #[cfg_attr(
+        not(feature = "raw_vulkan_init"),
+        expect(unused_mut, reason = "only mutated to replace the Vulkan resources")
+    )]
+    let mut resources = previous;
+    let device_descriptor = wgpu::DeviceDescriptor {
+        label: Some("recovered_device"),
+        required_features: resources.0.features(),
+        required_limits: resources.0.limits(),
+        // SAFETY: TODO, see https://github.com/bevyengine/bevy/issues/22082
+        experimental_features: unsafe { wgpu::ExperimentalFeatures::enabled() },
+        memory_hints: Default::default(),
+        // See https://github.com/gfx-rs/wgpu/issues/5974
+        trace: Trace::Off,
+    };
+
+    #[cfg(not(feature = "raw_vulkan_init"))]
+    let result = resources.3.request_device(&device_descriptor).await;
+
+    #[cfg(feature = "raw_vulkan_init")]
+    let result = raw_vulkan_init::create_raw_device(
+        &resources.3,
+        &device_descriptor,
+        &raw_vulkan_init_settings,
+        &mut resources.5,
+    )
+    .await
+    .map(|(device, queue, additional_vulkan_queues)| {
+        resources.6 = additional_vulkan_queues;
+        (device, queue)
+    });
+
+    match result {
+        Ok((device, queue)) => {
+            resources.0 = RenderDevice::from(device);
+            resources.1 = RenderQueue::new(queue, &resources.0);
+            Some(resources)
+        }
+        Err(err) => {
+            warn!("Failed to recreate the device from the existing adapter: {err}");
+            None
+        }
+    }
+}
+
+/// Creates [`RenderResources`] on the `wgpu` noop backend, which needs no GPU, with a device
+/// supporting the given `features`.
+#[cfg(test)]
+pub(crate) fn noop_render_resources(features: wgpu::Features) -> RenderResources {
+    let instance = Instance::new(wgpu::InstanceDescriptor {
+        backends: Backends::NOOP,
+        flags: wgpu::InstanceFlags::empty(),
+        memory_budget_thresholds: Default::default(),
+        display: None,
+        backend_options: wgpu::BackendOptions {
+            noop: wgpu::NoopBackendOptions { enable: true },
+            ..Default::default()
+        },
+    });
+    let adapter = bevy_tasks::block_on(instance.request_adapter(&RequestAdapterOptions::default()))
+        .expect("the noop backend always has an adapter");
+    let (device, queue) = bevy_tasks::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        required_features: features,
+        ..Default::default()
+    }))
+    .expect("the noop adapter supports every feature");
+    let adapter_info = adapter.get_info();
+    let render_device = RenderDevice::from(device);
+    let render_queue = RenderQueue::new(queue, &render_device);
+    RenderResources(
+        render_device,
+        render_queue,
+        RenderAdapterInfo(WgpuWrapper::new(adapter_info)),
+        RenderAdapter(Arc::new(WgpuWrapper::new(adapter))),
+        RenderInstance(Arc::new(WgpuWrapper::new(instance))),
+        #[cfg(feature = "raw_vulkan_init")]
+        Default::default(),
+        #[cfg(feature = "raw_vulkan_init")]
+        Default::default(),
     )
 }
+
+/// Creates [`RenderResources`] on an actual adapter, which may be a software one like lavapipe, to
+/// test what the noop backend can't, e.g. the result of rendering.
+///
+/// Returns `None` if there is no adapter, e.g. on machines without a GPU or software renderer, and
+/// prints that the calling test is skipped.
+#[cfg(test)]
+pub(crate) fn gpu_render_resources() -> Option<RenderResources> {
+    let resources = request_gpu_render_resources();
+    if resources.is_none() {
+        // The test harness names threads after their test.
+        let thread = std::thread::current();
+        let test = thread.name().unwrap_or("test");
+        eprintln!("skipping {test}: no adapter to create a device on");
+    }
+    resources
+}
+
+#[cfg(test)]
+fn request_gpu_render_resources() -> Option<RenderResources> {
+    let instance = Instance::new(wgpu::InstanceDescriptor {
+        backends: Backends::PRIMARY,
+        flags: wgpu::InstanceFlags::empty(),
+        memory_budget_thresholds: Default::default(),
+        display: None,
+        backend_options: Default::default(),
+    });
+    let adapter = [false, true]
+        .into_iter()
+        .find_map(|force_fallback_adapter| {
+            bevy_tasks::block_on(instance.request_adapter(&RequestAdapterOptions {
+                force_fallback_adapter,
+                ..Default::default()
+            }))
+            .ok()
+        })?;
+    let (device, queue) =
+        bevy_tasks::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).ok()?;
+    Some(RenderResources::from_wgpu(instance, adapter, device, queue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adapter_matches, device_limits};
+    use crate::settings::{WgpuSettings, WgpuSettingsPriority};
+
+    fn adapter_limits() -> wgpu::Limits {
+        wgpu::Limits {
+            max_texture_dimension_2d: 16384,
+            max_bind_groups: 8,
+            min_uniform_buffer_offset_alignment: 64,
+            ..wgpu::Limits::default()
+        }
+    }
+
+    #[test]
+    fn functionality_uses_adapter_limits() {
+        let settings = WgpuSettings {
+            priority: WgpuSettingsPriority::Functionality,
+            ..Default::default()
+        };
+
+        assert_eq!(device_limits(&settings, adapter_limits()), adapter_limits());
+    }
+
+    #[test]
+    fn compatibility_uses_requested_limits() {
+        let settings = WgpuSettings {
+            priority: WgpuSettingsPriority::Compatibility,
+            limits: wgpu::Limits::downlevel_defaults(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            device_limits(&settings, adapter_limits()),
+            wgpu::Limits::downlevel_defaults()
+        );
+    }
+
+    #[test]
+    fn constrained_limits_are_an_upper_bound() {
+        let settings = WgpuSettings {
+            priority: WgpuSettingsPriority::Functionality,
+            constrained_limits: Some(wgpu::Limits {
+                max_texture_dimension_2d: 4096,
+                max_bind_groups: 16,
+                min_uniform_buffer_offset_alignment: 256,
+                ..adapter_limits()
+            }),
+            ..Default::default()
+        };
+        let limits = device_limits(&settings, adapter_limits());
+
+        // Maximums take the lower value, minimums the higher one.
+        assert_eq!(limits.max_texture_dimension_2d, 4096);
+        assert_eq!(limits.max_bind_groups, 8);
+        assert_eq!(limits.min_uniform_buffer_offset_alignment, 256);
+    }
+
+    #[test]
+    fn adapter_filters() {
+        use wgpu::Backend;
+
+        let name = "NVIDIA GeForce RTX 4070 Laptop GPU";
+        assert!(adapter_matches(name, Backend::Vulkan, None, None));
+        assert!(adapter_matches(
+            name,
+            Backend::Vulkan,
+            Some("geforce"),
+            None
+        ));
+        assert!(!adapter_matches(name, Backend::Vulkan, Some("intel"), None));
+        assert!(adapter_matches(
+            name,
+            Backend::Vulkan,
+            Some("RTX"),
+            Some(Backend::Vulkan)
+        ));
+        assert!(!adapter_matches(
+            name,
+            Backend::Gl,
+            None,
+            Some(Backend::Vulkan)
+        ));
+    }
+}
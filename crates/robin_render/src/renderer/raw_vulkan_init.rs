@@ -1,4 +1,5 @@
 use alloc::sync::Arc;
+use ash::vk;
 use bevy_ecs::resource::Resource;
 use bevy_platform::collections::HashSet;
 use core::any::{Any, TypeId};
@@ -31,6 +32,7 @@ pub struct RawVulkanInitSettings {
                 + Sync,
         >,
     >,
+    queue_requests: Vec<VulkanQueueRequest>,
 }
 
 impl RawVulkanInitSettings {
@@ -68,6 +70,103 @@ impl RawVulkanInitSettings {
     ) {
         self.create_device_callbacks.push(Arc::new(callback));
     }
+
+    /// Requests an additional device queue, e.g. a dedicated transfer queue for uploads.
+    ///
+    /// Returns the index of the queue in [`AdditionalVulkanQueues`]. The queue is created in the
+    /// queue family that supports `request.flags` with the fewest other capabilities. If the
+    /// adapter has no spare queue matching the request, which is common on drivers exposing a
+    /// single queue, the main wgpu queue is exposed instead and
+    /// [`AdditionalVulkanQueue::aliases_main_queue`] is set.
+    ///
+    /// Queues are requested after all create device callbacks ran.
+    pub fn request_additional_queue(&mut self, request: VulkanQueueRequest) -> usize {
+        self.queue_requests.push(request);
+        self.queue_requests.len() - 1
+    }
+}
+
+/// A request for an additional Vulkan queue, see [`RawVulkanInitSettings::request_additional_queue`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VulkanQueueRequest {
+    /// The capabilities the queue must support.
+    pub flags: vk::QueueFlags,
+    /// The priority of the queue, between `0.0` and `1.0`. The main wgpu queue uses `1.0`.
+    pub priority: f32,
+}
+
+/// A queue created for a [`VulkanQueueRequest`].
+#[derive(Clone, Copy, Debug)]
+pub struct AdditionalVulkanQueue {
+    /// The request this queue was created for.
+    pub request: VulkanQueueRequest,
+    /// The queue family the queue belongs to.
+    pub family_index: u32,
+    /// The index of the queue within its family.
+    pub queue_index: u32,
+    /// The raw Vulkan queue.
+    pub raw: vk::Queue,
+    /// Whether this is the main wgpu queue because no additional queue could be created.
+    ///
+    /// Submissions to an aliased queue must be externally synchronized with the submissions of
+    /// the [`RenderQueue`](crate::renderer::RenderQueue).
+    pub aliases_main_queue: bool,
+}
+
+/// The queues requested with [`RawVulkanInitSettings::request_additional_queue`], in request
+/// order.
+///
+/// This is empty when the device was not created with the Vulkan backend.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AdditionalVulkanQueues(Vec<AdditionalVulkanQueue>);
+
+impl AdditionalVulkanQueues {
+    /// Returns the queue at the index returned by [`RawVulkanInitSettings::request_additional_queue`].
+    pub fn get(&self, index: usize) -> Option<&AdditionalVulkanQueue> {
+        self.0.get(index)
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &AdditionalVulkanQueue> {
+        self.0.iter()
+    }
+}
+
+/// Assigns a `(family index, queue index)` to every request, or `None` if no family has a spare
+/// queue supporting it.
+///
+/// `main_family` is the family wgpu creates its own queue in, whose first queue is reserved.
+fn plan_queue_requests(
+    families: &[vk::QueueFamilyProperties],
+    main_family: Option<u32>,
+    requests: &[VulkanQueueRequest],
+) -> Vec<Option<(u32, u32)>> {
+    let mut used: Vec<u32> = (0..families.len() as u32)
+        .map(|family| u32::from(Some(family) == main_family))
+        .collect();
+
+    requests
+        .iter()
+        .map(|request| {
+            let (family, _) = families
+                .iter()
+                .enumerate()
+                .filter(|(family, properties)| used[*family] < properties.queue_count)
+                .map(|(family, properties)| {
+                    // Graphics and compute queues implicitly support transfer operations.
+                    let mut flags = properties.queue_flags;
+                    if flags.intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE) {
+                        flags |= vk::QueueFlags::TRANSFER;
+                    }
+                    (family, flags)
+                })
+                .filter(|(_, flags)| flags.contains(request.flags))
+                .min_by_key(|(family, flags)| {
+                    ((*flags & !request.flags).as_raw().count_ones(), *family)
+                })?;
+            used[family] += 1;
+            Some((family as u32, used[family] - 1))
+        })
+        .collect()
 }
 
 pub(crate) fn create_raw_vulkan_instance(
@@ -104,13 +203,51 @@ pub(crate) async fn create_raw_device(
     device_descriptor: &DeviceDescriptor<'_>,
     settings: &RawVulkanInitSettings,
     additional_features: &mut AdditionalVulkanFeatures,
-) -> Result<(Device, Queue), CreateRawVulkanDeviceError> {
+) -> Result<(Device, Queue, AdditionalVulkanQueues), CreateRawVulkanDeviceError> {
     // SAFETY: Registering callbacks is unsafe. Callback authors promise not to remove features
     // or change the adapter to something it does not support
     unsafe {
         let Some(raw_adapter) = adapter.as_hal::<Vulkan>() else {
-            return Ok(adapter.request_device(device_descriptor).await?);
+            let (device, queue) = adapter.request_device(device_descriptor).await?;
+            return Ok((device, queue, AdditionalVulkanQueues::default()));
         };
+
+        let families = raw_adapter
+            .shared_instance()
+            .raw_instance()
+            .get_physical_device_queue_family_properties(raw_adapter.raw_physical_device());
+        // wgpu creates its queue in the first graphics capable family.
+        let main_family = families
+            .iter()
+            .position(|family| family.queue_flags.contains(vk::QueueFlags::GRAPHICS))
+            .map(|family| family as u32);
+        let plan = plan_queue_requests(&families, main_family, &settings.queue_requests);
+
+        // The priorities of every family we create queues in. The priority of the main queue is
+        // kept first in the main family.
+        let mut family_priorities: Vec<(u32, Vec<f32>)> = Vec::new();
+        for (request, assignment) in settings.queue_requests.iter().zip(&plan) {
+            let Some((family, _)) = assignment else {
+                continue;
+            };
+            let index = match family_priorities.iter().position(|(f, _)| f == family) {
+                Some(index) => index,
+                None => {
+                    let priorities = if Some(*family) == main_family {
+                        vec![1.0]
+                    } else {
+                        Vec::new()
+                    };
+                    family_priorities.push((*family, priorities));
+                    family_priorities.len() - 1
+                }
+            };
+            family_priorities[index]
+                .1
+                .push(request.priority.clamp(0.0, 1.0));
+        }
+
+        let mut created_families = Vec::new();
         let open_device = raw_adapter.open_with_callback(
             device_descriptor.required_features,
             &adapter.limits(),
@@ -119,15 +256,71 @@ pub(crate) async fn create_raw_device(
                 for callback in &settings.create_device_callbacks {
                     (callback)(&mut args, &raw_adapter, additional_features);
                 }
+                for (family, priorities) in &family_priorities {
+                    let existing = args
+                        .queue_create_infos
+                        .iter_mut()
+                        .find(|info| info.queue_family_index == *family);
+                    match existing {
+                        // Only extend the main family if it holds nothing but the wgpu queue.
+                        Some(info) if Some(*family) == main_family && info.queue_count == 1 => {
+                            *info = info.queue_priorities(priorities);
+                        }
+                        None if Some(*family) != main_family => {
+                            args.queue_create_infos.push(
+                                vk::DeviceQueueCreateInfo::default()
+                                    .queue_family_index(*family)
+                                    .queue_priorities(priorities),
+                            );
+                        }
+                        // A callback already configured this family, leave it alone.
+                        _ => continue,
+                    }
+                    created_families.push(*family);
+                }
             })),
         )?;
 
-        Ok(adapter.create_device_from_hal::<Vulkan>(open_device, device_descriptor)?)
+        let (device, queue) =
+            adapter.create_device_from_hal::<Vulkan>(open_device, device_descriptor)?;
+
+        let mut additional_queues = AdditionalVulkanQueues::default();
+        if let Some(raw_device) = device.as_hal::<Vulkan>()
+            && let Some(raw_queue) = queue.as_hal::<Vulkan>()
+        {
+            for (request, assignment) in settings.queue_requests.iter().zip(plan) {
+                additional_queues.0.push(match assignment {
+                    Some((family_index, queue_index))
+                        if created_families.contains(&family_index) =>
+                    {
+                        AdditionalVulkanQueue {
+                            request: *request,
+                            family_index,
+                            queue_index,
+                            raw: raw_device
+                                .raw_device()
+                                .get_device_queue(family_index, queue_index),
+                            aliases_main_queue: false,
+                        }
+                    }
+                    _ => AdditionalVulkanQueue {
+                        request: *request,
+                        family_index: raw_device.queue_family_index(),
+                        queue_index: raw_device.queue_index(),
+                        raw: raw_queue.as_raw(),
+                        aliases_main_queue: true,
+                    },
+                });
+            }
+        }
+
+        Ok((device, queue, additional_queues))
     }
 }
 
+/// An error creating the device with [`RawVulkanInitSettings`].
 #[derive(Error, Debug)]
-pub(crate) enum CreateRawVulkanDeviceError {
+pub enum CreateRawVulkanDeviceError {
     #[error(transparent)]
     RequestDeviceError(#[from] wgpu::RequestDeviceError),
     #[error(transparent)]
@@ -152,3 +345,125 @@ impl AdditionalVulkanFeatures {
         self.0.remove(&TypeId::of::<T>());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    fn family(queue_flags: vk::QueueFlags, queue_count: u32) -> vk::QueueFamilyProperties {
+        vk::QueueFamilyProperties {
+            queue_flags,
+            queue_count,
+            ..Default::default()
+        }
+    }
+
+    fn request(flags: vk::QueueFlags) -> VulkanQueueRequest {
+        VulkanQueueRequest {
+            flags,
+            priority: 0.5,
+        }
+    }
+
+    #[test]
+    fn queue_requests_prefer_dedicated_families() {
+        let families = [
+            family(
+                vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE | vk::QueueFlags::TRANSFER,
+                16,
+            ),
+            family(vk::QueueFlags::COMPUTE | vk::QueueFlags::TRANSFER, 2),
+            family(vk::QueueFlags::TRANSFER, 1),
+        ];
+        let plan = plan_queue_requests(
+            &families,
+            Some(0),
+            &[
+                request(vk::QueueFlags::TRANSFER),
+                request(vk::QueueFlags::TRANSFER),
+                request(vk::QueueFlags::COMPUTE),
+                request(vk::QueueFlags::GRAPHICS),
+            ],
+        );
+        assert_eq!(
+            plan,
+            [Some((2, 0)), Some((1, 0)), Some((1, 1)), Some((0, 1))]
+        );
+    }
+
+    #[test]
+    fn queue_requests_fall_back_with_a_single_queue() {
+        let families = [family(
+            vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE,
+            1,
+        )];
+        let plan = plan_queue_requests(&families, Some(0), &[request(vk::QueueFlags::TRANSFER)]);
+        assert_eq!(plan, [None]);
+    }
+
+    #[test]
+    fn device_callbacks_run_and_additional_queues_are_exposed() {
+        struct CallbackFeature;
+
+        let invoked = Arc::new(AtomicBool::new(false));
+        let mut settings = RawVulkanInitSettings::default();
+        let flag = invoked.clone();
+        // SAFETY: the callback doesn't touch the device creation arguments.
+        unsafe {
+            settings.add_create_device_callback(move |_, _, features| {
+                flag.store(true, Ordering::Relaxed);
+                features.insert::<CallbackFeature>();
+            });
+        }
+        let requests = [
+            request(vk::QueueFlags::TRANSFER),
+            request(vk::QueueFlags::COMPUTE),
+        ];
+        for (index, request) in requests.into_iter().enumerate() {
+            assert_eq!(settings.request_additional_queue(request), index);
+        }
+
+        let mut features = AdditionalVulkanFeatures::default();
+        let instance = create_raw_vulkan_instance(
+            InstanceDescriptor {
+                backends: wgpu::Backends::VULKAN,
+                flags: wgpu::InstanceFlags::empty(),
+                memory_budget_thresholds: Default::default(),
+                display: None,
+                backend_options: Default::default(),
+            },
+            &settings,
+            &mut features,
+        );
+        let Ok(adapter) =
+            bevy_tasks::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            return;
+        };
+        let Ok((device, _queue, queues)) = bevy_tasks::block_on(create_raw_device(
+            &adapter,
+            &DeviceDescriptor::default(),
+            &settings,
+            &mut features,
+        )) else {
+            return;
+        };
+
+        assert!(invoked.load(Ordering::Relaxed));
+        assert!(features.has::<CallbackFeature>());
+        assert_eq!(queues.iter().len(), requests.len());
+        // SAFETY: the raw device is only used to read where the main queue lives.
+        let raw_device = unsafe { device.as_hal::<Vulkan>() }.unwrap();
+        let main_queue = (raw_device.queue_family_index(), raw_device.queue_index());
+        for (index, request) in requests.iter().enumerate() {
+            let queue = queues.get(index).unwrap();
+            assert_eq!(queue.request, *request);
+            assert_ne!(queue.raw, vk::Queue::null());
+            assert_eq!(
+                queue.aliases_main_queue,
+                (queue.family_index, queue.queue_index) == main_queue
+            );
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::diagnostic::internal::DiagnosticsRecorder;
 use crate::render_phase::TrackedRenderPass;
 use crate::render_resource::{CommandEncoder, RenderPassDescriptor};
 use crate::renderer::RenderDevice;
+use alloc::boxed::Box;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::change_detection::Tick;
 use bevy_ecs::component::ComponentId;
@@ -59,10 +60,57 @@ impl PendingCommandBuffers {
     }
 }
 
+/// A command buffer, or a task generating one, in the order it is to be submitted.
+enum QueuedCommandBuffer<T = CommandBuffer> {
+    Ready(T),
+    Task(Box<dyn FnOnce() -> T + Send>),
+}
+
+/// Returns the command buffers of `queue` in queue order, running its command buffer generation
+/// tasks in parallel on the [`ComputeTaskPool`].
+///
+/// The tasks run inline on wasm and without the `multi_threaded` feature.
+///
+/// [`ComputeTaskPool`]: bevy_tasks::ComputeTaskPool
+fn resolve_command_buffers<T: Send + 'static>(queue: Vec<QueuedCommandBuffer<T>>) -> Vec<T> {
+    #[cfg(all(feature = "multi_threaded", not(target_arch = "wasm32")))]
+    if queue
+        .iter()
+        .any(|queued| matches!(queued, QueuedCommandBuffer::Task(_)))
+    {
+        let _span = info_span!("command_buffer_generation_tasks").entered();
+        let mut command_buffers = Vec::with_capacity(queue.len());
+        let mut generated = bevy_tasks::ComputeTaskPool::get().scope(|scope| {
+            for (i, queued) in queue.into_iter().enumerate() {
+                match queued {
+                    QueuedCommandBuffer::Ready(command_buffer) => {
+                        command_buffers.push((i, command_buffer));
+                    }
+                    QueuedCommandBuffer::Task(task) => scope.spawn(async move { (i, task()) }),
+                }
+            }
+        });
+        command_buffers.append(&mut generated);
+        command_buffers.sort_unstable_by_key(|(i, _)| *i);
+        return command_buffers
+            .into_iter()
+            .map(|(_, command_buffer)| command_buffer)
+            .collect();
+    }
+
+    queue
+        .into_iter()
+        .map(|queued| match queued {
+            QueuedCommandBuffer::Ready(command_buffer) => command_buffer,
+            QueuedCommandBuffer::Task(task) => task(),
+        })
+        .collect()
+}
+
 #[derive(Default)]
 struct RenderContextStateInner {
     command_encoder: Option<CommandEncoder>,
-    command_buffers: Vec<CommandBuffer>,
+    command_buffers: Vec<QueuedCommandBuffer>,
     render_device: Option<RenderDevice>,
 }
 
@@ -80,7 +128,9 @@ impl Default for RenderContextState {
 impl RenderContextState {
     fn flush_encoder(&mut self) {
         if let Some(encoder) = self.0.command_encoder.take() {
-            self.0.command_buffers.push(encoder.finish());
+            self.0
+                .command_buffers
+                .push(QueuedCommandBuffer::Ready(encoder.finish()));
         }
     }
 
@@ -96,9 +146,11 @@ impl RenderContextState {
         })
     }
 
+    /// Returns the recorded command buffers in submission order, waiting for the ones generated
+    /// by tasks.
     pub fn finish(&mut self) -> Vec<CommandBuffer> {
         self.flush_encoder();
-        core::mem::take(&mut self.0.command_buffers)
+        resolve_command_buffers(core::mem::take(&mut self.0.command_buffers))
     }
 }
 
@@ -106,19 +158,15 @@ impl SystemBuffer for RenderContextState {
     fn queue(&mut self, system_meta: &SystemMeta, mut world: DeferredWorld) {
         let _span = info_span!("RenderContextState::apply", system = %system_meta.name()).entered();
 
-        let inner = &mut *self.0;
-
         // flush to ensure correct submission order
-        if let Some(encoder) = inner.command_encoder.take() {
-            inner.command_buffers.push(encoder.finish());
-        }
+        let command_buffers = self.finish();
 
-        if !inner.command_buffers.is_empty() {
+        if !command_buffers.is_empty() {
             let mut pending = world.resource_mut::<PendingCommandBuffers>();
-            pending.push(core::mem::take(&mut inner.command_buffers));
+            pending.push(command_buffers);
         }
 
-        inner.render_device = None;
+        self.0.render_device = None;
     }
 }
 
@@ -174,7 +222,30 @@ impl<'w, 's> RenderContext<'w, 's> {
     /// Adds a finished command buffer to be submitted later.
     pub fn add_command_buffer(&mut self, command_buffer: CommandBuffer) {
         self.state.flush_encoder();
-        self.state.0.command_buffers.push(command_buffer);
+        self.state
+            .0
+            .command_buffers
+            .push(QueuedCommandBuffer::Ready(command_buffer));
+    }
+
+    /// Adds a task recording a command buffer on the
+    /// [`ComputeTaskPool`](bevy_tasks::ComputeTaskPool), in parallel with the other tasks of this
+    /// system.
+    ///
+    /// The command buffer is submitted after everything recorded before this call and before
+    /// everything recorded after it, no matter when the task finishes.
+    pub fn add_command_buffer_generation_task(
+        &mut self,
+        task: impl FnOnce(RenderDevice) -> CommandBuffer + Send + 'static,
+    ) {
+        self.state.flush_encoder();
+        let render_device = self.render_device.clone();
+        self.state
+            .0
+            .command_buffers
+            .push(QueuedCommandBuffer::Task(Box::new(move || {
+                task(render_device)
+            })));
     }
 }
 
@@ -296,3 +367,27 @@ unsafe impl<'w, 's, D: bevy_ecs::query::ReadOnlyQueryData + 'static, F: QueryFil
     bevy_ecs::system::ReadOnlySystemParam for ViewQuery<'w, 's, D, F>
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_tasks::{ComputeTaskPool, TaskPool};
+
+    use super::{QueuedCommandBuffer, resolve_command_buffers};
+
+    #[test]
+    fn generated_command_buffers_keep_submission_order() {
+        ComputeTaskPool::get_or_init(TaskPool::default);
+
+        let queue = vec![
+            QueuedCommandBuffer::Ready(0),
+            QueuedCommandBuffer::Task(Box::new(|| {
+                std::thread::sleep(core::time::Duration::from_millis(10));
+                1
+            })),
+            QueuedCommandBuffer::Task(Box::new(|| 2)),
+            QueuedCommandBuffer::Ready(3),
+        ];
+
+        assert_eq!(resolve_command_buffers(queue), [0, 1, 2, 3]);
+    }
+}
@@ -1,19 +1,43 @@
 use super::RenderQueue;
 use crate::render_resource::{
     BindGroup, BindGroupLayout, Buffer, ComputePipeline, RawRenderPipelineDescriptor,
-    RenderPipeline, Sampler, Texture,
+    RenderPipeline, Sampler, Texture, TextureValidationError, validate_texture_descriptor,
 };
-use crate::renderer::WgpuWrapper;
+use crate::{diagnostic::FrameStatisticsCounters, renderer::WgpuWrapper};
 use bevy_ecs::resource::Resource;
+use thiserror::Error;
 use wgpu::{
     BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
     BufferAsyncError, BufferBindingType, PollError, PollStatus, util::DeviceExt,
 };
 
+/// The features missing from a [`RenderDevice`], see [`RenderDevice::require_features`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the render device doesn't support the required features {missing:?}")]
+pub struct UnsupportedFeatureError {
+    pub missing: wgpu::Features,
+}
+
+impl UnsupportedFeatureError {
+    /// Checks that `available` contains all of `required`.
+    pub fn check(
+        available: wgpu::Features,
+        required: wgpu::Features,
+    ) -> Result<(), UnsupportedFeatureError> {
+        let missing = required - available;
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(UnsupportedFeatureError { missing })
+        }
+    }
+}
+
 /// This GPU device is responsible for the creation of most rendering and compute resources.
 #[derive(Resource, Clone)]
 pub struct RenderDevice {
     device: WgpuWrapper<wgpu::Device>,
+    frame_statistics: FrameStatisticsCounters,
 }
 
 impl From<wgpu::Device> for RenderDevice {
@@ -24,7 +48,17 @@ impl From<wgpu::Device> for RenderDevice {
 
 impl RenderDevice {
     pub fn new(device: WgpuWrapper<wgpu::Device>) -> Self {
-        Self { device }
+        Self {
+            device,
+            frame_statistics: FrameStatisticsCounters::new(),
+        }
+    }
+
+    /// The counters the work recorded with this device is added to, shared with the
+    /// [`RenderQueue`] created for it.
+    #[inline]
+    pub fn frame_statistics(&self) -> &FrameStatisticsCounters {
+        &self.frame_statistics
     }
 
     /// List all [`Features`](wgpu::Features) that may be used with this device.
@@ -35,6 +69,19 @@ impl RenderDevice {
         self.device.features()
     }
 
+    /// Checks that all of `features` are enabled on this device, returning the missing ones
+    /// otherwise.
+    ///
+    /// Plugins can call this in [`RenderStartup`](crate::RenderStartup) to fail with a clear error
+    /// rather than a wgpu validation error when the features are first used.
+    #[inline]
+    pub fn require_features(
+        &self,
+        features: wgpu::Features,
+    ) -> Result<(), UnsupportedFeatureError> {
+        UnsupportedFeatureError::check(self.features(), features)
+    }
+
     /// List all [`Limits`](wgpu::Limits) that were requested of this device.
     ///
     /// If any of these limits are exceeded, functions may panic.
@@ -126,6 +173,21 @@ impl RenderDevice {
         self.device.poll(maintain)
     }
 
+    /// Blocks until the submission with the given `index` has completed, without waiting on
+    /// work submitted after it.
+    ///
+    /// Pair this with the index returned by [`RenderQueue::submit`], e.g. to wait on a specific
+    /// readback instead of the whole device.
+    ///
+    /// no-op on the web, device is automatically polled.
+    #[inline]
+    pub fn poll_for(&self, index: wgpu::SubmissionIndex) -> Result<PollStatus, PollError> {
+        self.device.poll(wgpu::PollType::Wait {
+            submission_index: Some(index),
+            timeout: None,
+        })
+    }
+
     /// Creates an empty [`CommandEncoder`](wgpu::CommandEncoder).
     #[inline]
     pub fn create_command_encoder(
@@ -203,12 +265,14 @@ impl RenderDevice {
     }
 
     /// Creates a [`Buffer`].
+    #[inline]
     pub fn create_buffer(&self, desc: &wgpu::BufferDescriptor) -> Buffer {
         let wgpu_buffer = self.device.create_buffer(desc);
         Buffer::from(wgpu_buffer)
     }
 
     /// Creates a [`Buffer`] and initializes it with the specified data.
+    #[inline]
     pub fn create_buffer_with_data(&self, desc: &wgpu::util::BufferInitDescriptor) -> Buffer {
         let wgpu_buffer = self.device.create_buffer_init(desc);
         Buffer::from(wgpu_buffer)
@@ -217,7 +281,10 @@ impl RenderDevice {
     /// Creates a new [`Texture`] and initializes it with the specified data.
     ///
     /// `desc` specifies the general format of the texture.
-    /// `data` is the raw data.
+    /// `data` is the raw data, with tightly packed rows. Rows don't need to be padded to
+    /// [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`], as the data is written through the queue rather
+    /// than copied from a buffer. See [`texture_data_layout`](super::texture_data_layout) for the
+    /// expected size of `data`.
     pub fn create_texture_with_data(
         &self,
         render_queue: &RenderQueue,
@@ -234,14 +301,28 @@ impl RenderDevice {
     /// Creates a new [`Texture`].
     ///
     /// `desc` specifies the general format of the texture.
+    #[inline]
     pub fn create_texture(&self, desc: &wgpu::TextureDescriptor) -> Texture {
         let wgpu_texture = self.device.create_texture(desc);
         Texture::from(wgpu_texture)
     }
 
+    /// Creates a new [`Texture`], returning an error instead of raising a wgpu validation error
+    /// if its size or array layers exceed the [`limits`](Self::limits) of the device.
+    ///
+    /// This is mostly useful for 3D textures and texture arrays, whose limits are lower.
+    pub fn try_create_texture(
+        &self,
+        desc: &wgpu::TextureDescriptor,
+    ) -> Result<Texture, TextureValidationError> {
+        validate_texture_descriptor(desc, &self.limits())?;
+        Ok(self.create_texture(desc))
+    }
+
     /// Creates a new [`Sampler`].
     ///
     /// `desc` specifies the behavior of the sampler.
+    #[inline]
     pub fn create_sampler(&self, desc: &wgpu::SamplerDescriptor) -> Sampler {
         let wgpu_sampler = self.device.create_sampler(desc);
         Sampler::from(wgpu_sampler)
@@ -262,6 +343,16 @@ impl RenderDevice {
         &self.device
     }
 
+    /// Returns an owned handle to the wgpu [`Device`](wgpu::Device), for crates that need to keep
+    /// the device alive on their own.
+    ///
+    /// wgpu devices are reference counted, so this is cheap. Note that the handle keeps the old
+    /// device alive when the renderer is reinitialized with a new [`RenderDevice`] after a device
+    /// loss, so holders should watch for a new [`RenderDevice`] and drop theirs.
+    pub fn clone_device(&self) -> wgpu::Device {
+        wgpu::Device::clone(&self.device)
+    }
+
     pub fn map_buffer(
         &self,
         buffer: &wgpu::BufferSlice,
@@ -297,7 +388,16 @@ impl RenderDevice {
 
 #[cfg(test)]
 mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use bevy_ecs::world::World;
+
     use super::*;
+    use crate::{
+        renderer::{gpu_render_resources, noop_render_resources},
+        settings::RenderResources,
+    };
 
     #[test]
     fn align_copy_bytes_per_row() {
@@ -309,4 +409,209 @@ mod tests {
         assert_eq!(RenderDevice::align_copy_bytes_per_row(align + 1), align * 2);
         assert_eq!(RenderDevice::align_copy_bytes_per_row(align), align);
     }
+
+    #[test]
+    fn missing_features_are_reported() {
+        let available = wgpu::Features::DEPTH_CLIP_CONTROL | wgpu::Features::SHADER_F16;
+
+        assert_eq!(
+            UnsupportedFeatureError::check(available, wgpu::Features::SHADER_F16),
+            Ok(())
+        );
+        let error = UnsupportedFeatureError::check(
+            available,
+            wgpu::Features::SHADER_F16 | wgpu::Features::TEXTURE_COMPRESSION_BC,
+        )
+        .unwrap_err();
+        assert_eq!(error.missing, wgpu::Features::TEXTURE_COMPRESSION_BC);
+        assert!(error.to_string().contains("required features"));
+    }
+
+    #[test]
+    fn created_buffers_report_their_size() {
+        let RenderResources(device, ..) = noop_render_resources(wgpu::Features::empty());
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("sized"),
+            size: 1024,
+            usage: wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+        assert_eq!(buffer.size(), 1024);
+        // Initialized buffers are padded to `COPY_BUFFER_ALIGNMENT`.
+        let initialized = device.create_buffer_with_data(&wgpu::util::BufferInitDescriptor {
+            label: Some("initialized"),
+            contents: &[1; 10],
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        assert_eq!(initialized.size(), 12);
+
+        let layout = device.create_bind_group_layout(
+            "sized_layout",
+            &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        );
+        let _bind_group = device.create_bind_group(
+            "sized_bind_group",
+            &layout,
+            &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        );
+    }
+
+    fn texture_descriptor(width: u32, height: u32) -> wgpu::TextureDescriptor<'static> {
+        wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        }
+    }
+
+    #[test]
+    fn resources_are_created_through_the_render_device() {
+        let RenderResources(device, queue, ..) = noop_render_resources(wgpu::Features::empty());
+
+        let texture = device.create_texture(&texture_descriptor(16, 8));
+        assert_eq!(texture.width(), 16);
+        assert_eq!(texture.height(), 8);
+        let texture = device.create_texture_with_data(
+            &queue,
+            &texture_descriptor(3, 2),
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &[0; 3 * 2 * 4],
+        );
+        assert_eq!(texture.format(), wgpu::TextureFormat::Rgba8Unorm);
+        let _sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+
+        let buffer = device.create_buffer_with_data(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &[0; 256],
+            usage: wgpu::BufferUsages::COPY_DST,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.clear_buffer(&buffer, 0, None);
+        queue.submit([encoder.finish()]);
+    }
+
+    #[test]
+    fn textures_with_unaligned_rows_read_back() {
+        // The noop backend doesn't store texture data, this needs an actual adapter.
+        let Some(RenderResources(device, queue, ..)) = gpu_render_resources() else {
+            return;
+        };
+        // Rows of 12 bytes, far from `COPY_BYTES_PER_ROW_ALIGNMENT`.
+        let data = (1..=3 * 2 * 4).collect::<Vec<u8>>();
+        let texture = device.create_texture_with_data(
+            &queue,
+            &texture_descriptor(3, 2),
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &data,
+        );
+
+        let bytes_per_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: u64::from(bytes_per_row * 2),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        queue.submit([encoder.finish()]);
+        buffer.slice(..).map_async(wgpu::MapMode::Read, |result| {
+            result.expect("the readback buffer should map");
+        });
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+        let texels = buffer.slice(..).get_mapped_range();
+        for (y, row) in data.chunks_exact(12).enumerate() {
+            let start = y * bytes_per_row as usize;
+            assert_eq!(&texels[start..start + 12], row);
+        }
+    }
+
+    #[test]
+    fn cloned_devices_outlive_the_render_device() {
+        let RenderResources(device, queue, ..) = noop_render_resources(wgpu::Features::empty());
+        let mut world = World::new();
+        world.insert_resource(device);
+
+        let cloned = world.resource::<RenderDevice>().clone_device();
+        drop(world.remove_resource::<RenderDevice>());
+        drop(world);
+
+        let buffer = cloned.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cloned_device"),
+            size: 256,
+            usage: wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = cloned.create_command_encoder(&Default::default());
+        encoder.clear_buffer(&buffer, 0, None);
+        queue.submit([encoder.finish()]);
+        cloned.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    }
+
+    #[test]
+    fn poll_for_waits_on_a_single_submission() {
+        // The noop backend finishes every submission right away, this needs an actual adapter.
+        let Some(RenderResources(device, queue, ..)) = gpu_render_resources() else {
+            return;
+        };
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("poll_for"),
+            size: 1 << 20,
+            usage: wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let submit = || {
+            let mut encoder = device.create_command_encoder(&Default::default());
+            encoder.clear_buffer(&buffer, 0, None);
+            let index = queue.submit([encoder.finish()]);
+            let done = Arc::new(AtomicBool::new(false));
+            let flag = done.clone();
+            queue.on_submitted_work_done(move || flag.store(true, Ordering::Relaxed));
+            (index, done)
+        };
+
+        let (first, first_done) = submit();
+        let (second, second_done) = submit();
+
+        // Returns once the first batch is done, whether the second one is or not.
+        device.poll_for(first).unwrap();
+        assert!(first_done.load(Ordering::Relaxed));
+
+        device.poll_for(second).unwrap();
+        assert!(second_done.load(Ordering::Relaxed));
+    }
 }
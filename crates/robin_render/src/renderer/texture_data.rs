@@ -0,0 +1,354 @@
+use super::RenderQueue;
+use thiserror::Error;
+use wgpu::{Extent3d, Origin3d, TexelCopyBufferLayout, TextureAspect, TextureFormat};
+
+/// An error returned by [`RenderQueue::write_texture_data`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TextureDataError {
+    #[error("{0:?} textures cannot be written from the CPU")]
+    UnsupportedFormat(TextureFormat),
+    #[error("mip level {mip_level} does not exist, the texture has {mip_level_count} mip levels")]
+    MipLevelOutOfRange {
+        mip_level: u32,
+        mip_level_count: u32,
+    },
+    #[error("the {size:?} region at {origin:?} is outside of the {mip_size:?} mip level")]
+    RegionOutOfBounds {
+        origin: Origin3d,
+        size: Extent3d,
+        mip_size: Extent3d,
+    },
+    #[error(
+        "the {size:?} region at {origin:?} is not aligned to the {block_width}x{block_height} \
+        blocks of the format"
+    )]
+    UnalignedRegion {
+        origin: Origin3d,
+        size: Extent3d,
+        block_width: u32,
+        block_height: u32,
+    },
+    #[error("expected {expected} bytes of texture data, got {actual}")]
+    SizeMismatch { expected: u64, actual: u64 },
+}
+
+/// Returns the layout of tightly packed data for a `size` region of a texture of the given
+/// format, and its length in bytes.
+///
+/// Block-compressed formats are laid out in rows of blocks, so the width and height are rounded
+/// up to whole blocks.
+pub fn texture_data_layout(
+    format: TextureFormat,
+    size: Extent3d,
+) -> Result<(TexelCopyBufferLayout, u64), TextureDataError> {
+    let block_size = format
+        .block_copy_size(None)
+        .ok_or(TextureDataError::UnsupportedFormat(format))?;
+    let (block_width, block_height) = format.block_dimensions();
+    let bytes_per_row = size.width.div_ceil(block_width) * block_size;
+    let rows_per_image = size.height.div_ceil(block_height);
+    let layout = TexelCopyBufferLayout {
+        offset: 0,
+        bytes_per_row: Some(bytes_per_row),
+        rows_per_image: Some(rows_per_image),
+    };
+    let len = u64::from(bytes_per_row)
+        * u64::from(rows_per_image)
+        * u64::from(size.depth_or_array_layers);
+    Ok((layout, len))
+}
+
+/// Checks the `size` region at `origin` of a mip level for [`RenderQueue::write_texture_data`],
+/// and returns the layout of its data.
+fn texture_data_region(
+    format: TextureFormat,
+    mip_size: Extent3d,
+    origin: Origin3d,
+    size: Extent3d,
+    data_len: usize,
+) -> Result<TexelCopyBufferLayout, TextureDataError> {
+    let (block_width, block_height) = format.block_dimensions();
+    if origin.x % block_width != 0
+        || origin.y % block_height != 0
+        || size.width % block_width != 0
+        || size.height % block_height != 0
+    {
+        return Err(TextureDataError::UnalignedRegion {
+            origin,
+            size,
+            block_width,
+            block_height,
+        });
+    }
+    let mip_size = mip_size.physical_size(format);
+    let fits = |origin: u32, size: u32, mip_size: u32| {
+        origin.checked_add(size).is_some_and(|end| end <= mip_size)
+    };
+    if !fits(origin.x, size.width, mip_size.width)
+        || !fits(origin.y, size.height, mip_size.height)
+        || !fits(
+            origin.z,
+            size.depth_or_array_layers,
+            mip_size.depth_or_array_layers,
+        )
+    {
+        return Err(TextureDataError::RegionOutOfBounds {
+            origin,
+            size,
+            mip_size,
+        });
+    }
+
+    let (layout, expected) = texture_data_layout(format, size)?;
+    if data_len as u64 != expected {
+        return Err(TextureDataError::SizeMismatch {
+            expected,
+            actual: data_len as u64,
+        });
+    }
+    Ok(layout)
+}
+
+impl RenderQueue {
+    /// Writes `data` into the `size` region at `origin` of a mip level of `texture`.
+    ///
+    /// Unlike [`Queue::write_texture`](wgpu::Queue::write_texture), the data layout is derived
+    /// from the texture, so `data` must be tightly packed. For 3D textures and texture arrays,
+    /// `origin.z` and `size.depth_or_array_layers` select the slices or layers to write.
+    ///
+    /// Regions of block-compressed textures are made of whole blocks, so a whole mip level is
+    /// written with its [`physical_size`](Extent3d::physical_size).
+    pub fn write_texture_data(
+        &self,
+        texture: &wgpu::Texture,
+        mip_level: u32,
+        origin: Origin3d,
+        size: Extent3d,
+        data: &[u8],
+    ) -> Result<(), TextureDataError> {
+        if mip_level >= texture.mip_level_count() {
+            return Err(TextureDataError::MipLevelOutOfRange {
+                mip_level,
+                mip_level_count: texture.mip_level_count(),
+            });
+        }
+        let mip_size = texture
+            .size()
+            .mip_level_size(mip_level, texture.dimension());
+        let layout = texture_data_region(texture.format(), mip_size, origin, size, data.len())?;
+
+        self.write_texture_counted(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level,
+                origin,
+                aspect: TextureAspect::All,
+            },
+            data,
+            layout,
+            size,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        renderer::{gpu_render_resources, noop_render_resources},
+        settings::RenderResources,
+    };
+    use wgpu::{TextureDescriptor, TextureDimension, TextureUsages};
+
+    fn extent(width: u32, height: u32, depth_or_array_layers: u32) -> Extent3d {
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers,
+        }
+    }
+
+    #[test]
+    fn texture_data_layouts() {
+        let (layout, len) =
+            texture_data_layout(TextureFormat::Rgba8Unorm, extent(4, 4, 1)).unwrap();
+        assert_eq!(layout.bytes_per_row, Some(16));
+        assert_eq!(layout.rows_per_image, Some(4));
+        assert_eq!(len, 64);
+
+        // BC1 stores 4x4 blocks of 8 bytes, partial blocks are rounded up.
+        let (layout, len) =
+            texture_data_layout(TextureFormat::Bc1RgbaUnorm, extent(5, 8, 1)).unwrap();
+        assert_eq!(layout.bytes_per_row, Some(16));
+        assert_eq!(layout.rows_per_image, Some(2));
+        assert_eq!(len, 32);
+
+        assert_eq!(
+            texture_data_layout(TextureFormat::Depth24Plus, extent(4, 4, 1)),
+            Err(TextureDataError::UnsupportedFormat(
+                TextureFormat::Depth24Plus
+            ))
+        );
+    }
+
+    #[test]
+    fn texture_data_regions() {
+        // The second slice of a 4x4x2 3D texture.
+        let layout = texture_data_region(
+            TextureFormat::Rgba8Unorm,
+            extent(4, 4, 2),
+            Origin3d { x: 0, y: 0, z: 1 },
+            extent(4, 4, 1),
+            64,
+        )
+        .unwrap();
+        assert_eq!(layout.bytes_per_row, Some(16));
+
+        assert_eq!(
+            texture_data_region(
+                TextureFormat::Rgba8Unorm,
+                extent(4, 4, 2),
+                Origin3d::ZERO,
+                extent(4, 4, 2),
+                64
+            ),
+            Err(TextureDataError::SizeMismatch {
+                expected: 128,
+                actual: 64
+            })
+        );
+        assert_eq!(
+            texture_data_region(
+                TextureFormat::Rgba8Unorm,
+                extent(4, 4, 1),
+                Origin3d { x: 2, y: 0, z: 0 },
+                extent(4, 1, 1),
+                16
+            ),
+            Err(TextureDataError::RegionOutOfBounds {
+                origin: Origin3d { x: 2, y: 0, z: 0 },
+                size: extent(4, 1, 1),
+                mip_size: extent(4, 4, 1),
+            })
+        );
+
+        // A 6x6 BC1 mip level is physically 8x8.
+        let layout = texture_data_region(
+            TextureFormat::Bc1RgbaUnorm,
+            extent(6, 6, 1),
+            Origin3d { x: 4, y: 0, z: 0 },
+            extent(4, 8, 1),
+            16,
+        )
+        .unwrap();
+        assert_eq!(layout.bytes_per_row, Some(8));
+
+        assert_eq!(
+            texture_data_region(
+                TextureFormat::Bc1RgbaUnorm,
+                extent(8, 8, 1),
+                Origin3d { x: 2, y: 0, z: 0 },
+                extent(4, 4, 1),
+                0
+            ),
+            Err(TextureDataError::UnalignedRegion {
+                origin: Origin3d { x: 2, y: 0, z: 0 },
+                size: extent(4, 4, 1),
+                block_width: 4,
+                block_height: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn texture_writes_are_counted_as_such() {
+        let RenderResources(device, queue, ..) = noop_render_resources(wgpu::Features::empty());
+        let texture = device.create_texture(&TextureDescriptor {
+            label: None,
+            size: extent(4, 4, 1),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        queue
+            .write_texture_data(&texture, 0, Origin3d::ZERO, extent(2, 2, 1), &[0; 16])
+            .unwrap();
+
+        let statistics = device.frame_statistics().take();
+        assert_eq!(statistics.texture_writes, 1);
+        assert_eq!(statistics.buffer_writes, 0);
+        assert_eq!(statistics.bytes_uploaded, 16);
+    }
+
+    #[test]
+    fn written_regions_read_back() {
+        // The noop backend doesn't store texture data, this needs an actual adapter.
+        let Some(RenderResources(device, queue, ..)) = gpu_render_resources() else {
+            return;
+        };
+        // 64 texels of 4 bytes are already aligned to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+        let size = extent(64, 4, 1);
+        let texture = device.create_texture(&TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::COPY_DST | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let origin = Origin3d { x: 16, y: 1, z: 0 };
+        let region = extent(8, 2, 1);
+        let data = (0..8 * 2 * 4)
+            .map(|byte| byte as u8 + 1)
+            .collect::<Vec<_>>();
+        queue
+            .write_texture_data(&texture, 0, origin, region, &data)
+            .unwrap();
+
+        let (layout, len) = texture_data_layout(TextureFormat::Rgba8Unorm, size).unwrap();
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: len,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout,
+            },
+            size,
+        );
+        queue.submit([encoder.finish()]);
+        buffer.slice(..).map_async(wgpu::MapMode::Read, |result| {
+            result.expect("the readback buffer should map");
+        });
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+        let texels = buffer.slice(..).get_mapped_range();
+        let row = |y: u32| &texels[(y * 64 * 4) as usize..((y + 1) * 64 * 4) as usize];
+        for y in 0..4 {
+            for (x, texel) in row(y).chunks_exact(4).enumerate() {
+                let x = x as u32;
+                let inside = (16..24).contains(&x) && (1..3).contains(&y);
+                let expected = if inside {
+                    let start = (((y - 1) * 8 + x - 16) * 4) as usize;
+                    &data[start..start + 4]
+                } else {
+                    // Textures are zero-initialized.
+                    &[0; 4][..]
+                };
+                assert_eq!(texel, expected, "texel ({x}, {y})");
+            }
+        }
+    }
+}
@@ -1,13 +1,21 @@
 use crate::{
     FutureRenderResources,
-    error_handler::DeviceErrorHandler,
+    error_handler::{
+        DeviceErrorHandler, LastWgpuSettings, RenderCreationReport, advance_device_generation,
+    },
     render_resource::PipelineCache,
-    renderer::{self, RenderAdapter, RenderAdapterInfo, RenderDevice, RenderInstance, RenderQueue},
+    renderer::{
+        self, DeviceGeneration, RenderAdapter, RenderAdapterInfo, RenderDevice, RenderInstance,
+        RenderQueue, WgpuWrapper,
+    },
 };
-use alloc::borrow::Cow;
-use bevy_ecs::world::World;
+use alloc::{borrow::Cow, sync::Arc};
+use bevy_ecs::{query::With, resource::Resource, world::World};
 use bevy_image::{CompressedImageFormatSupport, CompressedImageFormats};
-use bevy_window::RawHandleWrapperHolder;
+use bevy_platform::sync::atomic::{AtomicU8, Ordering};
+use bevy_tasks::ConditionalSendFuture;
+use bevy_window::{PrimaryWindow, RawHandleWrapperHolder};
+use std::path::PathBuf;
 
 use wgpu::MemoryBudgetThresholds;
 pub use wgpu::{
@@ -42,8 +50,16 @@ pub struct WgpuSettings {
     pub power_preference: PowerPreference,
     pub priority: WgpuSettingsPriority,
     /// The features to ensure are enabled regardless of what the adapter/backend supports.
-    /// Setting these explicitly may cause renderer initialization to fail.
+    ///
+    /// If the adapter doesn't support all of them, the renderer isn't created and the missing
+    /// features are logged. Use [`WgpuSettings::optional_features`] for features that are
+    /// nice to have.
     pub features: WgpuFeatures,
+    /// The features to enable only if the adapter supports them.
+    ///
+    /// Features that could not be granted are logged and listed in
+    /// [`RenderCreationReport::ungranted_features`].
+    pub optional_features: WgpuFeatures,
     /// The features to ensure are disabled regardless of what the adapter/backend supports
     pub disabled_features: Option<WgpuFeatures>,
     /// The imposed limits.
@@ -63,8 +79,33 @@ pub struct WgpuSettings {
     pub instance_memory_budget_thresholds: MemoryBudgetThresholds,
     /// If true, will force wgpu to use a software renderer, if available.
     pub force_fallback_adapter: bool,
-    /// The name of the adapter to use.
+    /// A case-insensitive substring of the name of the adapter to use.
+    ///
+    /// Renderer creation fails if no adapter matches, instead of falling back to another one.
+    /// Can be overridden with the `WGPU_ADAPTER_NAME` environment variable.
     pub adapter_name: Option<String>,
+    /// The backend of the adapter to prefer, e.g. to pick Vulkan over OpenGL when both are
+    /// enabled in [`WgpuSettings::backends`].
+    ///
+    /// Unlike [`WgpuSettings::adapter_name`], this falls back to requesting an adapter with
+    /// [`WgpuSettings::power_preference`] if no adapter of this backend is found.
+    pub preferred_backend: Option<wgpu::Backend>,
+    /// If true, the adapter isn't required to be compatible with the surface of the primary
+    /// window, e.g. for offscreen rendering on servers and CI.
+    ///
+    /// Without a primary window, e.g. when the window plugin is disabled, this is the case
+    /// regardless of this setting. A headless renderer doesn't create surfaces for windows, so
+    /// cameras rendering to windows are skipped and only images are rendered to.
+    ///
+    /// A software adapter is used if no hardware adapter is found, unless
+    /// [`WgpuSettings::force_fallback_adapter`] already requires one.
+    pub headless: bool,
+    /// A directory to persist compiled pipelines in, so they don't need to be recompiled by the
+    /// driver on the next launch.
+    ///
+    /// This enables [`WgpuFeatures::PIPELINE_CACHE`] if the adapter supports it, and does nothing
+    /// otherwise. Only the Vulkan backend currently supports it.
+    pub pipeline_cache_dir: Option<PathBuf>,
 }
 
 impl Default for WgpuSettings {
@@ -138,6 +179,7 @@ impl Default for WgpuSettings {
             power_preference,
             priority,
             features: wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+            optional_features: WgpuFeatures::empty(),
             disabled_features: None,
             limits,
             constrained_limits: None,
@@ -148,10 +190,18 @@ impl Default for WgpuSettings {
             instance_memory_budget_thresholds: MemoryBudgetThresholds::default(),
             force_fallback_adapter: false,
             adapter_name: None,
+            preferred_backend: None,
+            headless: false,
+            pipeline_cache_dir: None,
         }
     }
 }
 
+/// The GPU handles the renderer runs on, see [`RenderCreation::Manual`].
+///
+/// The handles are wrapped in [`WgpuWrapper`] so they can be shared with the render world,
+/// which may run on another thread. On the web with atomics, they must be created and dropped on
+/// the thread `wgpu` runs on.
 #[derive(Clone)]
 pub struct RenderResources(
     pub RenderDevice,
@@ -160,6 +210,7 @@ pub struct RenderResources(
     pub RenderAdapter,
     pub RenderInstance,
     #[cfg(feature = "raw_vulkan_init")] pub renderer::raw_vulkan_init::AdditionalVulkanFeatures,
+    #[cfg(feature = "raw_vulkan_init")] pub renderer::raw_vulkan_init::AdditionalVulkanQueues,
 );
 
 impl RenderResources {
@@ -167,9 +218,11 @@ impl RenderResources {
     ///
     /// We deconstruct the [`RenderResources`] and make them usable by the main and render worlds,
     /// and insert [`PipelineCache`] and [`CompressedImageFormats`] which directly depend on having
-    /// references to these resources within them to be accurate. This causes all shaders to
-    /// be recompiled, and the set of supported images to possibly change. This is necessary
-    /// because the new backend may have different compression support or shader language.
+    /// references to these resources within them to be accurate. A [`PipelineCache`] of a previous
+    /// device is recreated through [`RenderDeviceRecreated`](crate::error_handler::RenderDeviceRecreated).
+    /// This causes all shaders to be recompiled, and the set of supported images to possibly
+    /// change. This is necessary because the new backend may have different compression support
+    /// or shader language.
     pub(crate) fn unpack_into(
         self,
         main_world: &mut World,
@@ -178,6 +231,26 @@ impl RenderResources {
     ) {
         let RenderResources(device, queue, adapter_info, render_adapter, instance, ..) = self;
 
+        if let Some(progress) = main_world.get_resource::<RenderInitProgress>() {
+            progress.set(RenderInitStage::Unpacking);
+        }
+
+        // Manually created renderers request exactly the features of the device they supply.
+        let requested_features = main_world
+            .get_resource::<LastWgpuSettings>()
+            .map(|settings| settings.0.features | settings.0.optional_features)
+            .unwrap_or_else(|| device.features());
+        main_world.insert_resource(RenderCreationReport::new(
+            requested_features,
+            device.features(),
+            device.limits(),
+        ));
+
+        // Only set for automatic creation, manually created renderers have no settings.
+        let pipeline_cache_dir = main_world
+            .get_resource::<LastWgpuSettings>()
+            .and_then(|settings| settings.0.pipeline_cache_dir.clone());
+
         let compressed_image_format_support =
             CompressedImageFormatSupport(CompressedImageFormats::from_features(device.features()));
 
@@ -192,25 +265,119 @@ impl RenderResources {
             let additional_vulkan_features: renderer::raw_vulkan_init::AdditionalVulkanFeatures =
                 self.5;
             render_world.insert_resource(additional_vulkan_features);
+            let additional_vulkan_queues: renderer::raw_vulkan_init::AdditionalVulkanQueues =
+                self.6;
+            render_world.insert_resource(additional_vulkan_queues);
         }
 
         render_world.insert_resource(instance);
-        render_world.insert_resource(PipelineCache::new(
-            device.clone(),
-            render_adapter.clone(),
-            synchronous_pipeline_compilation,
-        ));
-        render_world.insert_resource(DeviceErrorHandler::new(&device));
+        // A cache for a lost device is recreated when `RenderDeviceRecreated` is triggered.
+        let new_pipeline_cache = !render_world.contains_resource::<PipelineCache>();
+        if new_pipeline_cache {
+            let mut pipeline_cache = PipelineCache::new(
+                device.clone(),
+                render_adapter.clone(),
+                synchronous_pipeline_compilation,
+            );
+            if let Some(cache_dir) = &pipeline_cache_dir {
+                pipeline_cache = pipeline_cache.with_disk_cache(cache_dir);
+            }
+            render_world.insert_resource(pipeline_cache);
+        }
+        let device_error_handler =
+            DeviceErrorHandler::new(&device, render_world.get_resource::<DeviceErrorHandler>());
+        render_world.insert_resource(device_error_handler);
+        render_world.insert_resource(device.frame_statistics().clone());
         render_world.insert_resource(device);
         render_world.insert_resource(queue);
         render_world.insert_resource(render_adapter);
         render_world.insert_resource(adapter_info);
+        // Observers of `RenderDeviceRecreated` can use the new device.
+        advance_device_generation(main_world, render_world);
+        if new_pipeline_cache {
+            let generation = *render_world.resource::<DeviceGeneration>();
+            render_world
+                .resource_mut::<PipelineCache>()
+                .device_generation = generation;
+        }
+
+        if let Some(progress) = main_world.get_resource::<RenderInitProgress>() {
+            progress.set(RenderInitStage::Ready);
+        }
     }
 }
 
+/// A coarse stage of renderer creation, see [`RenderInitProgress`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum RenderInitStage {
+    /// Renderer creation hasn't started.
+    #[default]
+    NotStarted,
+    /// Creating the wgpu instance and the surface of the primary window.
+    CreatingInstance,
+    /// Enumerating the adapters to find the one named in [`WgpuSettings::adapter_name`], or of
+    /// the [`WgpuSettings::preferred_backend`].
+    EnumeratingAdapters,
+    /// Requesting an adapter from the instance.
+    RequestingAdapter,
+    /// Requesting the device and queue from the adapter.
+    RequestingDevice,
+    /// Inserting the created resources into the main and render worlds.
+    Unpacking,
+    /// The renderer is ready.
+    Ready,
+}
+
+impl RenderInitStage {
+    const ALL: [Self; 7] = [
+        Self::NotStarted,
+        Self::CreatingInstance,
+        Self::EnumeratingAdapters,
+        Self::RequestingAdapter,
+        Self::RequestingDevice,
+        Self::Unpacking,
+        Self::Ready,
+    ];
+}
+
+/// The progress of renderer creation, which can take seconds, e.g. to show it on a loading
+/// screen.
+///
+/// This resource lives in the main world and is updated from the thread creating the renderer,
+/// including when the renderer is recreated after an error.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct RenderInitProgress(Arc<AtomicU8>);
+
+impl RenderInitProgress {
+    /// The current stage of renderer creation.
+    pub fn stage(&self) -> RenderInitStage {
+        RenderInitStage::ALL[self.0.load(Ordering::Acquire) as usize]
+    }
+
+    pub(crate) fn set(&self, stage: RenderInitStage) {
+        #[cfg(test)]
+        RENDER_INIT_STAGES.with_borrow_mut(|stages| stages.push(stage));
+        self.0.store(stage as u8, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+std::thread_local! {
+    /// Every stage set on this thread, to test the order renderer creation goes through them.
+    pub(crate) static RENDER_INIT_STAGES: core::cell::RefCell<Vec<RenderInitStage>> =
+        const { core::cell::RefCell::new(Vec::new()) };
+}
+
 /// An enum describing how the renderer will initialize resources. This is used when creating the [`RenderPlugin`](crate::RenderPlugin).
+#[derive(Clone)]
 pub enum RenderCreation {
     /// Allows renderer resource initialization to happen outside of the rendering plugin.
+    ///
+    /// No adapter is selected, the given resources are used as they are and the renderer is
+    /// ready right away. To recover from device loss, return a
+    /// [`RenderErrorPolicy::Recover`](crate::error_handler::RenderErrorPolicy::Recover) with a
+    /// new device, as recovering with the lost one fails again.
     Manual(RenderResources),
     /// Lets the rendering plugin create resources itself.
     Automatic(Box<WgpuSettings>),
@@ -226,6 +393,8 @@ impl RenderCreation {
         instance: RenderInstance,
         #[cfg(feature = "raw_vulkan_init")]
         additional_vulkan_features: renderer::raw_vulkan_init::AdditionalVulkanFeatures,
+        #[cfg(feature = "raw_vulkan_init")]
+        additional_vulkan_queues: renderer::raw_vulkan_init::AdditionalVulkanQueues,
     ) -> Self {
         RenderResources(
             device,
@@ -235,58 +404,173 @@ impl RenderCreation {
             instance,
             #[cfg(feature = "raw_vulkan_init")]
             additional_vulkan_features,
+            #[cfg(feature = "raw_vulkan_init")]
+            additional_vulkan_queues,
         )
         .into()
     }
 
-    /// Creates [`RenderResources`] from this [`RenderCreation`] and an optional primary window
-    /// and writes them into `future_resources`, possibly asynchronously.
+    /// Creates a [`RenderCreation::Manual`] variant from handles created outside of the renderer,
+    /// e.g. to share the device with another renderer.
+    ///
+    /// The [`RenderAdapterInfo`] is queried from `adapter`. No adapter or device is requested, and
+    /// the [`PipelineCache`] is created for the given `device` and `adapter`.
+    pub fn from_wgpu(
+        instance: wgpu::Instance,
+        adapter: wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+    ) -> Self {
+        let adapter_info = adapter.get_info();
+        let render_device = RenderDevice::from(device);
+        let render_queue = RenderQueue::new(queue, &render_device);
+        RenderResources(
+            render_device,
+            render_queue,
+            RenderAdapterInfo(WgpuWrapper::new(adapter_info)),
+            RenderAdapter(Arc::new(WgpuWrapper::new(adapter))),
+            RenderInstance(Arc::new(WgpuWrapper::new(instance))),
+            #[cfg(feature = "raw_vulkan_init")]
+            Default::default(),
+            #[cfg(feature = "raw_vulkan_init")]
+            Default::default(),
+        )
+        .into()
+    }
+
+    /// Function to create a [`RenderCreation::Automatic`] variant for a renderer without windows,
+    /// see [`WgpuSettings::headless`].
+    ///
+    /// If no hardware adapter is found, a software adapter is used instead.
+    pub fn headless(settings: WgpuSettings) -> Self {
+        WgpuSettings {
+            headless: true,
+            ..settings
+        }
+        .into()
+    }
+
+    /// Creates [`RenderResources`] from this [`RenderCreation`] and writes them into
+    /// `future_resources`, blocking until they are created, except on the web.
     ///
     /// Returns true if creation was successful, false otherwise.
     ///
-    /// Note: [`RenderCreation::Manual`] will ignore the provided primary window.
+    /// Note: [`RenderCreation::Manual`] will ignore the primary window of the `context`.
     pub(crate) fn create_render(
         &self,
         future_resources: FutureRenderResources,
-        primary_window: Option<RawHandleWrapperHolder>,
-        #[cfg(feature = "raw_vulkan_init")]
-        raw_vulkan_init_settings: renderer::raw_vulkan_init::RawVulkanInitSettings,
+        context: RenderCreationContext,
     ) -> bool {
-        match self {
-            RenderCreation::Manual(resources) => {
-                *future_resources.lock().unwrap() = Some(resources.clone());
-            }
-            RenderCreation::Automatic(render_creation) => {
-                let Some(backends) = render_creation.backends else {
-                    return false;
-                };
-                let settings = render_creation.clone();
-
-                let async_renderer = async move {
-                    let render_resources = renderer::initialize_renderer(
-                        backends,
-                        primary_window,
-                        &settings,
-                        #[cfg(feature = "raw_vulkan_init")]
-                        raw_vulkan_init_settings,
-                    )
-                    .await;
-
-                    *future_resources.lock().unwrap() = Some(render_resources);
-                };
-
-                // In wasm, spawn a task and detach it for execution
-                #[cfg(target_arch = "wasm32")]
-                bevy_tasks::IoTaskPool::get()
-                    .spawn_local(async_renderer)
-                    .detach();
-                // Otherwise, just block for it to complete
-                #[cfg(not(target_arch = "wasm32"))]
-                bevy_tasks::block_on(async_renderer);
-            }
+        let Some(creation) = self.creation_task(future_resources, context) else {
+            return false;
+        };
+        // In wasm, spawn a task and detach it for execution
+        #[cfg(target_arch = "wasm32")]
+        {
+            bevy_tasks::IoTaskPool::get().spawn_local(creation).detach();
+            true
         }
+        // Otherwise, just block for it to complete
+        #[cfg(not(target_arch = "wasm32"))]
+        bevy_tasks::block_on(creation)
+    }
+
+    /// Like [`RenderCreation::create_render`], but never blocks: the renderer is created on the
+    /// [`AsyncComputeTaskPool`](bevy_tasks::AsyncComputeTaskPool), and
+    /// [`FutureRenderResources::poll`] tells when it is done.
+    ///
+    /// Returns true if creation started, false otherwise.
+    pub(crate) fn spawn_render(
+        &self,
+        future_resources: FutureRenderResources,
+        context: RenderCreationContext,
+    ) -> bool {
+        let Some(creation) = self.creation_task(future_resources, context) else {
+            return false;
+        };
+        #[cfg(target_arch = "wasm32")]
+        bevy_tasks::IoTaskPool::get().spawn_local(creation).detach();
+        #[cfg(not(target_arch = "wasm32"))]
+        bevy_tasks::AsyncComputeTaskPool::get_or_init(bevy_tasks::TaskPool::default)
+            .spawn(creation)
+            .detach();
         true
     }
+
+    /// The task creating the renderer into `future_resources`, returning whether it succeeded.
+    ///
+    /// Returns `None` if no renderer can be created, because no backend is enabled.
+    pub(crate) fn creation_task(
+        &self,
+        future_resources: FutureRenderResources,
+        context: RenderCreationContext,
+    ) -> Option<impl ConditionalSendFuture<Output = bool> + 'static> {
+        let backends = match self {
+            RenderCreation::Manual(_) => Backends::empty(),
+            RenderCreation::Automatic(settings) => settings.backends?,
+        };
+        let render_creation = self.clone();
+
+        Some(async move {
+            let settings = match render_creation {
+                RenderCreation::Manual(resources) => {
+                    future_resources.set(resources);
+                    return true;
+                }
+                RenderCreation::Automatic(settings) => settings,
+            };
+            match renderer::initialize_renderer(
+                backends,
+                context.primary_window,
+                &settings,
+                &context.progress,
+                #[cfg(feature = "raw_vulkan_init")]
+                context.raw_vulkan_init_settings,
+            )
+            .await
+            {
+                Ok(render_resources) => {
+                    future_resources.set(render_resources);
+                    true
+                }
+                Err(error) => {
+                    bevy_log::error!("Failed to create the renderer: {error}");
+                    future_resources.fail();
+                    false
+                }
+            }
+        })
+    }
+}
+
+/// What a [`RenderCreation`] needs from the main world to create a renderer.
+pub(crate) struct RenderCreationContext {
+    primary_window: Option<RawHandleWrapperHolder>,
+    progress: RenderInitProgress,
+    #[cfg(feature = "raw_vulkan_init")]
+    pub(crate) raw_vulkan_init_settings: renderer::raw_vulkan_init::RawVulkanInitSettings,
+}
+
+impl RenderCreationContext {
+    pub(crate) fn from_main_world(main_world: &mut World) -> Self {
+        let primary_window = main_world
+            .query_filtered::<&RawHandleWrapperHolder, With<PrimaryWindow>>()
+            .single(main_world)
+            .ok()
+            .cloned();
+
+        Self {
+            primary_window,
+            progress: main_world
+                .get_resource_or_init::<RenderInitProgress>()
+                .clone(),
+            #[cfg(feature = "raw_vulkan_init")]
+            raw_vulkan_init_settings: main_world
+                .get_resource::<renderer::raw_vulkan_init::RawVulkanInitSettings>()
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
 }
 
 impl From<RenderResources> for RenderCreation {
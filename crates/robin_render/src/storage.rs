@@ -183,7 +183,7 @@ impl RenderAsset for GpuShaderBuffer {
                     .contains(BufferUsages::COPY_DST))
         {
             if let Some(ref data) = source_asset.data {
-                render_queue.write_buffer(&prev.buffer, 0, data);
+                render_queue.write_buffer_counted(&prev.buffer, 0, data);
             }
             prev.buffer.clone()
         } else if let Some(ref data) = source_asset.data {
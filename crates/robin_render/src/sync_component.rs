@@ -62,3 +62,60 @@ impl<C: SyncComponent<F>, F: Send + Sync + 'static> Plugin for SyncComponentPlug
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::App;
+    use bevy_ecs::{component::Component, schedule::ScheduleLabel};
+
+    use super::{SyncComponent, SyncComponentPlugin};
+    use crate::{Render, RenderApp, extract_plugin::ExtractPlugin, sync_world::RenderEntity};
+
+    #[derive(Component)]
+    struct Synced;
+
+    #[derive(Component)]
+    struct RenderA;
+
+    #[derive(Component)]
+    struct RenderB;
+
+    #[derive(Component)]
+    struct Unrelated;
+
+    impl SyncComponent for Synced {
+        type Target = (RenderA, RenderB);
+    }
+
+    #[test]
+    fn removal_is_synced() {
+        let mut app = App::new();
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(SyncComponentPlugin::<Synced>::default());
+        app.get_sub_app_mut(RenderApp).unwrap().update_schedule = Some(Render.intern());
+
+        let main_entity = app.world_mut().spawn(Synced).id();
+        app.update();
+
+        let render_entity = app.world().get::<RenderEntity>(main_entity).unwrap().id();
+        let render_world = app.get_sub_app_mut(RenderApp).unwrap().world_mut();
+        render_world
+            .entity_mut(render_entity)
+            .insert((RenderA, RenderB, Unrelated));
+
+        app.world_mut().entity_mut(main_entity).remove::<Synced>();
+        app.update();
+
+        let render_world = app.get_sub_app(RenderApp).unwrap().world();
+        let entity = render_world.entity(render_entity);
+        assert!(!entity.contains::<RenderA>());
+        assert!(!entity.contains::<RenderB>());
+        assert!(entity.contains::<Unrelated>());
+
+        // Despawning the main entity despawns the render entity with everything on it.
+        app.world_mut().despawn(main_entity);
+        app.update();
+        let render_world = app.get_sub_app(RenderApp).unwrap().world();
+        assert!(render_world.get_entity(render_entity).is_err());
+    }
+}
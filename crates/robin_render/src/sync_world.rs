@@ -1,18 +1,25 @@
 use bevy_app::Plugin;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
-    component::Component,
-    entity::{ContainsEntity, Entity, EntityEquivalent, EntityHash},
-    lifecycle::{Add, Remove},
+    component::{Component, ComponentId},
+    entity::{ContainsEntity, Entity, EntityEquivalent, EntityHash, EntityHashMap, EntityHashSet},
+    event::EntityEvent,
+    lifecycle::{Add, HookContext, Remove},
     observer::On,
-    query::With,
+    query::{With, Without},
     reflect::ReflectComponent,
     resource::Resource,
-    system::{Local, Query, ResMut, SystemState},
-    world::{EntityWorldMut, Mut, World},
+    system::{Commands, Local, Query, ResMut, SystemState},
+    world::{DeferredWorld, EntityWorldMut, Mut, World},
 };
 use bevy_platform::collections::{HashMap, HashSet};
+#[cfg(feature = "debug")]
+use bevy_platform::sync::atomic::{AtomicUsize, Ordering};
 use bevy_reflect::{Reflect, std_traits::ReflectDefault};
+use bevy_tasks::{ComputeTaskPool, TaskPool};
+use core::marker::PhantomData;
+
+use crate::diagnostic::extract_throughput_diagnostic_plugin::ExtractThroughputMeasurements;
 
 /// A plugin that synchronizes entities with [`SyncToRenderWorld`] between the main world and the render world.
 ///
@@ -72,8 +79,9 @@ use bevy_reflect::{Reflect, std_traits::ReflectDefault};
 /// Note that this effectively establishes a link between the main world entity and the render world entity.
 /// Not every entity needs to be synchronized, however; only entities with the [`SyncToRenderWorld`] component are synced.
 /// Adding [`SyncToRenderWorld`] to a main world component will establish such a link.
+/// Entities with [`NoRenderWorldSync`] are never synchronized.
 /// Once a synchronized main entity is despawned, its corresponding render entity will be automatically
-/// despawned in the next `sync`.
+/// despawned in the next `sync`, after triggering [`RenderEntityDespawned`] on it.
 ///
 /// The sync step does not copy any of component data between worlds, since its often not necessary to transfer over all
 /// the components of a main world entity.
@@ -83,6 +91,8 @@ use bevy_reflect::{Reflect, std_traits::ReflectDefault};
 /// Moreover, [`SyncWorldPlugin`] only synchronizes *entities*. [`RenderAsset`](crate::render_asset::RenderAsset)s like meshes and textures are handled
 /// differently.
 ///
+/// To only sync the entities with a given marker component, add [`SyncWithMarkerPlugin`].
+///
 /// [`PipelinedRenderingPlugin`]: crate::pipelined_rendering::PipelinedRenderingPlugin
 /// [`ExtractComponentPlugin`]: crate::extract_component::ExtractComponentPlugin
 /// [`SyncComponentPlugin`]: crate::sync_component::SyncComponentPlugin
@@ -108,6 +118,76 @@ impl Plugin for SyncWorldPlugin {
         );
     }
 }
+
+/// Restricts [`SyncWorldPlugin`] to the entities with the marker component `M`, e.g. when most
+/// entities with [`SyncToRenderWorld`] never need to be rendered.
+///
+/// Entities are synced once they have both [`SyncToRenderWorld`] and `M`, and their render entity
+/// is despawned when `M` is removed. Adding `M` again syncs them to a new render entity.
+///
+/// # Panics
+///
+/// Only a single marker is supported: adding this plugin for a second marker type panics, rather
+/// than silently replacing the first marker.
+pub struct SyncWithMarkerPlugin<M: Component>(PhantomData<M>);
+
+impl<M: Component> Default for SyncWithMarkerPlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: Component> Plugin for SyncWithMarkerPlugin<M> {
+    fn build(&self, app: &mut bevy_app::App) {
+        if let Some(SyncMarker(marker)) = app.world().get_resource::<SyncMarker>() {
+            let name = app
+                .world()
+                .components()
+                .get_name(*marker)
+                .map(|name| name.to_string())
+                .unwrap_or_default();
+            panic!(
+                "entities are already synced with the marker `{name}`, they can't also be synced \
+                with `{}`",
+                core::any::type_name::<M>()
+            );
+        }
+        let marker = app.world_mut().register_component::<M>();
+        app.insert_resource(SyncMarker(marker));
+        app.add_observer(
+            |add: On<Add, M>,
+             mut pending: ResMut<PendingSyncEntity>,
+             query: Query<
+                (),
+                (
+                    With<SyncToRenderWorld>,
+                    Without<RenderEntity>,
+                    Without<NoRenderWorldSync>,
+                ),
+            >| {
+                if query.contains(add.entity) {
+                    pending.push(EntityRecord::Added(add.entity));
+                }
+            },
+        );
+        app.add_observer(
+            |remove: On<Remove, M>,
+             mut pending: ResMut<PendingSyncEntity>,
+             query: Query<&RenderEntity>,
+             mut commands: Commands| {
+                if let Ok(e) = query.get(remove.entity) {
+                    pending.push(EntityRecord::Removed(*e));
+                    commands.entity(remove.entity).try_remove::<RenderEntity>();
+                };
+            },
+        );
+    }
+}
+
+/// The marker component entities need to be synced, see [`SyncWithMarkerPlugin`].
+#[derive(Resource)]
+struct SyncMarker(ComponentId);
+
 /// Marker component that indicates that its entity needs to be synchronized to the render world.
 ///
 /// This component is automatically added as a required component by [`ExtractComponentPlugin`] and [`SyncComponentPlugin`].
@@ -123,6 +203,25 @@ impl Plugin for SyncWorldPlugin {
 #[component(storage = "SparseSet")]
 pub struct SyncToRenderWorld;
 
+/// Marker component that keeps its entity from being synchronized to the render world, even if it
+/// has [`SyncToRenderWorld`], e.g. as a required component of a component only the main world
+/// cares about for this entity.
+///
+/// It must be present when [`SyncToRenderWorld`] is added, adding it later has no effect.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default, Clone)]
+pub struct NoRenderWorldSync;
+
+/// Triggered in the render world on a synced render entity right before it is despawned because
+/// its main world entity was despawned or lost [`SyncToRenderWorld`], to clean up data tied to it.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct RenderEntityDespawned {
+    /// The render world entity being despawned.
+    pub entity: Entity,
+    /// The main world entity it was synced with.
+    pub main_entity: MainEntity,
+}
+
 /// Component added on the main world entities that are synced to the Render World in order to keep track of the corresponding render world entity.
 ///
 /// Can also be used as a newtype wrapper for render world entities.
@@ -156,8 +255,64 @@ unsafe impl EntityEquivalent for RenderEntity {}
 ///
 /// Can also be used as a newtype wrapper for main world entities.
 #[derive(Component, Deref, Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Reflect)]
+#[component(on_remove = remove_from_entity_sync_map)]
 #[reflect(Component, Clone)]
 pub struct MainEntity(Entity);
+
+fn remove_from_entity_sync_map(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+    let Some(&main_entity) = world.get::<MainEntity>(entity) else {
+        return;
+    };
+    if let Some(mut map) = world.get_resource_mut::<EntitySyncMap>() {
+        map.remove(main_entity, entity);
+    }
+}
+
+/// Maps the main world entities synced to the render world to their render entities, and back,
+/// without querying either world.
+///
+/// This lives in the render world and is maintained by entity sync. Entries are removed when the
+/// render entity is despawned or loses its [`MainEntity`], whatever the reason. Render entities
+/// spawned with a [`MainEntity`] by other means, e.g. [`TemporaryRenderEntity`]s, aren't part of
+/// it.
+#[derive(Resource, Debug, Default)]
+pub struct EntitySyncMap {
+    render_entities: MainEntityHashMap<Entity>,
+    main_entities: EntityHashMap<MainEntity>,
+}
+
+impl EntitySyncMap {
+    /// The render entity `main_entity` is synced to.
+    pub fn get_render_entity(&self, main_entity: MainEntity) -> Option<Entity> {
+        self.render_entities.get(&main_entity).copied()
+    }
+
+    /// The main world entity `render_entity` was synced from.
+    pub fn get_main_entity(&self, render_entity: Entity) -> Option<MainEntity> {
+        self.main_entities.get(&render_entity).copied()
+    }
+
+    /// The number of synced entities.
+    pub fn len(&self) -> usize {
+        self.render_entities.len()
+    }
+
+    /// Returns `true` if no entity is synced.
+    pub fn is_empty(&self) -> bool {
+        self.render_entities.is_empty()
+    }
+
+    fn insert(&mut self, main_entity: MainEntity, render_entity: Entity) {
+        self.render_entities.insert(main_entity, render_entity);
+        self.main_entities.insert(render_entity, main_entity);
+    }
+
+    fn remove(&mut self, main_entity: MainEntity, render_entity: Entity) {
+        if self.main_entities.remove(&render_entity).is_some() {
+            self.render_entities.remove(&main_entity);
+        }
+    }
+}
 impl MainEntity {
     #[inline]
     pub fn id(&self) -> Entity {
@@ -191,6 +346,16 @@ pub type MainEntityHashSet = HashSet<MainEntity, EntityHash>;
 #[reflect(Component, Default, Clone)]
 pub struct TemporaryRenderEntity;
 
+/// Keeps a [`TemporaryRenderEntity`] alive for the given number of additional frames, e.g. for
+/// fading effects spawned once in the render world.
+///
+/// The count is decremented at the end of every frame, and the entity is despawned at the end of
+/// the frame it reached zero in. A count of zero behaves like [`TemporaryRenderEntity`] alone.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default, Clone)]
+#[require(TemporaryRenderEntity)]
+pub struct TemporaryRenderEntityFor(pub u32);
+
 /// A record enum to what entities with [`SyncToRenderWorld`] have been added or removed.
 #[derive(Debug)]
 pub(crate) enum EntityRecord {
@@ -205,57 +370,219 @@ pub(crate) enum EntityRecord {
     ComponentRemoved(Entity, fn(EntityWorldMut<'_>)),
 }
 
+/// Counts the changes applied to the render world by sync and extraction in the current frame.
+///
+/// The counters are reset at the start of every extraction. Component inserts and removals
+/// done by [`ExtractComponentPlugin`] are counted when they are queued, and applied later in
+/// [`RenderSystems::ExtractCommands`](crate::RenderSystems::ExtractCommands).
+///
+/// [`ExtractComponentPlugin`]: crate::extract_component::ExtractComponentPlugin
+#[cfg(feature = "debug")]
+#[derive(Resource, Debug, Default)]
+pub struct ExtractChangeSummary {
+    entities_spawned: AtomicUsize,
+    entities_despawned: AtomicUsize,
+    components_inserted: AtomicUsize,
+    components_removed: AtomicUsize,
+}
+
+#[cfg(feature = "debug")]
+impl ExtractChangeSummary {
+    /// The number of render entities spawned for newly synced main world entities.
+    pub fn entities_spawned(&self) -> usize {
+        self.entities_spawned.load(Ordering::Relaxed)
+    }
+
+    /// The number of render entities despawned because their main world entity was despawned.
+    pub fn entities_despawned(&self) -> usize {
+        self.entities_despawned.load(Ordering::Relaxed)
+    }
+
+    /// The number of extracted components inserted into render entities.
+    pub fn components_inserted(&self) -> usize {
+        self.components_inserted.load(Ordering::Relaxed)
+    }
+
+    /// The number of extracted components removed from render entities.
+    pub fn components_removed(&self) -> usize {
+        self.components_removed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_inserted(&self, count: usize) {
+        self.components_inserted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_removed(&self, count: usize) {
+        self.components_removed.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
 // Entity Record in MainWorld pending to Sync
 #[derive(Resource, Default, Deref, DerefMut)]
 pub(crate) struct PendingSyncEntity {
     records: Vec<EntityRecord>,
 }
 
+/// The number of added entities checked per task when entities are synced in parallel.
+const PARALLEL_SYNC_BATCH_SIZE: usize = 1024;
+
 pub(crate) fn entity_sync_system(main_world: &mut World, render_world: &mut World) {
+    sync_entities(main_world, render_world, cfg!(feature = "multi_threaded"));
+}
+
+/// Syncs the pending entities, checking the added ones on the [`ComputeTaskPool`] if `parallel`.
+///
+/// Render world mutations stay serial, so both paths produce the same render world.
+fn sync_entities(main_world: &mut World, render_world: &mut World, parallel: bool) {
+    #[cfg(feature = "debug")]
+    if let Some(mut summary) = render_world.get_resource_mut::<ExtractChangeSummary>() {
+        *summary = ExtractChangeSummary::default();
+    }
+
     main_world.resource_scope(|world, mut pending: Mut<PendingSyncEntity>| {
-        // TODO : batching record
+        let mut entities_synced = 0;
+        let marker = world.get_resource::<SyncMarker>().map(|marker| marker.0);
+        // New entities are spawned in a single batch once the other records were applied, which
+        // is much cheaper than spawning them one by one when many entities appear at once.
+        let mut added = Vec::new();
         for record in pending.drain(..) {
             match record {
-                EntityRecord::Added(e) => {
-                    if let Ok(mut main_entity) = world.get_entity_mut(e) {
-                        match main_entity.entry::<RenderEntity>() {
-                            bevy_ecs::world::ComponentEntry::Occupied(_) => {
-                                panic!("Attempting to synchronize an entity that has already been synchronized!");
-                            }
-                            bevy_ecs::world::ComponentEntry::Vacant(entry) => {
-                                let id = render_world.spawn(MainEntity(e)).id();
-
-                                entry.insert(RenderEntity(id));
-                            }
-                        };
-                    }
-                }
+                EntityRecord::Added(e) => added.push(e),
                 EntityRecord::Removed(render_entity) => {
+                    if let Some(&main_entity) = render_world.get::<MainEntity>(render_entity.id()) {
+                        render_world.trigger(RenderEntityDespawned {
+                            entity: render_entity.id(),
+                            main_entity,
+                        });
+                    }
+                    // Observers may have despawned it already.
                     if let Ok(ec) = render_world.get_entity_mut(render_entity.id()) {
                         ec.despawn();
+                        entities_synced += 1;
+                        #[cfg(feature = "debug")]
+                        if let Some(summary) = render_world.get_resource::<ExtractChangeSummary>() {
+                            summary.entities_despawned.fetch_add(1, Ordering::Relaxed);
+                        }
                     };
                 }
                 EntityRecord::ComponentRemoved(main_entity, removal_function) => {
                     let Some(render_entity) = world.get::<RenderEntity>(main_entity) else {
                         continue;
                     };
-                    if let Ok(render_world_entity) = render_world.get_entity_mut(render_entity.id()) {
+                    if let Ok(render_world_entity) = render_world.get_entity_mut(render_entity.id())
+                    {
                         removal_function(render_world_entity);
+                        #[cfg(feature = "debug")]
+                        if let Some(summary) = render_world.get_resource::<ExtractChangeSummary>() {
+                            summary.record_removed(1);
+                        }
                     }
-                },
+                }
+            }
+        }
+
+        let added = if parallel && added.len() > PARALLEL_SYNC_BATCH_SIZE {
+            entities_to_sync_parallel(world, &added, marker)
+        } else {
+            entities_to_sync(world, &added, marker)
+        };
+        let Ok(added) = added else {
+            panic!("Attempting to synchronize an entity that has already been synchronized!");
+        };
+        // The marker and `SyncToRenderWorld` may both have been added this frame.
+        let mut added_set = EntityHashSet::default();
+        let added = added
+            .into_iter()
+            .filter(|&e| added_set.insert(e))
+            .collect::<Vec<_>>();
+
+        if !added.is_empty() {
+            let render_entities = render_world
+                .spawn_batch(added.iter().map(|&e| MainEntity(e)))
+                .collect::<Vec<_>>();
+            let mut map = render_world.get_resource_or_init::<EntitySyncMap>();
+            for (&main_entity, &render_entity) in added.iter().zip(&render_entities) {
+                map.insert(MainEntity(main_entity), render_entity);
             }
+            world.insert_batch(
+                added
+                    .iter()
+                    .copied()
+                    .zip(render_entities.into_iter().map(RenderEntity)),
+            );
+
+            entities_synced += added.len();
+            #[cfg(feature = "debug")]
+            if let Some(summary) = render_world.get_resource::<ExtractChangeSummary>() {
+                summary
+                    .entities_spawned
+                    .fetch_add(added.len(), Ordering::Relaxed);
+            }
+        }
+
+        if let Some(measurements) = world.get_resource::<ExtractThroughputMeasurements>() {
+            measurements.record_synced(entities_synced);
+        }
+    });
+}
+
+/// Returns the `added` main entities that get a render entity, in order, or an entity that was
+/// already synchronized.
+fn entities_to_sync(
+    world: &World,
+    added: &[Entity],
+    marker: Option<ComponentId>,
+) -> Result<Vec<Entity>, Entity> {
+    let mut entities = Vec::with_capacity(added.len());
+    for &e in added {
+        if let Ok(main_entity) = world.get_entity(e)
+            && !main_entity.contains::<NoRenderWorldSync>()
+            && marker.is_none_or(|marker| main_entity.contains_id(marker))
+        {
+            if main_entity.contains::<RenderEntity>() {
+                return Err(e);
+            }
+            entities.push(e);
+        }
+    }
+    Ok(entities)
+}
+
+/// Like [`entities_to_sync`], checking batches of [`PARALLEL_SYNC_BATCH_SIZE`] entities on the
+/// [`ComputeTaskPool`].
+fn entities_to_sync_parallel(
+    world: &World,
+    added: &[Entity],
+    marker: Option<ComponentId>,
+) -> Result<Vec<Entity>, Entity> {
+    // The results are returned in the order the tasks were spawned.
+    let batches = ComputeTaskPool::get_or_init(TaskPool::default).scope(|scope| {
+        for batch in added.chunks(PARALLEL_SYNC_BATCH_SIZE) {
+            scope.spawn(async move { entities_to_sync(world, batch, marker) });
         }
     });
+    let mut entities = Vec::with_capacity(added.len());
+    for batch in batches {
+        entities.extend(batch?);
+    }
+    Ok(entities)
 }
 
 pub(crate) fn despawn_temporary_render_entities(
     world: &mut World,
-    state: &mut SystemState<Query<Entity, With<TemporaryRenderEntity>>>,
+    state: &mut SystemState<
+        Query<(Entity, Option<&mut TemporaryRenderEntityFor>), With<TemporaryRenderEntity>>,
+    >,
     mut local: Local<Vec<Entity>>,
 ) {
-    let query = state.get(world).unwrap();
+    let mut query = state.get_mut(world).unwrap();
 
-    local.extend(query.iter());
+    for (entity, lifetime) in &mut query {
+        match lifetime {
+            Some(mut lifetime) if lifetime.0 > 0 => lifetime.0 -= 1,
+            _ => local.push(entity),
+        }
+    }
 
     // Ensure next frame allocation keeps order
     local.sort_unstable_by_key(|e| e.index());
@@ -529,30 +856,37 @@ mod render_entities_world_query_impls {
 
 #[cfg(test)]
 mod tests {
+    use bevy_app::App;
     use bevy_ecs::{
         component::Component,
         entity::Entity,
         lifecycle::{Add, Remove},
         observer::On,
         query::With,
+        resource::Resource,
+        schedule::ScheduleLabel,
         system::{Query, ResMut},
         world::World,
     };
 
     use super::{
-        EntityRecord, MainEntity, PendingSyncEntity, RenderEntity, SyncToRenderWorld,
-        entity_sync_system,
+        EntityRecord, EntitySyncMap, MainEntity, NoRenderWorldSync, PendingSyncEntity,
+        RenderEntity, RenderEntityDespawned, SyncToRenderWorld, SyncWithMarkerPlugin,
+        TemporaryRenderEntity, TemporaryRenderEntityFor, despawn_temporary_render_entities,
+        entity_sync_system, sync_entities,
     };
+    use crate::{Render, RenderApp, extract_plugin::ExtractPlugin};
 
     #[derive(Component)]
     struct RenderDataComponent;
 
-    #[test]
-    fn sync_world() {
+    /// Creates a main world that queues the entities to sync like [`SyncWorldPlugin`], and an
+    /// empty render world.
+    ///
+    /// [`SyncWorldPlugin`]: super::SyncWorldPlugin
+    fn sync_worlds() -> (World, World) {
         let mut main_world = World::new();
-        let mut render_world = World::new();
         main_world.init_resource::<PendingSyncEntity>();
-
         main_world.add_observer(
             |add: On<Add, SyncToRenderWorld>, mut pending: ResMut<PendingSyncEntity>| {
                 pending.push(EntityRecord::Added(add.entity));
@@ -567,6 +901,157 @@ mod tests {
                 };
             },
         );
+        (main_world, World::new())
+    }
+
+    #[test]
+    fn temporary_render_entity_lifetimes() {
+        let mut world = World::new();
+        let frame = world.spawn(TemporaryRenderEntity).id();
+        let zero = world.spawn(TemporaryRenderEntityFor(0)).id();
+        let one = world.spawn(TemporaryRenderEntityFor(1)).id();
+        let three = world.spawn(TemporaryRenderEntityFor(3)).id();
+
+        let mut cleanup = |world: &mut World| {
+            world
+                .run_system_cached(despawn_temporary_render_entities)
+                .unwrap();
+        };
+        let alive = |world: &World| {
+            [frame, zero, one, three].map(|entity| world.get_entity(entity).is_ok())
+        };
+
+        cleanup(&mut world);
+        assert_eq!(alive(&world), [false, false, true, true]);
+        cleanup(&mut world);
+        assert_eq!(alive(&world), [false, false, false, true]);
+        cleanup(&mut world);
+        cleanup(&mut world);
+        assert_eq!(alive(&world), [false, false, false, false]);
+    }
+
+    #[test]
+    fn sync_with_marker() {
+        #[derive(Component)]
+        struct Rendered;
+
+        let mut app = App::new();
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(SyncWithMarkerPlugin::<Rendered>::default());
+        app.get_sub_app_mut(RenderApp).unwrap().update_schedule = Some(Render.intern());
+
+        let synced = |app: &App| {
+            app.get_sub_app(RenderApp)
+                .unwrap()
+                .world()
+                .resource::<EntitySyncMap>()
+                .len()
+        };
+
+        let both = app.world_mut().spawn((SyncToRenderWorld, Rendered)).id();
+        let entity = app.world_mut().spawn(SyncToRenderWorld).id();
+        app.update();
+        assert_eq!(synced(&app), 1);
+        assert!(app.world().get::<RenderEntity>(both).is_some());
+        assert!(app.world().get::<RenderEntity>(entity).is_none());
+
+        app.world_mut().entity_mut(entity).insert(Rendered);
+        app.update();
+        assert_eq!(synced(&app), 2);
+        let render_entity = app.world().get::<RenderEntity>(entity).unwrap().id();
+
+        app.world_mut().entity_mut(entity).remove::<Rendered>();
+        app.update();
+        assert_eq!(synced(&app), 1);
+        assert!(app.world().get::<RenderEntity>(entity).is_none());
+        let render_world = app.get_sub_app(RenderApp).unwrap().world();
+        assert!(render_world.get_entity(render_entity).is_err());
+
+        app.world_mut().entity_mut(entity).insert(Rendered);
+        app.update();
+        assert_eq!(synced(&app), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "already synced with the marker")]
+    fn sync_with_a_second_marker_panics() {
+        #[derive(Component)]
+        struct Rendered;
+        #[derive(Component)]
+        struct AlsoRendered;
+
+        let mut app = App::new();
+        app.add_plugins(ExtractPlugin::default());
+        app.add_plugins(SyncWithMarkerPlugin::<Rendered>::default());
+        app.add_plugins(SyncWithMarkerPlugin::<AlsoRendered>::default());
+    }
+
+    #[test]
+    fn batched_sync_keeps_mappings() {
+        let (mut main_world, mut render_world) = sync_worlds();
+
+        let main_entities = (0..1000)
+            .map(|_| main_world.spawn(SyncToRenderWorld).id())
+            .collect::<Vec<_>>();
+        // Despawned before being synced.
+        main_world.despawn(main_entities[10]);
+        entity_sync_system(&mut main_world, &mut render_world);
+
+        let map = render_world.resource::<EntitySyncMap>();
+        assert_eq!(map.len(), 999);
+        for &main_entity in &main_entities {
+            let Some(render_entity) = main_world.get::<RenderEntity>(main_entity) else {
+                assert_eq!(main_entity, main_entities[10]);
+                continue;
+            };
+            assert_eq!(
+                render_world.get::<MainEntity>(render_entity.id()),
+                Some(&MainEntity(main_entity))
+            );
+            assert_eq!(
+                map.get_render_entity(MainEntity(main_entity)),
+                Some(render_entity.id())
+            );
+        }
+    }
+
+    #[test]
+    fn parallel_and_serial_sync_match() {
+        let sync = |parallel: bool| {
+            let (mut main_world, mut render_world) = sync_worlds();
+            for i in 0..5000 {
+                if i % 7 == 0 {
+                    main_world.spawn((SyncToRenderWorld, NoRenderWorldSync));
+                } else {
+                    main_world.spawn(SyncToRenderWorld);
+                }
+            }
+            sync_entities(&mut main_world, &mut render_world, parallel);
+
+            let mut query = render_world.query::<(Entity, &MainEntity)>();
+            let mut entities = query
+                .iter(&render_world)
+                .map(|(render_entity, main_entity)| (render_entity, main_entity.id()))
+                .collect::<Vec<_>>();
+            entities.sort_unstable();
+            let mut query = main_world.query::<(Entity, &RenderEntity)>();
+            let mut main_entities = query
+                .iter(&main_world)
+                .map(|(main_entity, render_entity)| (render_entity.id(), main_entity))
+                .collect::<Vec<_>>();
+            main_entities.sort_unstable();
+            assert_eq!(entities, main_entities);
+            entities
+        };
+
+        let serial = sync(false);
+        assert_eq!(serial.len(), 5000 - 5000_usize.div_ceil(7));
+        assert_eq!(sync(true), serial);
+    }
+
+    #[test]
+    fn sync_world() {
+        let (mut main_world, mut render_world) = sync_worlds();
 
         // spawn some empty entities for test
         for _ in 0..99 {
@@ -606,4 +1091,77 @@ mod tests {
         // Only one synchronized entity
         assert!(q.iter(&render_world).count() == 0);
     }
+
+    #[test]
+    fn entity_sync_map_follows_despawns() {
+        let (mut main_world, mut render_world) = sync_worlds();
+
+        let main_entity = main_world.spawn(SyncToRenderWorld).id();
+        entity_sync_system(&mut main_world, &mut render_world);
+        let render_entity = main_world.get::<RenderEntity>(main_entity).unwrap().id();
+
+        let map = render_world.resource::<EntitySyncMap>();
+        assert_eq!(
+            map.get_render_entity(MainEntity(main_entity)),
+            Some(render_entity)
+        );
+        assert_eq!(
+            map.get_main_entity(render_entity),
+            Some(MainEntity(main_entity))
+        );
+
+        // Despawning the main entity removes the mapping.
+        main_world.despawn(main_entity);
+        entity_sync_system(&mut main_world, &mut render_world);
+        assert!(render_world.resource::<EntitySyncMap>().is_empty());
+
+        // Render entities despawned in the render world are removed as well.
+        let main_entity = main_world.spawn(SyncToRenderWorld).id();
+        entity_sync_system(&mut main_world, &mut render_world);
+        let render_entity = main_world.get::<RenderEntity>(main_entity).unwrap().id();
+        assert_eq!(render_world.resource::<EntitySyncMap>().len(), 1);
+        render_world.despawn(render_entity);
+        let map = render_world.resource::<EntitySyncMap>();
+        assert_eq!(map.get_render_entity(MainEntity(main_entity)), None);
+        assert_eq!(map.get_main_entity(render_entity), None);
+    }
+
+    #[derive(Resource, Default)]
+    struct Despawned(Vec<MainEntity>);
+
+    #[test]
+    fn opt_out_and_despawn_hook() {
+        let (mut main_world, mut render_world) = sync_worlds();
+        render_world.init_resource::<Despawned>();
+        render_world.add_observer(
+            |despawned: On<RenderEntityDespawned>,
+             main_entities: Query<&MainEntity>,
+             mut despawned_entities: ResMut<Despawned>| {
+                // The render entity still exists when the event is triggered.
+                assert!(main_entities.contains(despawned.entity));
+                despawned_entities.0.push(despawned.main_entity);
+            },
+        );
+
+        let synced = main_world.spawn(SyncToRenderWorld).id();
+        let opted_out = main_world
+            .spawn((SyncToRenderWorld, NoRenderWorldSync))
+            .id();
+        entity_sync_system(&mut main_world, &mut render_world);
+
+        assert!(main_world.get::<RenderEntity>(synced).is_some());
+        assert!(main_world.get::<RenderEntity>(opted_out).is_none());
+        let mut q = render_world.query::<&MainEntity>();
+        assert_eq!(q.iter(&render_world).count(), 1);
+
+        main_world.despawn(synced);
+        main_world.despawn(opted_out);
+        entity_sync_system(&mut main_world, &mut render_world);
+
+        assert_eq!(q.iter(&render_world).count(), 0);
+        assert_eq!(
+            render_world.resource::<Despawned>().0,
+            [MainEntity::from(synced)]
+        );
+    }
 }
@@ -81,7 +81,7 @@ impl RenderAsset for GpuImage {
                     image.texture_descriptor.format.block_dimensions();
 
                 // queue copy
-                render_queue.write_texture(
+                render_queue.write_texture_counted(
                     prev.texture.as_image_copy(),
                     data,
                     TexelCopyBufferLayout {
@@ -30,17 +30,24 @@ impl Plugin for TexturePlugin {
         app.add_plugins((
             RenderAssetPlugin::<GpuImage>::default(),
             ExtractResourcePlugin::<ManualTextureViews>::default(),
+            ExtractResourcePlugin::<TextureCacheSettings>::default(),
         ))
-        .init_resource::<ManualTextureViews>();
+        .init_resource::<ManualTextureViews>()
+        .init_resource::<TextureCacheSettings>();
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<ManualTextureViews>()
-                .init_gpu_resource::<TextureCache>()
+                .init_resource::<TextureCacheSettings>()
+                .init_resource::<TextureCache>()
+                .register_reclaimable_gpu_resource::<TextureCache>()
                 .allow_ambiguous_resource::<TextureCache>()
                 .add_systems(
                     Render,
                     update_texture_cache_system.in_set(RenderSystems::Cleanup),
                 );
+            render_app
+                .world_mut()
+                .add_observer(texture_cache::clear_texture_cache);
         }
     }
 
@@ -1,8 +1,14 @@
 use crate::{
+    error_handler::{ReclaimableGpuResource, RenderDeviceRecreated},
+    extract_resource::ExtractResource,
     render_resource::{Texture, TextureView},
     renderer::RenderDevice,
 };
-use bevy_ecs::{prelude::ResMut, resource::Resource};
+use bevy_ecs::{
+    observer::On,
+    prelude::{Res, ResMut},
+    resource::Resource,
+};
 use bevy_platform::collections::{HashMap, hash_map::Entry};
 use wgpu::{TextureDescriptor, TextureViewDescriptor};
 
@@ -27,11 +33,38 @@ pub struct CachedTexture {
 
 /// This resource caches textures that are created repeatedly in the rendering process and
 /// are only required for one frame.
+///
+/// The cache is emptied when [`RenderDeviceRecreated`] is triggered, so textures of a lost device
+/// are never reused. It is configured with [`TextureCacheSettings`].
 #[derive(Resource, Default)]
 pub struct TextureCache {
     textures: HashMap<TextureDescriptor<'static>, Vec<CachedTextureMeta>>,
 }
 
+/// Configures the [`TextureCache`].
+///
+/// Insert it in the main world, it is extracted to the render world every frame.
+#[derive(Resource, ExtractResource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextureCacheSettings {
+    /// The number of frames a texture stays in the cache without being used, before it's dropped.
+    ///
+    /// Defaults to [`TextureCacheSettings::DEFAULT_MAX_UNUSED_FRAMES`].
+    pub max_unused_frames: usize,
+}
+
+impl TextureCacheSettings {
+    /// The default of [`TextureCacheSettings::max_unused_frames`].
+    pub const DEFAULT_MAX_UNUSED_FRAMES: usize = 3;
+}
+
+impl Default for TextureCacheSettings {
+    fn default() -> Self {
+        Self {
+            max_unused_frames: Self::DEFAULT_MAX_UNUSED_FRAMES,
+        }
+    }
+}
+
 impl TextureCache {
     /// Retrieves a texture that matches the `descriptor`. If no matching one is found a new
     /// [`CachedTexture`] is created.
@@ -88,21 +121,44 @@ impl TextureCache {
         self.textures.is_empty()
     }
 
-    /// Updates the cache and only retains recently used textures.
-    pub fn update(&mut self) {
+    /// Updates the cache and only retains the textures used in the last
+    /// [`TextureCacheSettings::max_unused_frames`] frames.
+    pub fn update(&mut self, settings: &TextureCacheSettings) {
+        let max_unused_frames = settings.max_unused_frames;
         self.textures.retain(|_, textures| {
             for texture in textures.iter_mut() {
                 texture.frames_since_last_use += 1;
                 texture.taken = false;
             }
 
-            textures.retain(|texture| texture.frames_since_last_use < 3);
+            textures.retain(|texture| texture.frames_since_last_use < max_unused_frames);
             !textures.is_empty()
         });
     }
 }
 
+impl ReclaimableGpuResource for TextureCache {
+    fn reclaim(&mut self) {
+        // Taken textures are still referenced by their users, so only unused ones are freed.
+        self.textures.retain(|_, textures| {
+            textures.retain(|texture| texture.taken);
+            !textures.is_empty()
+        });
+    }
+}
+
+/// Drops the textures of a lost device when [`RenderDeviceRecreated`] is triggered.
+pub(crate) fn clear_texture_cache(
+    _: On<RenderDeviceRecreated>,
+    mut texture_cache: ResMut<TextureCache>,
+) {
+    texture_cache.textures.clear();
+}
+
 /// Updates the [`TextureCache`] to only retains recently used textures.
-pub fn update_texture_cache_system(mut texture_cache: ResMut<TextureCache>) {
-    texture_cache.update();
+pub fn update_texture_cache_system(
+    mut texture_cache: ResMut<TextureCache>,
+    settings: Res<TextureCacheSettings>,
+) {
+    texture_cache.update(&settings);
 }
@@ -18,7 +18,7 @@ use crate::{
     render_asset::RenderAssets,
     render_phase::ViewRangefinder3d,
     render_resource::{DynamicUniformBuffer, ShaderType, Texture, TextureView},
-    renderer::{RenderDevice, RenderQueue},
+    renderer::{RenderAdapter, RenderDevice, RenderQueue},
     sync_world::MainEntity,
     texture::{
         CachedTexture, ColorAttachment, DepthAttachment, GpuImage, ManualTextureViews,
@@ -31,6 +31,7 @@ use bevy_color::{LinearRgba, Oklaba, Srgba};
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::prelude::*;
 use bevy_image::ToExtents;
+use bevy_log::warn_once;
 use bevy_math::{Mat3, Mat4, UVec4, Vec2, Vec3, Vec4, Vec4Swizzles, mat3, vec2, vec3};
 use bevy_platform::collections::{HashMap, hash_map::Entry};
 use bevy_reflect::{Reflect, std_traits::ReflectDefault};
@@ -43,7 +44,8 @@ use core::{
 };
 use wgpu::{
     BufferUsages, Color as WgpuColor, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
-    StoreOp, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    StoreOp, TextureDescriptor, TextureDimension, TextureFormat, TextureFormatFeatureFlags,
+    TextureUsages,
 };
 
 /// The matrix that converts from the RGB to the LMS color space.
@@ -183,6 +185,7 @@ impl Plugin for ViewPlugin {
             render_app.add_systems(
                 Render,
                 (
+                    clamp_msaa_to_supported_samples.in_set(RenderSystems::CreateViews),
                     // `TextureView`s need to be dropped before reconfiguring window surfaces.
                     clear_view_attachments
                         .in_set(RenderSystems::PrepareViews)
@@ -260,6 +263,38 @@ impl Msaa {
             _ => panic!("Unsupported MSAA sample count: {samples}"),
         }
     }
+
+    /// Returns the highest sample count up to this one that is supported by a texture format
+    /// with the given features, or [`Msaa::Off`] if multisampling isn't supported at all.
+    pub fn clamp_to_supported(self, features: TextureFormatFeatureFlags) -> Self {
+        [Msaa::Sample8, Msaa::Sample4, Msaa::Sample2]
+            .into_iter()
+            .find(|msaa| *msaa <= self && features.sample_count_supported(msaa.samples()))
+            .unwrap_or(Msaa::Off)
+    }
+}
+
+/// Lowers the [`Msaa`] of views to a sample count their target format supports on the adapter,
+/// before pipelines are specialized and the main textures are created.
+pub fn clamp_msaa_to_supported_samples(
+    adapter: Res<RenderAdapter>,
+    mut views: Query<(&ExtractedView, &mut Msaa)>,
+) {
+    for (view, mut msaa) in &mut views {
+        let features = adapter
+            .get_texture_format_features(view.target_format)
+            .flags;
+        let supported = msaa.clamp_to_supported(features);
+        if supported != *msaa {
+            warn_once!(
+                "{:?} is not supported for {:?} views on this adapter, using {:?} instead",
+                *msaa,
+                view.target_format,
+                supported
+            );
+            *msaa = supported;
+        }
+    }
 }
 
 /// An identifier for a view that is stable across frames.
@@ -1278,3 +1313,120 @@ pub fn prepare_view_targets(
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::Handle;
+    use bevy_camera::{CameraOutputMode, MsaaWriteback, RenderTarget};
+    use bevy_ecs::{schedule::ScheduleLabel, system::RunSystemOnce};
+    use bevy_math::UVec2;
+
+    use super::*;
+    use crate::{renderer::noop_render_resources, settings::RenderResources};
+
+    #[test]
+    fn extracted_views_are_prepared() {
+        let RenderResources(device, queue, ..) = noop_render_resources(wgpu::Features::empty());
+        let mut world = World::new();
+        world.insert_resource(device.clone());
+        world.insert_resource(queue);
+        world.init_resource::<ViewUniforms>();
+        world.init_resource::<FrameCount>();
+        world.init_resource::<ClearColor>();
+        world.init_resource::<TextureCache>();
+        world.init_resource::<ViewTargetAttachments>();
+
+        let size = UVec2::new(64, 32);
+        let target = RenderTarget::Image(Handle::default().into())
+            .normalize(None)
+            .unwrap();
+        let out_texture = device.create_texture(&TextureDescriptor {
+            label: None,
+            size: size.to_extents(),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        world.resource_mut::<ViewTargetAttachments>().insert(
+            target.clone(),
+            OutputColorAttachment::new(
+                out_texture.create_view(&Default::default()),
+                TextureFormat::Rgba8UnormSrgb,
+            ),
+        );
+
+        let mut spawn_view = |target_format: TextureFormat| {
+            let hdr = target_format == TextureFormat::Rgba16Float;
+            world
+                .spawn((
+                    ExtractedView {
+                        retained_view_entity: RetainedViewEntity::new(
+                            MainEntity::from(Entity::PLACEHOLDER),
+                            None,
+                            0,
+                        ),
+                        clip_from_view: Mat4::IDENTITY,
+                        world_from_view: GlobalTransform::IDENTITY,
+                        clip_from_world: None,
+                        target_format,
+                        viewport: UVec4::new(0, 0, size.x, size.y),
+                        color_grading: ColorGrading::default(),
+                        invert_culling: false,
+                    },
+                    ExtractedCamera {
+                        target: Some(target.clone()),
+                        physical_viewport_size: Some(size),
+                        physical_target_size: Some(size),
+                        viewport: None,
+                        schedule: Render.intern(),
+                        order: 0,
+                        output_mode: CameraOutputMode::default(),
+                        msaa_writeback: MsaaWriteback::default(),
+                        clear_color: ClearColorConfig::Default,
+                        sorted_camera_index_for_target: 0,
+                        exposure: 1.0,
+                        hdr,
+                        compositing_space: None,
+                    },
+                    CameraMainTextureUsages::default(),
+                    Msaa::Off,
+                ))
+                .id()
+        };
+        let hdr = spawn_view(TextureFormat::Rgba16Float);
+        let ldr = spawn_view(TextureFormat::Rgba8Unorm);
+
+        world.run_system_once(prepare_view_uniforms).unwrap();
+        world.run_system_once(prepare_view_targets).unwrap();
+
+        // Every view gets its own slot, aligned for dynamic offsets.
+        assert!(world.resource::<ViewUniforms>().uniforms.buffer().is_some());
+        let stride = (ViewUniform::min_size().get() as u32)
+            .next_multiple_of(device.limits().min_uniform_buffer_offset_alignment);
+        let mut offsets =
+            [hdr, ldr].map(|view| world.get::<ViewUniformOffset>(view).unwrap().offset);
+        offsets.sort();
+        assert_eq!(offsets, [0, stride]);
+
+        for (view, format) in [
+            (hdr, TextureFormat::Rgba16Float),
+            (ldr, TextureFormat::Rgba8Unorm),
+        ] {
+            let target = world.get::<ViewTarget>(view).unwrap();
+            assert_eq!(target.main_texture_format(), format);
+            assert_eq!(target.main_texture().format(), format);
+            assert_eq!(target.main_texture().size(), size.to_extents());
+
+            // Post processing ping-pongs between the two main textures.
+            let main_texture = target.main_texture().id();
+            let post_process = target.post_process_write();
+            assert_eq!(post_process.source_texture.id(), main_texture);
+            let destination = post_process.destination_texture.id();
+            assert_ne!(destination, main_texture);
+            assert_eq!(target.main_texture().id(), destination);
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use super::ExtractedWindows;
+use alloc::{collections::VecDeque, sync::Arc};
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_math::IVec2;
+use bevy_platform::time::Instant;
+use bevy_window::{Monitor, MonitorSelection, PresentMode, PrimaryMonitor, Window, WindowPosition};
+use core::time::Duration;
+use std::sync::Mutex;
+
+/// The number of present-to-present deltas kept per window by [`PresentStatistics`].
+pub const DEFAULT_PRESENT_STATISTICS_WINDOW: usize = 240;
+
+/// Timing information about the monitor a window is currently shown on.
+///
+/// This is refreshed every frame during extraction, so it follows the window when it is moved
+/// between displays. Not every windowing backend reports a refresh rate, in which case
+/// [`WindowDisplayInfo::refresh_rate_millihertz`] is `None`.
+///
+/// This is not a component: windows aren't synced to the render world as entities, they are
+/// extracted into [`ExtractedWindows`] instead, so the display info of a window is kept in
+/// [`ExtractedWindow::display_info`](super::ExtractedWindow::display_info).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WindowDisplayInfo {
+    /// The main world [`Monitor`] entity the window is on, if it could be determined.
+    pub monitor: Option<Entity>,
+    /// The refresh rate of that monitor in millihertz.
+    pub refresh_rate_millihertz: Option<u32>,
+}
+
+impl WindowDisplayInfo {
+    /// The refresh rate of the monitor in hertz.
+    pub fn refresh_rate(&self) -> Option<f64> {
+        self.refresh_rate_millihertz
+            .filter(|&millihertz| millihertz > 0)
+            .map(|millihertz| millihertz as f64 / 1000.0)
+    }
+
+    /// The time between two refreshes of the monitor.
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_rate()
+            .map(|refresh_rate| Duration::from_secs_f64(1.0 / refresh_rate))
+    }
+}
+
+/// Finds the monitor `window` is shown on.
+///
+/// Windows with an explicit position are matched against the monitor containing their center,
+/// otherwise the monitor selection used to place the window is respected, falling back to the
+/// [`PrimaryMonitor`].
+pub(crate) fn window_display_info<'a>(
+    window: &Window,
+    monitors: impl Iterator<Item = (Entity, &'a Monitor, bool)> + Clone,
+) -> WindowDisplayInfo {
+    let primary = || monitors.clone().find(|(_, _, primary)| *primary);
+
+    let monitor = match window.position {
+        WindowPosition::At(position) => {
+            let center = position
+                + IVec2::new(
+                    window.resolution.physical_width() as i32 / 2,
+                    window.resolution.physical_height() as i32 / 2,
+                );
+            monitors
+                .clone()
+                .find(|(_, monitor, _)| {
+                    let min = monitor.physical_position;
+                    let max = min
+                        + IVec2::new(
+                            monitor.physical_width as i32,
+                            monitor.physical_height as i32,
+                        );
+                    center.cmpge(min).all() && center.cmplt(max).all()
+                })
+                .or_else(primary)
+        }
+        WindowPosition::Centered(MonitorSelection::Entity(entity)) => monitors
+            .clone()
+            .find(|(monitor, _, _)| *monitor == entity)
+            .or_else(primary),
+        WindowPosition::Centered(MonitorSelection::Index(index)) => {
+            monitors.clone().nth(index).or_else(primary)
+        }
+        _ => primary(),
+    };
+
+    match monitor {
+        Some((entity, monitor, _)) => WindowDisplayInfo {
+            monitor: Some(entity),
+            refresh_rate_millihertz: monitor.refresh_rate_millihertz,
+        },
+        None => WindowDisplayInfo::default(),
+    }
+}
+
+/// Present-to-present timings of a single window over a sliding window of frames.
+#[derive(Clone, Debug)]
+pub struct PresentTimings {
+    last_present: Option<Instant>,
+    deltas: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl Default for PresentTimings {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRESENT_STATISTICS_WINDOW)
+    }
+}
+
+impl PresentTimings {
+    /// Creates empty timings that keep at most `capacity` deltas.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            last_present: None,
+            deltas: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Records a present that happened at `now`.
+    pub fn record(&mut self, now: Instant) {
+        if let Some(last_present) = self.last_present.replace(now) {
+            if self.deltas.len() == self.capacity {
+                self.deltas.pop_front();
+            }
+            self.deltas
+                .push_back(now.saturating_duration_since(last_present));
+        }
+    }
+
+    /// Forgets all recorded presents, e.g. after the window moved to another display.
+    pub fn clear(&mut self) {
+        self.last_present = None;
+        self.deltas.clear();
+    }
+
+    /// The recorded present-to-present deltas, oldest first.
+    pub fn deltas(&self) -> impl ExactSizeIterator<Item = Duration> + '_ {
+        self.deltas.iter().copied()
+    }
+
+    /// The most recent present-to-present delta.
+    pub fn latest(&self) -> Option<Duration> {
+        self.deltas.back().copied()
+    }
+
+    /// Returns the `percentile` (in `0.0..=100.0`) of the recorded deltas, using nearest-rank.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.deltas.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<_> = self.deltas.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1).min(sorted.len() - 1)])
+    }
+
+    /// The median present-to-present delta.
+    pub fn p50(&self) -> Option<Duration> {
+        self.percentile(50.0)
+    }
+
+    /// The 95th percentile present-to-present delta.
+    pub fn p95(&self) -> Option<Duration> {
+        self.percentile(95.0)
+    }
+
+    /// The 99th percentile present-to-present delta.
+    pub fn p99(&self) -> Option<Duration> {
+        self.percentile(99.0)
+    }
+}
+
+/// Present-to-present timings of every window, measured in the render world and mirrored to
+/// the main world at the start of every frame.
+///
+/// This measures when frames were handed to the presentation engine, which is the closest
+/// approximation of what the user sees without platform specific present statistics.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PresentStatistics {
+    pub windows: EntityHashMap<PresentTimings>,
+    /// The present modes the window surfaces are configured with, see
+    /// [`PresentStatistics::present_mode`].
+    pub present_modes: EntityHashMap<PresentMode>,
+}
+
+impl PresentStatistics {
+    /// Returns the timings of the given window.
+    pub fn get(&self, window: Entity) -> Option<&PresentTimings> {
+        self.windows.get(&window)
+    }
+
+    /// Records a present of `window` at `now`.
+    pub fn record(&mut self, window: Entity, now: Instant) {
+        self.windows.entry(window).or_default().record(now);
+    }
+
+    /// The present mode the surface of `window` is configured with.
+    ///
+    /// This is the mode actually in use after falling back from modes the surface doesn't
+    /// support, so it never is [`PresentMode::AutoVsync`] or [`PresentMode::AutoNoVsync`].
+    pub fn present_mode(&self, window: Entity) -> Option<PresentMode> {
+        self.present_modes.get(&window).copied()
+    }
+
+    /// Whether `window` presents in sync with the vertical blank of its display, i.e. its
+    /// [`present_mode`](Self::present_mode) is [`PresentMode::Fifo`] or
+    /// [`PresentMode::FifoRelaxed`].
+    pub fn is_vsync(&self, window: Entity) -> bool {
+        matches!(
+            self.present_mode(window),
+            Some(PresentMode::Fifo | PresentMode::FifoRelaxed)
+        )
+    }
+}
+
+/// Shares the latest [`PresentStatistics`] of the render world with the main world.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct PresentStatisticsMutex(Arc<Mutex<Option<PresentStatistics>>>);
+
+/// Drops timings of closed windows and resets them for windows that moved to another display.
+pub(crate) fn update_present_statistics(
+    windows: Res<ExtractedWindows>,
+    mut statistics: ResMut<PresentStatistics>,
+) {
+    statistics
+        .windows
+        .retain(|window, _| windows.contains_key(window));
+    statistics
+        .present_modes
+        .retain(|window, _| windows.contains_key(window));
+    for window in windows.values() {
+        if window.display_info_changed
+            && let Some(timings) = statistics.windows.get_mut(&window.entity)
+        {
+            timings.clear();
+        }
+    }
+}
+
+/// Sends the render world [`PresentStatistics`] to the main world.
+pub(crate) fn send_present_statistics(
+    statistics: Res<PresentStatistics>,
+    mutex: Res<PresentStatisticsMutex>,
+) {
+    *mutex.0.lock().unwrap() = Some(statistics.clone());
+}
+
+/// Receives the [`PresentStatistics`] sent by the render world.
+pub(crate) fn sync_present_statistics(
+    mutex: Res<PresentStatisticsMutex>,
+    mut statistics: ResMut<PresentStatistics>,
+) {
+    if let Some(received) = mutex.0.lock().ok().and_then(|mut v| v.take()) {
+        *statistics = received;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings_from_deltas(deltas_ms: &[u64]) -> PresentTimings {
+        let mut timings = PresentTimings::new(DEFAULT_PRESENT_STATISTICS_WINDOW);
+        let mut now = Instant::now();
+        timings.record(now);
+        for delta in deltas_ms {
+            now += Duration::from_millis(*delta);
+            timings.record(now);
+        }
+        timings
+    }
+
+    #[test]
+    fn present_timings_percentiles() {
+        // 90 frames at 16ms, 9 at 33ms and one 100ms hitch.
+        let mut deltas = vec![16; 90];
+        deltas.extend([33; 9]);
+        deltas.push(100);
+        let timings = timings_from_deltas(&deltas);
+
+        assert_eq!(timings.deltas().len(), 100);
+        assert_eq!(timings.p50(), Some(Duration::from_millis(16)));
+        assert_eq!(timings.p95(), Some(Duration::from_millis(33)));
+        assert_eq!(timings.p99(), Some(Duration::from_millis(33)));
+        assert_eq!(timings.percentile(100.0), Some(Duration::from_millis(100)));
+        assert_eq!(timings.latest(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn present_timings_sliding_window() {
+        let mut timings = PresentTimings::new(4);
+        let mut now = Instant::now();
+        timings.record(now);
+        assert_eq!(timings.p50(), None);
+
+        for delta in [10, 20, 30, 40, 50, 60] {
+            now += Duration::from_millis(delta);
+            timings.record(now);
+        }
+        let deltas: Vec<_> = timings.deltas().map(|d| d.as_millis()).collect();
+        assert_eq!(deltas, [30, 40, 50, 60]);
+
+        timings.clear();
+        assert_eq!(timings.deltas().len(), 0);
+        timings.record(now + Duration::from_millis(5));
+        assert_eq!(timings.latest(), None);
+    }
+
+    #[test]
+    fn display_info_refresh_interval() {
+        let info = WindowDisplayInfo {
+            monitor: None,
+            refresh_rate_millihertz: Some(60_000),
+        };
+        assert_eq!(info.refresh_rate(), Some(60.0));
+        assert_eq!(
+            info.refresh_interval(),
+            Some(Duration::from_secs_f64(1.0 / 60.0))
+        );
+        assert_eq!(WindowDisplayInfo::default().refresh_interval(), None);
+    }
+}
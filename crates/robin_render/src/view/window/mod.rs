@@ -2,40 +2,77 @@ use crate::camera::extract_cameras;
 use crate::renderer::WgpuWrapper;
 use crate::{
     Extract, ExtractSchedule, GpuResourceAppExt, Render, RenderApp, RenderSystems,
+    error_handler::{ErrorType, LastWgpuSettings, RenderDeviceRecreated, RenderError},
     render_resource::{SurfaceTexture, TextureView},
     renderer::{RenderAdapter, RenderDevice, RenderInstance},
 };
-use bevy_app::{App, Plugin};
+use bevy_app::{App, Plugin, PreUpdate};
 use bevy_ecs::entity::EntityHashSet;
 use bevy_ecs::{entity::EntityHashMap, prelude::*};
-use bevy_log::{debug, info, warn};
+use bevy_log::{debug, warn};
 use bevy_utils::default;
 use bevy_window::{
-    CompositeAlphaMode, PresentMode, PrimaryWindow, RawHandleWrapper, Window, WindowClosing,
+    CompositeAlphaMode, Monitor, PresentMode, PrimaryMonitor, PrimaryWindow, RawHandleWrapper,
+    Window, WindowClosing,
 };
 use core::{
     num::NonZero,
     ops::{Deref, DerefMut},
 };
+use thiserror::Error;
 use wgpu::{
     SurfaceConfiguration, SurfaceTargetUnsafe, TextureFormat, TextureUsages, TextureViewDescriptor,
 };
 
+pub mod display;
 pub mod screenshot;
 
+use display::{
+    PresentStatistics, PresentStatisticsMutex, WindowDisplayInfo, send_present_statistics,
+    sync_present_statistics, update_present_statistics,
+};
 use screenshot::ScreenshotPlugin;
 
 pub struct WindowRenderPlugin;
 
+/// The formats to configure the surface of a [`Window`] with, in order of preference.
+///
+/// Without it, or if it is empty, an sRGB format is chosen when available. Requested formats are
+/// used as they are: a non-sRGB format like [`TextureFormat::Bgra8Unorm`] turns off the sRGB
+/// encoding of the output, e.g. for custom color management, and
+/// [`TextureFormat::Rgba16Float`] can be requested for HDR output. If none of the formats is
+/// supported, an error is logged and the default format is used instead.
+///
+/// The format is chosen again when the component changes, and when the surface is recreated
+/// after recovering from a lost device. The chosen format is
+/// [`ExtractedWindow::swap_chain_texture_format`] in the render world.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SurfaceTextureFormat(pub Vec<TextureFormat>);
+
 impl Plugin for WindowRenderPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(ScreenshotPlugin);
 
+        let present_statistics_mutex = PresentStatisticsMutex::default();
+        app.init_resource::<PresentStatistics>()
+            .insert_resource(present_statistics_mutex.clone())
+            .add_systems(PreUpdate, sync_present_statistics);
+
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_gpu_resource::<ExtractedWindows>()
-                .init_gpu_resource::<WindowSurfaces>()
+                .init_resource::<WindowSurfaces>()
+                .init_resource::<SurfaceAcquireRetries>()
+                .init_resource::<PresentStatistics>()
+                .insert_resource(present_statistics_mutex)
                 .add_systems(ExtractSchedule, extract_windows.before(extract_cameras))
+                .add_systems(
+                    Render,
+                    (
+                        update_present_statistics.in_set(RenderSystems::PrepareViews),
+                        send_present_statistics.in_set(RenderSystems::Cleanup),
+                    ),
+                )
                 .add_systems(
                     Render,
                     create_surfaces
@@ -43,6 +80,7 @@ impl Plugin for WindowRenderPlugin {
                         .before(prepare_windows),
                 )
                 .add_systems(Render, prepare_windows.in_set(RenderSystems::PrepareViews));
+            render_app.world_mut().add_observer(clear_window_surfaces);
         }
     }
 }
@@ -65,8 +103,20 @@ pub struct ExtractedWindow {
     /// so that in shaders we are always in linear space.
     pub swap_chain_texture_view_format: Option<TextureFormat>,
     pub size_changed: bool,
+    /// Whether the present mode or the desired maximum frame latency changed this frame, in which
+    /// case the surface is reconfigured.
     pub present_mode_changed: bool,
+    /// The preferred surface formats of the window, see [`SurfaceTextureFormat`]. The format the
+    /// surface was configured with is [`ExtractedWindow::swap_chain_texture_format`].
+    pub surface_formats: Vec<TextureFormat>,
+    /// Whether [`ExtractedWindow::surface_formats`] changed this frame, in which case the surface
+    /// is reconfigured.
+    pub surface_format_changed: bool,
     pub alpha_mode: CompositeAlphaMode,
+    /// The monitor the window is currently shown on.
+    pub display_info: WindowDisplayInfo,
+    /// Whether the window moved to a monitor with different timings this frame.
+    pub display_info_changed: bool,
     /// Whether this window needs an initial buffer commit.
     ///
     /// On Wayland, windows must present at least once before they are shown.
@@ -125,20 +175,42 @@ impl DerefMut for ExtractedWindows {
 fn extract_windows(
     mut extracted_windows: ResMut<ExtractedWindows>,
     mut closing: Extract<MessageReader<WindowClosing>>,
-    windows: Extract<Query<(Entity, &Window, &RawHandleWrapper, Option<&PrimaryWindow>)>>,
+    windows: Extract<
+        Query<(
+            Entity,
+            &Window,
+            &RawHandleWrapper,
+            Option<&PrimaryWindow>,
+            Option<&SurfaceTextureFormat>,
+        )>,
+    >,
     mut removed: Extract<RemovedComponents<RawHandleWrapper>>,
+    monitors: Extract<Query<(Entity, &Monitor, Has<PrimaryMonitor>)>>,
+    settings: Extract<Option<Res<LastWgpuSettings>>>,
     mut window_surfaces: ResMut<WindowSurfaces>,
 ) {
-    for (entity, window, handle, primary) in windows.iter() {
+    // The adapter of a headless renderer may not be able to present to the windows, so they
+    // never get a surface and cameras rendering to them are skipped.
+    if let Some(settings) = &*settings
+        && settings.0.headless
+    {
+        return;
+    }
+
+    let monitors: Vec<_> = monitors.iter().collect();
+    for (entity, window, handle, primary, surface_format) in windows.iter() {
         if primary.is_some() {
             extracted_windows.primary = Some(entity);
         }
 
+        let display_info = display::window_display_info(window, monitors.iter().copied());
+
         let (new_width, new_height) = (
             window.resolution.physical_width().max(1),
             window.resolution.physical_height().max(1),
         );
 
+        let surface_formats = surface_format.map_or(&[][..], |format| &format.0[..]);
         let extracted_window = extracted_windows.entry(entity).or_insert(ExtractedWindow {
             entity,
             handle: handle.clone(),
@@ -152,7 +224,11 @@ fn extract_windows(
             swap_chain_texture_format: None,
             swap_chain_texture_view_format: None,
             present_mode_changed: false,
+            surface_formats: surface_formats.to_vec(),
+            surface_format_changed: false,
             alpha_mode: window.composite_alpha_mode,
+            display_info,
+            display_info_changed: false,
             needs_initial_present: true,
         });
 
@@ -165,8 +241,10 @@ fn extract_windows(
         }
         extracted_window.size_changed = new_width != extracted_window.physical_width
             || new_height != extracted_window.physical_height;
-        extracted_window.present_mode_changed =
-            window.present_mode != extracted_window.present_mode;
+        extracted_window.present_mode_changed = window.present_mode
+            != extracted_window.present_mode
+            || window.desired_maximum_frame_latency
+                != extracted_window.desired_maximum_frame_latency;
 
         if extracted_window.size_changed {
             debug!(
@@ -180,12 +258,32 @@ fn extract_windows(
             extracted_window.physical_height = new_height;
         }
 
+        extracted_window.surface_format_changed =
+            surface_formats != &extracted_window.surface_formats[..];
+        if extracted_window.surface_format_changed {
+            debug!(
+                "Window surface formats changed from {:?} to {:?}",
+                extracted_window.surface_formats, surface_formats
+            );
+            extracted_window.surface_formats = surface_formats.to_vec();
+        }
+
+        extracted_window.display_info_changed = display_info != extracted_window.display_info;
+        if extracted_window.display_info_changed {
+            debug!(
+                "Window moved from {:?} to {:?}",
+                extracted_window.display_info, display_info
+            );
+            extracted_window.display_info = display_info;
+        }
+
         if extracted_window.present_mode_changed {
             debug!(
                 "Window Present Mode changed from {:?} to {:?}",
                 extracted_window.present_mode, window.present_mode
             );
             extracted_window.present_mode = window.present_mode;
+            extracted_window.desired_maximum_frame_latency = window.desired_maximum_frame_latency;
         }
     }
 
@@ -199,13 +297,36 @@ fn extract_windows(
     }
 }
 
-struct SurfaceData {
+/// The surface of a window, see [`WindowSurfaces`].
+pub struct SurfaceData {
     // TODO: what lifetime should this be?
     surface: WgpuWrapper<wgpu::Surface<'static>>,
     configuration: SurfaceConfiguration,
     texture_view_format: Option<TextureFormat>,
 }
 
+impl SurfaceData {
+    pub fn surface(&self) -> &wgpu::Surface<'static> {
+        &self.surface
+    }
+
+    /// The configuration the surface was last configured with.
+    pub fn configuration(&self) -> &SurfaceConfiguration {
+        &self.configuration
+    }
+
+    /// The format of the views of the swap chain textures, if it differs from
+    /// [`SurfaceConfiguration::format`].
+    pub fn texture_view_format(&self) -> Option<TextureFormat> {
+        self.texture_view_format
+    }
+}
+
+/// The surfaces of all extracted windows, by window entity.
+///
+/// Surfaces are created with the [`RenderInstance`] when a window is first extracted,
+/// reconfigured when it is resized or its present mode changes, and removed when it is closed.
+/// They are created again after recovering from a lost device.
 #[derive(Resource, Default)]
 pub struct WindowSurfaces {
     surfaces: EntityHashMap<SurfaceData>,
@@ -214,12 +335,118 @@ pub struct WindowSurfaces {
 }
 
 impl WindowSurfaces {
+    /// The surface of `window`, if it was created.
+    pub fn get(&self, window: Entity) -> Option<&SurfaceData> {
+        self.surfaces.get(&window)
+    }
+
+    /// Iterates over the windows and their surfaces.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &SurfaceData)> {
+        self.surfaces.iter().map(|(window, data)| (*window, data))
+    }
+
+    /// The number of surfaces.
+    pub fn len(&self) -> usize {
+        self.surfaces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.surfaces.is_empty()
+    }
+
     fn remove(&mut self, window: &Entity) {
         self.surfaces.remove(window);
         self.configured_windows.remove(window);
     }
 }
 
+/// Drops the surfaces configured for a lost device when [`RenderDeviceRecreated`] is triggered,
+/// so they are created again with the new [`RenderInstance`] and device.
+pub(crate) fn clear_window_surfaces(
+    _: On<RenderDeviceRecreated>,
+    mut window_surfaces: ResMut<WindowSurfaces>,
+) {
+    window_surfaces.surfaces.clear();
+    window_surfaces.configured_windows.clear();
+}
+
+/// Bounds how many frames [`prepare_windows`] skips while a window's swap chain texture can't be
+/// acquired.
+///
+/// Some compositors fail to hand out swap chain textures for a few frames, e.g. under memory
+/// pressure, and recover on their own. Each failure skips the frame for that window and
+/// reconfigures its surface. Once a window failed more than
+/// [`max_skipped_frames`](Self::max_skipped_frames) frames in a row, a [`RenderError`] is passed
+/// to the [`RenderErrorHandler`](crate::error_handler::RenderErrorHandler) so its policy can
+/// recover or stop rendering. A single success resets the count.
+///
+/// Errors the device reports while acquiring a texture, like running out of memory, aren't
+/// counted here. They reach the [`RenderErrorHandler`](crate::error_handler::RenderErrorHandler)
+/// through the [`DeviceErrorHandler`](crate::error_handler::DeviceErrorHandler) right away.
+#[derive(Resource)]
+pub struct SurfaceAcquireRetries {
+    pub max_skipped_frames: u32,
+    failures: EntityHashMap<u32>,
+    escalation: Option<RenderError>,
+}
+
+impl Default for SurfaceAcquireRetries {
+    fn default() -> Self {
+        Self {
+            max_skipped_frames: 5,
+            failures: default(),
+            escalation: None,
+        }
+    }
+}
+
+impl SurfaceAcquireRetries {
+    /// The number of consecutive frames `window` failed to acquire a swap chain texture.
+    pub fn consecutive_failures(&self, window: Entity) -> u32 {
+        self.failures.get(&window).copied().unwrap_or_default()
+    }
+
+    fn record_success(&mut self, window: Entity) {
+        self.failures.remove(&window);
+    }
+
+    /// Records the result of acquiring the swap chain texture of `window`, returning `true` if
+    /// the frame is skipped for it and its surface should be reconfigured.
+    fn record(&mut self, window: Entity, failure: Option<String>) -> bool {
+        match failure {
+            None => {
+                self.record_success(window);
+                false
+            }
+            Some(cause) => !self.record_failure(window, &cause),
+        }
+    }
+
+    /// Counts a failure of `window`, returning `true` if it was escalated.
+    fn record_failure(&mut self, window: Entity, cause: &str) -> bool {
+        let failures = self.failures.entry(window).or_default();
+        *failures += 1;
+        if *failures <= self.max_skipped_frames {
+            return false;
+        }
+
+        let failures = self.failures.remove(&window).unwrap_or_default();
+        self.escalation.get_or_insert(RenderError {
+            ty: ErrorType::Internal,
+            description: format!(
+                "Couldn't get a swap chain texture for window {window} for {failures} frames in a row. Cause: '{cause}'"
+            ),
+            source: None,
+        });
+        true
+    }
+
+    /// Takes the error of a window that exceeded [`max_skipped_frames`](Self::max_skipped_frames).
+    pub(crate) fn take_escalation(&mut self) -> Option<RenderError> {
+        self.escalation.take()
+    }
+}
+
 /// (re)configures window surfaces, and obtains a swapchain texture for rendering.
 ///
 /// NOTE: `get_current_texture` in `prepare_windows` can take a long time if the GPU workload is
@@ -244,10 +471,17 @@ impl WindowSurfaces {
 pub fn prepare_windows(
     mut windows: ResMut<ExtractedWindows>,
     mut window_surfaces: ResMut<WindowSurfaces>,
+    mut retries: ResMut<SurfaceAcquireRetries>,
     render_device: Res<RenderDevice>,
     sorted_cameras: Res<crate::camera::SortedCameras>,
     #[cfg(target_os = "linux")] render_instance: Res<RenderInstance>,
 ) {
+    retries
+        .failures
+        .retain(|window, _| windows.windows.contains_key(window));
+    let mut failed_windows = Vec::new();
+    let mut lost_windows = Vec::new();
+
     for window in windows.windows.values_mut() {
         // Skip acquiring a swap-chain texture for windows that no camera
         // targets. This avoids a wasted clear pass in
@@ -270,7 +504,11 @@ pub fn prepare_windows(
         };
 
         // We didn't present the previous frame, so we can keep using our existing swapchain texture.
-        if window.has_swapchain_texture() && !window.size_changed && !window.present_mode_changed {
+        if window.has_swapchain_texture()
+            && !window.size_changed
+            && !window.present_mode_changed
+            && !window.surface_format_changed
+        {
             continue;
         }
 
@@ -298,45 +536,136 @@ pub fn prepare_windows(
                 });
             })[0]
         };
+        #[cfg(not(target_os = "linux"))]
+        let may_erroneously_timeout = || false;
 
-        let surface = &surface_data.surface;
-        match surface.get_current_texture() {
-            wgpu::CurrentSurfaceTexture::Success(surface_texture)
-            | wgpu::CurrentSurfaceTexture::Suboptimal(surface_texture) => {
-                window.set_swapchain_texture(surface_texture);
-            }
-            #[cfg(target_os = "linux")]
-            wgpu::CurrentSurfaceTexture::Timeout if may_erroneously_timeout() => {
-                bevy_log::trace!(
-                    "Couldn't get swap chain texture. This is probably a quirk \
-                        of your Linux GPU driver, so it can be safely ignored."
-                );
+        let failure = match acquire_swap_chain_texture(
+            surface_data.surface(),
+            &surface_data.configuration,
+            &render_device,
+            may_erroneously_timeout,
+        ) {
+            Ok(surface_texture) => {
+                if let Some(surface_texture) = surface_texture {
+                    window.set_swapchain_texture(surface_texture);
+                }
+                None
             }
-            wgpu::CurrentSurfaceTexture::Outdated => {
-                render_device.configure_surface(surface, &surface_data.configuration);
-                let frame = match surface.get_current_texture() {
-                    wgpu::CurrentSurfaceTexture::Success(surface_texture)
-                    | wgpu::CurrentSurfaceTexture::Suboptimal(surface_texture) => surface_texture,
-                    variant => {
-                        // This is a common occurrence on X11 and Xwayland with NVIDIA drivers
-                        // when opening and resizing the window.
-                        warn!(
-                            "Couldn't get swap chain texture after configuring. Cause: '{variant:?}'"
-                        );
-                        continue;
-                    }
-                };
-                window.set_swapchain_texture(frame);
+            Err(status) => {
+                if matches!(status, wgpu::CurrentSurfaceTexture::Lost) {
+                    lost_windows.push(window.entity);
+                }
+                Some(format!("{status:?}"))
             }
-            wgpu::CurrentSurfaceTexture::Occluded => {}
-            other => {
-                bevy_log::error!("Couldn't get swap chain texture: {other:?}");
+        };
+        window.swap_chain_texture_format = Some(surface_data.configuration.format);
+
+        // Skip the frame for this window, and reconfigure its surface for the next one.
+        if retries.record(window.entity, failure) {
+            failed_windows.push(window.entity);
+        }
+    }
+
+    for window in failed_windows {
+        window_surfaces.configured_windows.remove(&window);
+    }
+    // `create_surfaces` creates and configures new surfaces for these next frame.
+    for window in lost_windows {
+        window_surfaces.remove(&window);
+    }
+}
+
+/// The swap chain of a window, a [`wgpu::Surface`] outside of tests.
+pub(crate) trait SwapChain {
+    /// See [`wgpu::Surface::get_current_texture`].
+    fn current_texture(&self) -> wgpu::CurrentSurfaceTexture;
+
+    /// See [`wgpu::Surface::configure`].
+    fn configure(&self, render_device: &RenderDevice, configuration: &SurfaceConfiguration);
+}
+
+impl SwapChain for wgpu::Surface<'_> {
+    fn current_texture(&self) -> wgpu::CurrentSurfaceTexture {
+        self.get_current_texture()
+    }
+
+    fn configure(&self, render_device: &RenderDevice, configuration: &SurfaceConfiguration) {
+        render_device.configure_surface(self, configuration);
+    }
+}
+
+/// Gets the next texture of `swap_chain`, reconfiguring it once if it is outdated.
+///
+/// Returns `None` if there is nothing to render to this frame, e.g. while the window is occluded
+/// or the device reported an error like running out of memory, and the status of the swap chain
+/// if acquiring the texture failed.
+fn acquire_swap_chain_texture(
+    swap_chain: &impl SwapChain,
+    configuration: &SurfaceConfiguration,
+    render_device: &RenderDevice,
+    may_erroneously_timeout: impl FnOnce() -> bool,
+) -> Result<Option<wgpu::SurfaceTexture>, wgpu::CurrentSurfaceTexture> {
+    match acquire_surface_texture(swap_chain, render_device.frame_statistics()) {
+        wgpu::CurrentSurfaceTexture::Success(surface_texture)
+        | wgpu::CurrentSurfaceTexture::Suboptimal(surface_texture) => Ok(Some(surface_texture)),
+        wgpu::CurrentSurfaceTexture::Timeout if may_erroneously_timeout() => {
+            bevy_log::trace!(
+                "Couldn't get swap chain texture. This is probably a quirk \
+                    of your Linux GPU driver, so it can be safely ignored."
+            );
+            Ok(None)
+        }
+        wgpu::CurrentSurfaceTexture::Outdated => {
+            swap_chain.configure(render_device, configuration);
+            match acquire_surface_texture(swap_chain, render_device.frame_statistics()) {
+                wgpu::CurrentSurfaceTexture::Success(surface_texture)
+                | wgpu::CurrentSurfaceTexture::Suboptimal(surface_texture) => {
+                    Ok(Some(surface_texture))
+                }
+                status => {
+                    // This is a common occurrence on X11 and Xwayland with NVIDIA drivers
+                    // when opening and resizing the window.
+                    warn!("Couldn't get swap chain texture after configuring. Cause: '{status:?}'");
+                    Err(status)
+                }
             }
         }
-        window.swap_chain_texture_format = Some(surface_data.configuration.format);
+        wgpu::CurrentSurfaceTexture::Occluded => Ok(None),
+        wgpu::CurrentSurfaceTexture::Validation => {
+            // wgpu already passed the cause, e.g. running out of memory, to the uncaptured error
+            // handler of the device. The `DeviceErrorHandler` hands it to the
+            // `RenderErrorHandler` with its `ErrorType`, whose policy decides how to recover, so
+            // it isn't counted as a failure to acquire the texture as well.
+            Ok(None)
+        }
+        wgpu::CurrentSurfaceTexture::Lost => {
+            // Reconfiguring a lost surface doesn't help, it has to be created again.
+            warn!("Swap chain surface was lost, recreating it");
+            Err(wgpu::CurrentSurfaceTexture::Lost)
+        }
+        status => {
+            bevy_log::error!("Couldn't get swap chain texture: {status:?}");
+            Err(status)
+        }
     }
 }
 
+/// Gets the next texture of `swap_chain`, counting the wait in `frame_statistics`.
+fn acquire_surface_texture(
+    swap_chain: &impl SwapChain,
+    frame_statistics: &crate::diagnostic::FrameStatisticsCounters,
+) -> wgpu::CurrentSurfaceTexture {
+    let (texture, acquire_time) = crate::diagnostic::timed(|| {
+        let _span = bevy_log::info_span!("acquire_swap_chain_texture").entered();
+        swap_chain.current_texture()
+    });
+    frame_statistics.add(&crate::diagnostic::RenderFrameStatistics {
+        acquire_time,
+        ..Default::default()
+    });
+    texture
+}
+
 pub fn need_surface_configuration(
     windows: Res<ExtractedWindows>,
     window_surfaces: Res<WindowSurfaces>,
@@ -345,6 +674,7 @@ pub fn need_surface_configuration(
         if !window_surfaces.configured_windows.contains(&window.entity)
             || window.size_changed
             || window.present_mode_changed
+            || window.surface_format_changed
         {
             return true;
         }
@@ -368,6 +698,7 @@ pub fn create_surfaces(
     render_instance: Res<RenderInstance>,
     render_adapter: Res<RenderAdapter>,
     render_device: Res<RenderDevice>,
+    mut present_statistics: ResMut<PresentStatistics>,
 ) {
     for window in windows.windows.values_mut() {
         let data = window_surfaces
@@ -387,37 +718,15 @@ pub fn create_surfaces(
                         .expect("Failed to create wgpu surface")
                 };
                 let caps = surface.get_capabilities(&render_adapter);
-                let present_mode = present_mode(window, &caps);
-                let formats = caps.formats;
-                // For future HDR output support, we'll need to request a format that supports HDR,
-                // but as of wgpu 0.15 that is not yet supported.
-                // Prefer sRGB formats for surfaces, but fall back to first available format if no sRGB formats are available.
-                let mut format = *formats.first().expect("No supported formats for surface");
-                for available_format in formats {
-                    // Rgba8UnormSrgb and Bgra8UnormSrgb and the only sRGB formats wgpu exposes that we can use for surfaces.
-                    if available_format == TextureFormat::Rgba8UnormSrgb
-                        || available_format == TextureFormat::Bgra8UnormSrgb
-                    {
-                        format = available_format;
-                        break;
-                    }
-                }
-
-                let texture_view_format = if !format.is_srgb() {
-                    Some(format.add_srgb_suffix())
-                } else {
-                    None
-                };
+                let present_mode = negotiate_present_mode(window.present_mode, &caps.present_modes);
+                let (format, texture_view_format) = surface_format(window, &caps.formats);
                 let configuration = SurfaceConfiguration {
                     format,
                     width: window.physical_width,
                     height: window.physical_height,
                     usage: TextureUsages::RENDER_ATTACHMENT,
                     present_mode,
-                    desired_maximum_frame_latency: window
-                        .desired_maximum_frame_latency
-                        .map(NonZero::<u32>::get)
-                        .unwrap_or(DEFAULT_DESIRED_MAXIMUM_FRAME_LATENCY),
+                    desired_maximum_frame_latency: desired_maximum_frame_latency(window),
                     alpha_mode: match window.alpha_mode {
                         CompositeAlphaMode::Auto => wgpu::CompositeAlphaMode::Auto,
                         CompositeAlphaMode::Opaque => wgpu::CompositeAlphaMode::Opaque,
@@ -457,19 +766,131 @@ pub fn create_surfaces(
             data.configuration.width = window.physical_width;
             data.configuration.height = window.physical_height;
             let caps = data.surface.get_capabilities(&render_adapter);
-            data.configuration.present_mode = present_mode(window, &caps);
+            data.configuration.present_mode =
+                negotiate_present_mode(window.present_mode, &caps.present_modes);
+            data.configuration.desired_maximum_frame_latency =
+                desired_maximum_frame_latency(window);
             render_device.configure_surface(&data.surface, &data.configuration);
         }
 
+        if window.surface_format_changed {
+            drop(window.swap_chain_texture.take());
+            #[cfg_attr(
+                target_arch = "wasm32",
+                expect(clippy::drop_non_drop, reason = "texture views are not drop on wasm")
+            )]
+            drop(window.swap_chain_texture_view.take());
+
+            let caps = data.surface.get_capabilities(&render_adapter);
+            let (format, texture_view_format) = surface_format(window, &caps.formats);
+            data.configuration.format = format;
+            data.configuration.view_formats = texture_view_format.into_iter().collect();
+            data.texture_view_format = texture_view_format;
+            render_device.configure_surface(&data.surface, &data.configuration);
+        }
+
+        present_statistics.present_modes.insert(
+            window.entity,
+            from_wgpu_present_mode(data.configuration.present_mode),
+        );
         window_surfaces.configured_windows.insert(window.entity);
     }
 }
 
-fn present_mode(
-    window: &mut ExtractedWindow,
-    caps: &wgpu::SurfaceCapabilities,
+/// Chooses the format of the surface of `window` and, if it isn't sRGB while no format was
+/// requested, the sRGB format its textures are viewed with.
+///
+/// Unsupported requested formats are reported and fall back to the default choice.
+fn surface_format(
+    window: &ExtractedWindow,
+    supported: &[TextureFormat],
+) -> (TextureFormat, Option<TextureFormat>) {
+    let format =
+        negotiate_surface_format(&window.surface_formats, supported).unwrap_or_else(|err| {
+            bevy_log::error!("{err}, falling back to the default surface format");
+            negotiate_surface_format(&[], supported).expect("No supported formats for surface")
+        });
+    // Requested formats are used as they are, e.g. to do custom color management.
+    let texture_view_format = if format.is_srgb() || window.surface_formats.contains(&format) {
+        None
+    } else {
+        Some(format.add_srgb_suffix())
+    };
+    (format, texture_view_format)
+}
+
+/// An error when choosing the format of a window surface, see [`SurfaceTextureFormat`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SurfaceFormatError {
+    #[error("The surface doesn't support any format")]
+    NoSupportedFormat,
+    #[error(
+        "None of the requested surface formats {requested:?} is supported, the surface supports {supported:?}"
+    )]
+    UnsupportedFormats {
+        requested: Vec<TextureFormat>,
+        supported: Vec<TextureFormat>,
+    },
+}
+
+/// Chooses the first of the `requested` formats that is `supported` by a surface.
+///
+/// If no format is requested, sRGB formats are preferred, falling back to the first supported
+/// format.
+pub fn negotiate_surface_format(
+    requested: &[TextureFormat],
+    supported: &[TextureFormat],
+) -> Result<TextureFormat, SurfaceFormatError> {
+    let first = *supported
+        .first()
+        .ok_or(SurfaceFormatError::NoSupportedFormat)?;
+    if requested.is_empty() {
+        // For future HDR output support, we'll need to request a format that supports HDR,
+        // but as of wgpu 0.15 that is not yet supported.
+        // Rgba8UnormSrgb and Bgra8UnormSrgb and the only sRGB formats wgpu exposes that we can use for surfaces.
+        return Ok(supported
+            .iter()
+            .copied()
+            .find(|format| {
+                *format == TextureFormat::Rgba8UnormSrgb || *format == TextureFormat::Bgra8UnormSrgb
+            })
+            .unwrap_or(first));
+    }
+    requested
+        .iter()
+        .copied()
+        .find(|format| supported.contains(format))
+        .ok_or_else(|| SurfaceFormatError::UnsupportedFormats {
+            requested: requested.to_vec(),
+            supported: supported.to_vec(),
+        })
+}
+
+fn desired_maximum_frame_latency(window: &ExtractedWindow) -> u32 {
+    window
+        .desired_maximum_frame_latency
+        .map(NonZero::<u32>::get)
+        .unwrap_or(DEFAULT_DESIRED_MAXIMUM_FRAME_LATENCY)
+}
+
+fn from_wgpu_present_mode(present_mode: wgpu::PresentMode) -> PresentMode {
+    match present_mode {
+        wgpu::PresentMode::Fifo => PresentMode::Fifo,
+        wgpu::PresentMode::FifoRelaxed => PresentMode::FifoRelaxed,
+        wgpu::PresentMode::Mailbox => PresentMode::Mailbox,
+        wgpu::PresentMode::Immediate => PresentMode::Immediate,
+        wgpu::PresentMode::AutoVsync => PresentMode::AutoVsync,
+        wgpu::PresentMode::AutoNoVsync => PresentMode::AutoNoVsync,
+    }
+}
+
+/// Chooses the first of the fallbacks of the `requested` present mode that is `available`,
+/// ending with [`wgpu::PresentMode::Fifo`], which every surface supports.
+fn negotiate_present_mode(
+    requested: PresentMode,
+    available: &[wgpu::PresentMode],
 ) -> wgpu::PresentMode {
-    let present_mode = match window.present_mode {
+    let present_mode = match requested {
         PresentMode::Fifo => wgpu::PresentMode::Fifo,
         PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
         PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
@@ -497,18 +918,230 @@ fn present_mode(
     let new_present_mode = fallbacks
         .iter()
         .copied()
-        .find(|fallback| caps.present_modes.contains(fallback))
+        .find(|fallback| available.contains(fallback))
         .unwrap_or_else(|| {
             unreachable!(
                 "Fallback system failed to choose present mode. \
                             This is a bug. Mode: {:?}, Options: {:?}",
-                window.present_mode, &caps.present_modes
+                requested, available
             );
         });
     if new_present_mode != present_mode && fallbacks.contains(&present_mode) {
-        info!(
+        warn!(
             "PresentMode {present_mode:?} requested but not available. Falling back to {new_present_mode:?}"
         );
     }
     new_present_mode
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::VecDeque;
+    use core::cell::{Cell, RefCell};
+
+    use bevy_ecs::entity::{Entity, EntityIndex};
+
+    use bevy_window::PresentMode;
+
+    use super::{
+        SurfaceAcquireRetries, SurfaceFormatError, SwapChain, acquire_swap_chain_texture,
+        negotiate_present_mode, negotiate_surface_format,
+    };
+    use crate::renderer::{RenderDevice, noop_render_resources};
+    use wgpu::{SurfaceConfiguration, TextureFormat, TextureUsages};
+
+    /// A swap chain returning the given statuses in order, and then [`Occluded`].
+    ///
+    /// [`Occluded`]: wgpu::CurrentSurfaceTexture::Occluded
+    #[derive(Default)]
+    struct FakeSwapChain {
+        statuses: RefCell<VecDeque<wgpu::CurrentSurfaceTexture>>,
+        configured: Cell<u32>,
+    }
+
+    impl SwapChain for FakeSwapChain {
+        fn current_texture(&self) -> wgpu::CurrentSurfaceTexture {
+            self.statuses
+                .borrow_mut()
+                .pop_front()
+                .unwrap_or(wgpu::CurrentSurfaceTexture::Occluded)
+        }
+
+        fn configure(&self, _: &RenderDevice, _: &SurfaceConfiguration) {
+            self.configured.set(self.configured.get() + 1);
+        }
+    }
+
+    #[test]
+    fn surface_format_negotiation() {
+        let supported = [
+            TextureFormat::Bgra8Unorm,
+            TextureFormat::Bgra8UnormSrgb,
+            TextureFormat::Rgba16Float,
+        ];
+
+        // sRGB is preferred by default.
+        assert_eq!(
+            negotiate_surface_format(&[], &supported),
+            Ok(TextureFormat::Bgra8UnormSrgb)
+        );
+        assert_eq!(
+            negotiate_surface_format(&[], &[TextureFormat::Rgba16Float]),
+            Ok(TextureFormat::Rgba16Float)
+        );
+        assert_eq!(
+            negotiate_surface_format(
+                &[TextureFormat::Rgb10a2Unorm, TextureFormat::Rgba16Float],
+                &supported
+            ),
+            Ok(TextureFormat::Rgba16Float)
+        );
+        assert_eq!(
+            negotiate_surface_format(&[TextureFormat::Rgb10a2Unorm], &supported),
+            Err(SurfaceFormatError::UnsupportedFormats {
+                requested: vec![TextureFormat::Rgb10a2Unorm],
+                supported: supported.to_vec(),
+            })
+        );
+        assert_eq!(
+            negotiate_surface_format(&[], &[]),
+            Err(SurfaceFormatError::NoSupportedFormat)
+        );
+    }
+
+    #[test]
+    fn present_mode_falls_back_to_fifo() {
+        let fifo_only = [wgpu::PresentMode::Fifo];
+        let all = [
+            wgpu::PresentMode::Fifo,
+            wgpu::PresentMode::FifoRelaxed,
+            wgpu::PresentMode::Mailbox,
+            wgpu::PresentMode::Immediate,
+        ];
+
+        for requested in [
+            PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync,
+            PresentMode::Mailbox,
+            PresentMode::Immediate,
+            PresentMode::FifoRelaxed,
+        ] {
+            assert_eq!(
+                negotiate_present_mode(requested, &fifo_only),
+                wgpu::PresentMode::Fifo
+            );
+        }
+        assert_eq!(
+            negotiate_present_mode(PresentMode::AutoVsync, &all),
+            wgpu::PresentMode::FifoRelaxed
+        );
+        assert_eq!(
+            negotiate_present_mode(PresentMode::AutoNoVsync, &all),
+            wgpu::PresentMode::Immediate
+        );
+        assert_eq!(
+            negotiate_present_mode(
+                PresentMode::Mailbox,
+                &[wgpu::PresentMode::Fifo, wgpu::PresentMode::Immediate]
+            ),
+            wgpu::PresentMode::Immediate
+        );
+    }
+
+    #[test]
+    fn surface_acquire_failures_escalate_past_threshold() {
+        let render_device = noop_render_resources(wgpu::Features::empty()).0;
+        let configuration = SurfaceConfiguration {
+            format: TextureFormat::Bgra8UnormSrgb,
+            width: 1,
+            height: 1,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+        let swap_chain = FakeSwapChain::default();
+        let window = Entity::from_index(EntityIndex::from_raw_u32(1).unwrap());
+        let mut retries = SurfaceAcquireRetries {
+            max_skipped_frames: 3,
+            ..Default::default()
+        };
+        // Acquires a texture like `prepare_windows`, returning whether the frame is skipped.
+        let frame = |retries: &mut SurfaceAcquireRetries,
+                     statuses: Vec<wgpu::CurrentSurfaceTexture>,
+                     may_erroneously_timeout: bool| {
+            swap_chain.statuses.borrow_mut().extend(statuses);
+            let failure =
+                acquire_swap_chain_texture(&swap_chain, &configuration, &render_device, || {
+                    may_erroneously_timeout
+                })
+                .err()
+                .map(|status| format!("{status:?}"));
+            retries.record(window, failure)
+        };
+
+        // A streak below the threshold, ended by a frame without a failure.
+        for _ in 0..3 {
+            assert!(frame(
+                &mut retries,
+                vec![wgpu::CurrentSurfaceTexture::Timeout],
+                false
+            ));
+        }
+        assert!(!frame(
+            &mut retries,
+            vec![wgpu::CurrentSurfaceTexture::Occluded],
+            false
+        ));
+        assert_eq!(retries.consecutive_failures(window), 0);
+        assert!(retries.take_escalation().is_none());
+
+        // Errors like running out of memory are handled through the `DeviceErrorHandler`.
+        assert!(!frame(
+            &mut retries,
+            vec![wgpu::CurrentSurfaceTexture::Validation],
+            false
+        ));
+        assert_eq!(retries.consecutive_failures(window), 0);
+
+        // Timeouts that are a quirk of the driver are not failures.
+        for _ in 0..5 {
+            assert!(!frame(
+                &mut retries,
+                vec![wgpu::CurrentSurfaceTexture::Timeout],
+                true
+            ));
+        }
+        assert_eq!(retries.consecutive_failures(window), 0);
+
+        // An outdated surface is reconfigured once before failing.
+        assert!(frame(
+            &mut retries,
+            vec![
+                wgpu::CurrentSurfaceTexture::Outdated,
+                wgpu::CurrentSurfaceTexture::Outdated,
+            ],
+            false
+        ));
+        assert_eq!(swap_chain.configured.get(), 1);
+
+        // A streak above the threshold escalates instead of skipping the frame.
+        for _ in 0..2 {
+            assert!(frame(
+                &mut retries,
+                vec![wgpu::CurrentSurfaceTexture::Lost],
+                false
+            ));
+        }
+        assert!(!frame(
+            &mut retries,
+            vec![wgpu::CurrentSurfaceTexture::Lost],
+            false
+        ));
+        let error = retries.take_escalation().unwrap();
+        assert!(error.description.contains("4 frames"));
+        assert!(error.description.contains("Lost"));
+        assert_eq!(retries.consecutive_failures(window), 0);
+    }
+}
@@ -15,6 +15,7 @@ use alloc::{borrow::Cow, sync::Arc};
 use bevy_app::{First, Plugin, Update};
 use bevy_asset::{AssetServer, Handle, RenderAssetUsages, embedded_asset, load_embedded_asset};
 use bevy_camera::{ManualTextureViewHandle, NormalizedRenderTarget, RenderTarget};
+use bevy_color::Srgba;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
     entity::EntityHashMap, message::message_update_system, prelude::*, system::SystemState,
@@ -28,20 +29,24 @@ use bevy_material::{
         VertexState,
     },
 };
+use bevy_math::{URect, UVec2};
 use bevy_platform::collections::HashSet;
 use bevy_reflect::Reflect;
 use bevy_shader::Shader;
-use bevy_tasks::AsyncComputeTaskPool;
+use bevy_tasks::{AsyncComputeTaskPool, IoTaskPool};
 use bevy_utils::default;
 use bevy_window::{PrimaryWindow, WindowRef};
 use core::ops::Deref;
+use half::f16;
+use image::{DynamicImage, ImageBuffer, ImageFormat, RgbaImage, codecs::jpeg::JpegEncoder};
 use std::{
-    path::Path,
+    path::{Path, PathBuf},
     sync::{
         Mutex,
         mpsc::{Receiver, Sender},
     },
 };
+use thiserror::Error;
 use wgpu::{CommandEncoder, Extent3d, TextureFormat};
 
 #[derive(EntityEvent, Reflect, Deref, DerefMut, Debug)]
@@ -79,6 +84,26 @@ pub struct ScreenshotCaptured {
 #[reflect(Component, Debug)]
 pub struct Screenshot(pub RenderTarget);
 
+/// Restricts a [`Screenshot`] to a sub-rectangle of its render target, in physical pixels.
+///
+/// Only the texels inside the region are copied back, and the resulting image has the size of
+/// the region. The region is clamped to the bounds of the render target, and the screenshot is
+/// skipped if nothing remains.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_math::URect;
+/// # use bevy_render::view::screenshot::{save_to_disk, Screenshot, ScreenshotRegion};
+///
+/// fn capture_widget(mut commands: Commands) {
+///    commands.spawn((Screenshot::primary_window(), ScreenshotRegion(URect::new(8, 8, 24, 24))))
+///       .observe(save_to_disk("widget.png"));
+/// }
+/// ```
+#[derive(Component, Deref, DerefMut, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Debug, Clone)]
+pub struct ScreenshotRegion(pub URect);
+
 /// A marker component that indicates that a screenshot is currently being captured.
 #[derive(Component, Default)]
 pub struct Capturing;
@@ -116,6 +141,25 @@ struct ScreenshotPreparedState {
     pub bind_group: BindGroup,
     pub pipeline_id: CachedRenderPipelineId,
     pub size: Extent3d,
+    /// The region copied back, clamped to `size`. The whole texture is copied if `None`.
+    pub region: Option<URect>,
+}
+
+impl ScreenshotPreparedState {
+    /// The origin and size of the texels copied back.
+    fn copy_region(&self) -> (UVec2, Extent3d) {
+        match self.region {
+            Some(region) => (
+                region.min,
+                Extent3d {
+                    width: region.width(),
+                    height: region.height(),
+                    depth_or_array_layers: 1,
+                },
+            ),
+            None => (UVec2::ZERO, self.size),
+        }
+    }
 }
 
 #[derive(Resource, Deref, DerefMut)]
@@ -124,6 +168,14 @@ pub struct CapturedScreenshots(pub Arc<Mutex<Receiver<(Entity, Image)>>>);
 #[derive(Resource, Deref, DerefMut, Default)]
 struct RenderScreenshotTargets(EntityHashMap<NormalizedRenderTarget>);
 
+#[derive(Resource, Deref, DerefMut, Default)]
+struct RenderScreenshotRegions(EntityHashMap<URect>);
+
+/// Screenshots of windows without a swap chain texture in the frame they were extracted, e.g.
+/// because the surface was outdated, to capture in the next frame instead.
+#[derive(Resource, Deref, DerefMut, Default)]
+struct RenderScreenshotRetries(EntityHashMap<(NormalizedRenderTarget, Option<URect>)>);
+
 #[derive(Resource, Deref, DerefMut, Default)]
 struct RenderScreenshotsPrepared(EntityHashMap<ScreenshotPreparedState>);
 
@@ -150,35 +202,10 @@ pub fn save_to_disk(path: impl AsRef<Path>) -> impl FnMut(On<ScreenshotCaptured>
                     #[cfg(target_arch = "wasm32")]
                     {
                         let save_screenshot = || {
-                            use image::EncodableLayout;
-                            use wasm_bindgen::{JsCast, JsValue};
-
                             let mut image_buffer = std::io::Cursor::new(Vec::new());
                             img.write_to(&mut image_buffer, format)
-                                .map_err(|e| JsValue::from_str(&format!("{e}")))?;
-
-                            let parts = js_sys::Array::of1(
-                                &js_sys::Uint8Array::new_from_slice(
-                                    image_buffer.into_inner().as_bytes(),
-                                )
-                                .into(),
-                            );
-                            let blob = web_sys::Blob::new_with_u8_array_sequence(&parts)?;
-                            let url = web_sys::Url::create_object_url_with_blob(&blob)?;
-                            let window = web_sys::window().unwrap();
-                            let document = window.document().unwrap();
-                            let link = document.create_element("a")?;
-                            link.set_attribute("href", &url)?;
-                            link.set_attribute(
-                                "download",
-                                path.file_name()
-                                    .and_then(|filename| filename.to_str())
-                                    .ok_or_else(|| JsValue::from_str("Invalid filename"))?,
-                            )?;
-                            let html_element = link.dyn_into::<web_sys::HtmlElement>()?;
-                            html_element.click();
-                            web_sys::Url::revoke_object_url(&url)?;
-                            Ok::<(), JsValue>(())
+                                .map_err(|e| wasm_bindgen::JsValue::from_str(&format!("{e}")))?;
+                            download_on_web(&path, &image_buffer.into_inner())
                         };
 
                         match (save_screenshot)() {
@@ -194,6 +221,254 @@ pub fn save_to_disk(path: impl AsRef<Path>) -> impl FnMut(On<ScreenshotCaptured>
     }
 }
 
+/// Offers `data` as a download named after the file name of `path`.
+#[cfg(target_arch = "wasm32")]
+fn download_on_web(path: &Path, data: &[u8]) -> Result<(), wasm_bindgen::JsValue> {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::new_from_slice(data).into());
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let link = document.create_element("a")?;
+    link.set_attribute("href", &url)?;
+    link.set_attribute(
+        "download",
+        path.file_name()
+            .and_then(|filename| filename.to_str())
+            .ok_or_else(|| JsValue::from_str("Invalid filename"))?,
+    )?;
+    let html_element = link.dyn_into::<web_sys::HtmlElement>()?;
+    html_element.click();
+    web_sys::Url::revoke_object_url(&url)?;
+    Ok(())
+}
+
+/// The file format [`save_to_disk_as`] encodes a screenshot in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenshotFormat {
+    /// A PNG image. HDR and 10-bit screenshots are saved as 16-bit PNGs.
+    Png,
+    /// A JPEG image with the given quality, from 1 to 100. JPEGs never have an alpha channel.
+    Jpeg(u8),
+    /// The bytes of the captured texture as-is, tightly packed and in the texture format.
+    ///
+    /// With `strip_alpha`, the alpha channel of each texel is removed, which isn't supported for
+    /// formats packing alpha with other channels, like `Rgb10a2Unorm`.
+    Raw,
+}
+
+/// An error that occurred while saving a screenshot.
+#[derive(Error, Debug)]
+pub enum ScreenshotError {
+    #[error("the screenshot has no data")]
+    MissingData,
+    #[error("screenshots of {0:?} textures cannot be encoded")]
+    UnsupportedFormat(TextureFormat),
+    #[error("failed to encode the screenshot: {0}")]
+    Encode(#[from] image::ImageError),
+    #[error("failed to write the screenshot: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to save the screenshot: {0}")]
+    Save(String),
+}
+
+/// Sent when a screenshot saved with [`save_to_disk_as`] was written.
+#[derive(Message, Debug)]
+pub struct ScreenshotSaved {
+    pub path: PathBuf,
+}
+
+/// Sent when a screenshot saved with [`save_to_disk_as`] could not be written.
+#[derive(Message, Debug)]
+pub struct ScreenshotFailed {
+    pub path: PathBuf,
+    pub error: ScreenshotError,
+}
+
+/// Sends the results of the screenshots encoded by [`save_to_disk_as`] back to the main world.
+#[derive(Resource)]
+pub struct ScreenshotSaveResults {
+    sender: async_channel::Sender<(PathBuf, Result<(), ScreenshotError>)>,
+    receiver: async_channel::Receiver<(PathBuf, Result<(), ScreenshotError>)>,
+}
+
+impl Default for ScreenshotSaveResults {
+    fn default() -> Self {
+        let (sender, receiver) = async_channel::unbounded();
+        Self { sender, receiver }
+    }
+}
+
+/// Saves the captured screenshot to disk at the provided path, in the given format.
+///
+/// Encoding and writing happen on the [`IoTaskPool`], never on the main or render thread. The
+/// result is reported with a [`ScreenshotSaved`] or [`ScreenshotFailed`] message. If
+/// `strip_alpha` is set, the alpha channel is discarded, which is recommended for HDR cameras
+/// where it stores brightness values.
+pub fn save_to_disk_as(
+    path: impl AsRef<Path>,
+    format: ScreenshotFormat,
+    strip_alpha: bool,
+) -> impl FnMut(On<ScreenshotCaptured>, Res<ScreenshotSaveResults>) {
+    let path = path.as_ref().to_owned();
+    move |screenshot_captured, results| {
+        let image = screenshot_captured.image.clone();
+        let path = path.clone();
+        let sender = results.sender.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                let result = encode_screenshot(&image, format, strip_alpha).and_then(|data| {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    std::fs::write(&path, data)?;
+                    #[cfg(target_arch = "wasm32")]
+                    download_on_web(&path, &data)
+                        .map_err(|e| ScreenshotError::Save(format!("{e:?}")))?;
+                    Ok(())
+                });
+                // The receiver lives as long as the app.
+                let _ = sender.send((path, result)).await;
+            })
+            .detach();
+    }
+}
+
+fn send_screenshot_save_results(
+    results: Res<ScreenshotSaveResults>,
+    mut saved: MessageWriter<ScreenshotSaved>,
+    mut failed: MessageWriter<ScreenshotFailed>,
+) {
+    while let Ok((path, result)) = results.receiver.try_recv() {
+        match result {
+            Ok(()) => {
+                info!("Screenshot saved to {}", path.display());
+                saved.write(ScreenshotSaved { path });
+            }
+            Err(error) => {
+                error!("Cannot save screenshot to {}: {error}", path.display());
+                failed.write(ScreenshotFailed { path, error });
+            }
+        }
+    }
+}
+
+/// Encodes a captured screenshot in the given format.
+///
+/// BGRA screenshots are swizzled, and 10-bit and `Rgba16Float` screenshots are kept at 16 bits
+/// per channel for PNGs, or reduced to 8 bits per channel for JPEGs. `Rgba16Float` screenshots
+/// are linear, so they are converted to sRGB like the other formats.
+pub fn encode_screenshot(
+    image: &Image,
+    format: ScreenshotFormat,
+    strip_alpha: bool,
+) -> Result<Vec<u8>, ScreenshotError> {
+    let data = image.data.as_deref().ok_or(ScreenshotError::MissingData)?;
+    let mut bytes = Vec::new();
+    match format {
+        ScreenshotFormat::Raw if strip_alpha => {
+            bytes = strip_raw_alpha(image.texture_descriptor.format, data)?;
+        }
+        ScreenshotFormat::Raw => bytes.extend_from_slice(data),
+        ScreenshotFormat::Png => {
+            let dynamic = screenshot_to_dynamic(image, data)?;
+            let dynamic = match (strip_alpha, &dynamic) {
+                (false, _) => dynamic,
+                (true, DynamicImage::ImageRgba16(_)) => {
+                    DynamicImage::ImageRgb16(dynamic.to_rgb16())
+                }
+                (true, _) => DynamicImage::ImageRgb8(dynamic.to_rgb8()),
+            };
+            dynamic.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)?;
+        }
+        ScreenshotFormat::Jpeg(quality) => {
+            let rgb = screenshot_to_dynamic(image, data)?.to_rgb8();
+            JpegEncoder::new_with_quality(&mut bytes, quality.clamp(1, 100)).encode_image(&rgb)?;
+        }
+    }
+    Ok(bytes)
+}
+
+fn screenshot_to_dynamic(image: &Image, data: &[u8]) -> Result<DynamicImage, ScreenshotError> {
+    let (width, height) = (image.width(), image.height());
+    let texture_format = image.texture_descriptor.format;
+    let invalid = || ScreenshotError::UnsupportedFormat(texture_format);
+    let dynamic = match texture_format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => DynamicImage::ImageRgba8(
+            RgbaImage::from_raw(width, height, data.to_vec()).ok_or_else(invalid)?,
+        ),
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            let rgba = data
+                .chunks_exact(4)
+                .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+                .collect();
+            DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, rgba).ok_or_else(invalid)?)
+        }
+        TextureFormat::Rgb10a2Unorm => {
+            let rgba = data
+                .chunks_exact(4)
+                .flat_map(|texel| {
+                    let texel = u32::from_le_bytes([texel[0], texel[1], texel[2], texel[3]]);
+                    let channel = |shift: u32| ((texel >> shift) & 0x3ff) * 65535 / 1023;
+                    [
+                        channel(0),
+                        channel(10),
+                        channel(20),
+                        (texel >> 30) * 65535 / 3,
+                    ]
+                    .map(|value| value as u16)
+                })
+                .collect();
+            DynamicImage::ImageRgba16(
+                ImageBuffer::from_raw(width, height, rgba).ok_or_else(invalid)?,
+            )
+        }
+        TextureFormat::Rgba16Float => {
+            let rgba = data
+                .chunks_exact(2)
+                .enumerate()
+                .map(|(channel, bytes)| {
+                    let value = f16::from_le_bytes([bytes[0], bytes[1]])
+                        .to_f32()
+                        .clamp(0.0, 1.0);
+                    // Alpha is linear in every format.
+                    let value = if channel % 4 == 3 {
+                        value
+                    } else {
+                        Srgba::gamma_function_inverse(value)
+                    };
+                    (value * 65535.0).round() as u16
+                })
+                .collect();
+            DynamicImage::ImageRgba16(
+                ImageBuffer::from_raw(width, height, rgba).ok_or_else(invalid)?,
+            )
+        }
+        _ => image.clone().try_into_dynamic().map_err(|_| invalid())?,
+    };
+    Ok(dynamic)
+}
+
+/// Removes the alpha channel from the texels of a raw screenshot.
+fn strip_raw_alpha(format: TextureFormat, data: &[u8]) -> Result<Vec<u8>, ScreenshotError> {
+    // The size of a texel, and of its alpha channel stored last.
+    let (texel_size, alpha_size) = match format {
+        _ if format.components() < 4 => return Ok(data.to_vec()),
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => (4, 1),
+        TextureFormat::Rgba16Float | TextureFormat::Rgba16Unorm => (8, 2),
+        TextureFormat::Rgba32Float => (16, 4),
+        _ => return Err(ScreenshotError::UnsupportedFormat(format)),
+    };
+    Ok(data
+        .chunks_exact(texel_size)
+        .flat_map(|texel| &texel[..texel_size - alpha_size])
+        .copied()
+        .collect())
+}
+
 fn clear_screenshots(mut commands: Commands, screenshots: Query<Entity, With<Captured>>) {
     for entity in screenshots.iter() {
         commands.entity(entity).despawn();
@@ -213,18 +488,36 @@ pub fn trigger_screenshots(
 
 fn extract_screenshots(
     mut targets: ResMut<RenderScreenshotTargets>,
+    mut regions: ResMut<RenderScreenshotRegions>,
+    mut retries: ResMut<RenderScreenshotRetries>,
     mut main_world: ResMut<MainWorld>,
     mut system_state: Local<
         Option<
             SystemState<(
                 Commands,
                 Query<Entity, With<PrimaryWindow>>,
-                Query<(Entity, &Screenshot), Without<Capturing>>,
+                Query<(Entity, &Screenshot, Option<&ScreenshotRegion>), Without<Capturing>>,
             )>,
         >,
     >,
     mut seen_targets: Local<HashSet<NormalizedRenderTarget>>,
 ) {
+    targets.clear();
+    regions.clear();
+    seen_targets.clear();
+
+    for (entity, (render_target, region)) in retries.drain() {
+        // The screenshot may have been despawned in the meantime.
+        if main_world.get_entity(entity).is_err() {
+            continue;
+        }
+        seen_targets.insert(render_target.clone());
+        targets.insert(entity, render_target);
+        if let Some(region) = region {
+            regions.insert(entity, region);
+        }
+    }
+
     if system_state.is_none() {
         *system_state = Some(SystemState::new(&mut main_world));
     }
@@ -232,12 +525,9 @@ fn extract_screenshots(
     let (mut commands, primary_window, screenshots) =
         system_state.get_mut(&mut main_world).unwrap();
 
-    targets.clear();
-    seen_targets.clear();
-
     let primary_window = primary_window.iter().next();
 
-    for (entity, screenshot) in screenshots.iter() {
+    for (entity, screenshot, region) in screenshots.iter() {
         let render_target = screenshot.0.clone();
         let Some(render_target) = render_target.normalize(primary_window) else {
             warn!(
@@ -257,6 +547,9 @@ fn extract_screenshots(
         }
         seen_targets.insert(render_target.clone());
         targets.insert(entity, render_target);
+        if let Some(region) = region {
+            regions.insert(entity, region.0);
+        }
         commands.entity(entity).insert(Capturing);
     }
 
@@ -265,7 +558,10 @@ fn extract_screenshots(
 
 fn prepare_screenshots(
     targets: Res<RenderScreenshotTargets>,
+    regions: Res<RenderScreenshotRegions>,
+    mut retries: ResMut<RenderScreenshotRetries>,
     mut prepared: ResMut<RenderScreenshotsPrepared>,
+    windows: Res<ExtractedWindows>,
     window_surfaces: Res<WindowSurfaces>,
     render_device: Res<RenderDevice>,
     screenshot_pipeline: Res<ScreenshotToScreenPipeline>,
@@ -277,6 +573,7 @@ fn prepare_screenshots(
 ) {
     prepared.clear();
     for (entity, target) in targets.iter() {
+        let region = regions.get(entity).copied();
         match target {
             NormalizedRenderTarget::Window(window) => {
                 let window = window.entity();
@@ -284,6 +581,14 @@ fn prepare_screenshots(
                     warn!("Unknown window for screenshot, skipping: {}", window);
                     continue;
                 };
+                if windows
+                    .get(&window)
+                    .is_none_or(|window| window.swap_chain_texture_view.is_none())
+                {
+                    // Nothing is presented this frame, e.g. because the surface is outdated.
+                    retries.insert(*entity, (target.clone(), region));
+                    continue;
+                }
                 let view_format = surface_data
                     .texture_view_format
                     .unwrap_or(surface_data.configuration.format);
@@ -292,14 +597,21 @@ fn prepare_screenshots(
                     height: surface_data.configuration.height,
                     ..default()
                 };
-                let (texture_view, state) = prepare_screenshot_state(
+                let Some((texture_view, state)) = prepare_screenshot_state(
                     size,
                     view_format,
+                    region,
                     &render_device,
                     &screenshot_pipeline,
                     &pipeline_cache,
                     &mut pipelines,
-                );
+                ) else {
+                    warn!(
+                        "Screenshot region {:?} is outside of the render target, skipping: {}",
+                        region, entity
+                    );
+                    continue;
+                };
                 prepared.insert(*entity, state);
                 view_target_attachments.insert(
                     target.clone(),
@@ -312,14 +624,21 @@ fn prepare_screenshots(
                     continue;
                 };
                 let view_format = gpu_image.view_format();
-                let (texture_view, state) = prepare_screenshot_state(
+                let Some((texture_view, state)) = prepare_screenshot_state(
                     gpu_image.texture_descriptor.size,
                     view_format,
+                    region,
                     &render_device,
                     &screenshot_pipeline,
                     &pipeline_cache,
                     &mut pipelines,
-                );
+                ) else {
+                    warn!(
+                        "Screenshot region {:?} is outside of the render target, skipping: {}",
+                        region, entity
+                    );
+                    continue;
+                };
                 prepared.insert(*entity, state);
                 view_target_attachments.insert(
                     target.clone(),
@@ -336,14 +655,21 @@ fn prepare_screenshots(
                 };
                 let view_format = manual_texture_view.view_format;
                 let size = manual_texture_view.size.to_extents();
-                let (texture_view, state) = prepare_screenshot_state(
+                let Some((texture_view, state)) = prepare_screenshot_state(
                     size,
                     view_format,
+                    region,
                     &render_device,
                     &screenshot_pipeline,
                     &pipeline_cache,
                     &mut pipelines,
-                );
+                ) else {
+                    warn!(
+                        "Screenshot region {:?} is outside of the render target, skipping: {}",
+                        region, entity
+                    );
+                    continue;
+                };
                 prepared.insert(*entity, state);
                 view_target_attachments.insert(
                     target.clone(),
@@ -360,11 +686,25 @@ fn prepare_screenshots(
 fn prepare_screenshot_state(
     size: Extent3d,
     format: TextureFormat,
+    region: Option<URect>,
     render_device: &RenderDevice,
     pipeline: &ScreenshotToScreenPipeline,
     pipeline_cache: &PipelineCache,
     pipelines: &mut SpecializedRenderPipelines<ScreenshotToScreenPipeline>,
-) -> (TextureView, ScreenshotPreparedState) {
+) -> Option<(TextureView, ScreenshotPreparedState)> {
+    let region = match region {
+        Some(region) => Some(clamp_region(region, size)?),
+        None => None,
+    };
+    let copy_size = match region {
+        Some(region) => Extent3d {
+            width: region.width(),
+            height: region.height(),
+            depth_or_array_layers: 1,
+        },
+        None => size,
+    };
+
     let texture = render_device.create_texture(&wgpu::TextureDescriptor {
         label: Some("screenshot-capture-rendertarget"),
         size,
@@ -380,7 +720,8 @@ fn prepare_screenshot_state(
     let texture_view = texture.create_view(&Default::default());
     let buffer = render_device.create_buffer(&wgpu::BufferDescriptor {
         label: Some("screenshot-transfer-buffer"),
-        size: gpu_readback::get_aligned_size(size, format.pixel_size().unwrap_or(0) as u32) as u64,
+        size: gpu_readback::get_aligned_size(copy_size, format.pixel_size().unwrap_or(0) as u32)
+            as u64,
         usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
         mapped_at_creation: false,
     });
@@ -391,7 +732,7 @@ fn prepare_screenshot_state(
     );
     let pipeline_id = pipelines.specialize(pipeline_cache, pipeline, format);
 
-    (
+    Some((
         texture_view,
         ScreenshotPreparedState {
             texture,
@@ -399,8 +740,35 @@ fn prepare_screenshot_state(
             bind_group,
             pipeline_id,
             size,
+            region,
         },
-    )
+    ))
+}
+
+/// Clamps `region` to a texture of the given `size`, returning `None` if nothing is left.
+fn clamp_region(region: URect, size: Extent3d) -> Option<URect> {
+    let region = region.intersect(URect::new(0, 0, size.width, size.height));
+    (!region.is_empty()).then_some(region)
+}
+
+/// Removes the padding added to every row of a texture copy to satisfy
+/// [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`].
+fn remove_row_padding(mut data: Vec<u8>, width: u32, height: u32, pixel_size: usize) -> Vec<u8> {
+    let initial_row_bytes = width as usize * pixel_size;
+    if data.len() == initial_row_bytes * height as usize {
+        return data;
+    }
+
+    let buffered_row_bytes = gpu_readback::align_byte_size(width * pixel_size as u32) as usize;
+    let mut take_offset = buffered_row_bytes;
+    let mut place_offset = initial_row_bytes;
+    for _ in 1..height {
+        data.copy_within(take_offset..take_offset + initial_row_bytes, place_offset);
+        take_offset += buffered_row_bytes;
+        place_offset += initial_row_bytes;
+    }
+    data.truncate(initial_row_bytes * height as usize);
+    data
 }
 
 pub struct ScreenshotPlugin;
@@ -411,15 +779,19 @@ impl Plugin for ScreenshotPlugin {
 
         let (tx, rx) = std::sync::mpsc::channel();
         app.register_type::<Screenshot>()
+            .register_type::<ScreenshotRegion>()
             .register_type::<ScreenshotCaptured>()
             .insert_resource(CapturedScreenshots(Arc::new(Mutex::new(rx))))
+            .init_resource::<ScreenshotSaveResults>()
+            .add_message::<ScreenshotSaved>()
+            .add_message::<ScreenshotFailed>()
             .add_systems(
                 First,
                 clear_screenshots
                     .after(message_update_system)
                     .before(ApplyDeferred),
             )
-            .add_systems(Update, trigger_screenshots);
+            .add_systems(Update, (trigger_screenshots, send_screenshot_save_results));
 
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
@@ -428,6 +800,8 @@ impl Plugin for ScreenshotPlugin {
         render_app
             .insert_resource(RenderScreenshotsSender(tx))
             .init_resource::<RenderScreenshotTargets>()
+            .init_resource::<RenderScreenshotRegions>()
+            .init_resource::<RenderScreenshotRetries>()
             .init_resource::<RenderScreenshotsPrepared>()
             .init_gpu_resource::<SpecializedRenderPipelines<ScreenshotToScreenPipeline>>()
             .add_systems(RenderStartup, init_screenshot_to_screen_pipeline)
@@ -590,13 +964,25 @@ fn render_screenshot(
     texture_view: &wgpu::TextureView,
 ) {
     if let Some(prepared_state) = &prepared.get(entity) {
-        let extent = Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
+        let (origin, extent) = match prepared_state.region {
+            Some(_) => prepared_state.copy_region(),
+            None => (
+                UVec2::ZERO,
+                Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            ),
+        };
+        let mut source = prepared_state.texture.as_image_copy();
+        source.origin = wgpu::Origin3d {
+            x: origin.x,
+            y: origin.y,
+            z: 0,
         };
         encoder.copy_texture_to_buffer(
-            prepared_state.texture.as_image_copy(),
+            source,
             wgpu::TexelCopyBufferInfo {
                 buffer: &prepared_state.buffer,
                 layout: gpu_readback::layout_data(extent, texture_format),
@@ -638,8 +1024,9 @@ pub(crate) fn collect_screenshots(world: &mut World) {
     for (entity, prepared) in prepared.iter() {
         let entity = *entity;
         let sender = sender.clone();
-        let width = prepared.size.width;
-        let height = prepared.size.height;
+        let (_, extent) = prepared.copy_region();
+        let width = extent.width;
+        let height = extent.height;
         let texture_format = prepared.texture.format();
         let Ok(pixel_size) = texture_format.pixel_size() else {
             continue;
@@ -659,25 +1046,12 @@ pub(crate) fn collect_screenshots(world: &mut World) {
             rx.recv().await.unwrap();
             let data = buffer_slice.get_mapped_range();
             // we immediately move the data to CPU memory to avoid holding the mapped view for long
-            let mut result = Vec::from(&*data);
+            let result = Vec::from(&*data);
             drop(data);
 
-            if result.len() != ((width * height) as usize * pixel_size) {
-                // Our buffer has been padded because we needed to align to a multiple of 256.
-                // We remove this padding here
-                let initial_row_bytes = width as usize * pixel_size;
-                let buffered_row_bytes =
-                    gpu_readback::align_byte_size(width * pixel_size as u32) as usize;
-
-                let mut take_offset = buffered_row_bytes;
-                let mut place_offset = initial_row_bytes;
-                for _ in 1..height {
-                    result.copy_within(take_offset..take_offset + buffered_row_bytes, place_offset);
-                    take_offset += buffered_row_bytes;
-                    place_offset += initial_row_bytes;
-                }
-                result.truncate(initial_row_bytes * height as usize);
-            }
+            // Our buffer has been padded because we needed to align to a multiple of 256.
+            // We remove this padding here
+            let result = remove_row_padding(result, width, height, pixel_size);
 
             if let Err(e) = sender.send((
                 entity,
@@ -700,3 +1074,181 @@ pub(crate) fn collect_screenshots(world: &mut World) {
         AsyncComputeTaskPool::get().spawn(finish).detach();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn screenshot(format: TextureFormat, data: Vec<u8>) -> Image {
+        Image::new(
+            Extent3d {
+                width: 8,
+                height: 8,
+                depth_or_array_layers: 1,
+            },
+            wgpu::TextureDimension::D2,
+            data,
+            format,
+            RenderAssetUsages::MAIN_WORLD,
+        )
+    }
+
+    #[test]
+    fn screenshot_encoding() {
+        // A BGRA8 readback of an opaque orange screenshot.
+        let bgra = [0x20, 0x80, 0xff, 0xff].repeat(64);
+        let image = screenshot(TextureFormat::Bgra8UnormSrgb, bgra.clone());
+
+        let raw = encode_screenshot(&image, ScreenshotFormat::Raw, false).unwrap();
+        assert_eq!(raw, bgra);
+
+        let raw = encode_screenshot(&image, ScreenshotFormat::Raw, true).unwrap();
+        assert_eq!(raw, [0x20, 0x80, 0xff].repeat(64));
+
+        let png = encode_screenshot(&image, ScreenshotFormat::Png, false).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert!(
+            decoded
+                .pixels()
+                .all(|pixel| pixel.0 == [0xff, 0x80, 0x20, 0xff])
+        );
+
+        let png = encode_screenshot(&image, ScreenshotFormat::Png, true).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert!(!decoded.color().has_alpha());
+
+        let jpeg = encode_screenshot(&image, ScreenshotFormat::Jpeg(90), false).unwrap();
+        let decoded = image::load_from_memory(&jpeg).unwrap().to_rgb8();
+        for pixel in decoded.pixels() {
+            for (channel, expected) in pixel.0.into_iter().zip([0xff, 0x80, 0x20]) {
+                assert!(channel.abs_diff(expected) <= 4, "{pixel:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn hdr_screenshot_encoding() {
+        // Linear `Rgba16Float` texels of (1.0, 0.5, 0.0, 0.5).
+        let texel = [0x3c00u16, 0x3800, 0x0000, 0x3800]
+            .into_iter()
+            .flat_map(u16::to_le_bytes);
+        let image = screenshot(
+            TextureFormat::Rgba16Float,
+            texel.collect::<Vec<_>>().repeat(64),
+        );
+
+        // 0.5 is 0.735 in sRGB, alpha stays linear.
+        let png = encode_screenshot(&image, ScreenshotFormat::Png, false).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap();
+        let DynamicImage::ImageRgba16(decoded) = decoded else {
+            panic!("expected a 16-bit RGBA image, got {:?}", decoded.color());
+        };
+        for pixel in decoded.pixels() {
+            let [r, g, b, a] = pixel.0;
+            assert_eq!((r, b, a), (65535, 0, 32768));
+            assert!(g.abs_diff(48192) <= 2, "{pixel:?}");
+        }
+
+        let png = encode_screenshot(&image, ScreenshotFormat::Png, true).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert!(matches!(decoded, DynamicImage::ImageRgb16(_)));
+
+        let raw = encode_screenshot(&image, ScreenshotFormat::Raw, true).unwrap();
+        let rgb = [0x3c00u16, 0x3800, 0x0000]
+            .into_iter()
+            .flat_map(u16::to_le_bytes);
+        assert_eq!(raw, rgb.collect::<Vec<_>>().repeat(64));
+    }
+
+    #[test]
+    fn screenshot_region_is_clamped() {
+        let size = Extent3d {
+            width: 64,
+            height: 32,
+            depth_or_array_layers: 1,
+        };
+        assert_eq!(
+            clamp_region(URect::new(8, 8, 24, 24), size),
+            Some(URect::new(8, 8, 24, 24))
+        );
+        assert_eq!(
+            clamp_region(URect::new(56, 24, 80, 40), size),
+            Some(URect::new(56, 24, 64, 32))
+        );
+        assert_eq!(clamp_region(URect::new(64, 0, 80, 16), size), None);
+    }
+
+    #[test]
+    fn screenshot_region_row_padding() {
+        // A 16x16 RGBA8 region, copied with rows padded to 256 bytes.
+        let (width, height, pixel_size) = (16, 16, 4);
+        let padded_row = gpu_readback::align_byte_size(width * pixel_size as u32) as usize;
+        assert_eq!(padded_row, 256);
+
+        let mut padded = vec![0xff; padded_row * height as usize];
+        for y in 0..height as usize {
+            for x in 0..width as usize * pixel_size {
+                padded[y * padded_row + x] = (y * 16 + x / pixel_size) as u8;
+            }
+        }
+
+        let data = remove_row_padding(padded, width, height, pixel_size);
+        assert_eq!(data.len(), 16 * 16 * 4);
+        for (index, pixel) in data.chunks_exact(pixel_size).enumerate() {
+            assert!(pixel.iter().all(|&byte| byte == index as u8));
+        }
+    }
+
+    #[test]
+    fn screenshots_without_a_swap_chain_texture_are_retried() {
+        let mut render_world = World::new();
+        render_world.init_resource::<RenderScreenshotTargets>();
+        render_world.init_resource::<RenderScreenshotRegions>();
+        render_world.init_resource::<RenderScreenshotRetries>();
+
+        let mut main_world = MainWorld::default();
+        let window = main_world.spawn_empty().id();
+        let closed_window = main_world.spawn_empty().id();
+        let region = URect::new(0, 0, 16, 16);
+        // Extracted in a previous frame in which `window` had no swap chain texture.
+        let retried = main_world
+            .spawn((
+                Screenshot::window(window),
+                ScreenshotRegion(region),
+                Capturing,
+            ))
+            .id();
+        let despawned = main_world
+            .spawn((Screenshot::window(closed_window), Capturing))
+            .id();
+        main_world.despawn(despawned);
+        let duplicate = main_world.spawn(Screenshot::window(window)).id();
+
+        let target = |window| Screenshot::window(window).0.normalize(None).unwrap();
+        let mut retries = render_world.resource_mut::<RenderScreenshotRetries>();
+        retries.insert(retried, (target(window), Some(region)));
+        retries.insert(despawned, (target(closed_window), None));
+        render_world.insert_resource(main_world);
+        render_world.run_system_once(extract_screenshots).unwrap();
+
+        // The retried screenshot is captured again, in place of new ones of the same window.
+        let targets = render_world.resource::<RenderScreenshotTargets>();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets.get(&retried), Some(&target(window)));
+        assert_eq!(
+            render_world
+                .resource::<RenderScreenshotRegions>()
+                .get(&retried),
+            Some(&region)
+        );
+        assert!(
+            render_world
+                .resource::<RenderScreenshotRetries>()
+                .is_empty()
+        );
+        let main_world = render_world.resource::<MainWorld>();
+        assert!(main_world.get_entity(duplicate).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Runs the renderer without windows, like on servers and CI machines.
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU32, Ordering},
+};
+
+use bevy::{
+    anti_alias::AntiAliasPlugin, asset::RenderAssetUsages, camera::RenderTarget, color::LinearRgba,
+    core_pipeline::CorePipelinePlugin, ecs::schedule::ScheduleLabel,
+    gizmos_render::GizmoRenderPlugin, gltf::GltfPlugin, log::LogPlugin, pbr::PbrPlugin,
+    post_process::PostProcessPlugin, prelude::*, render::RenderPlugin,
+    sprite_render::SpriteRenderPlugin, ui_render::UiRenderPlugin, window::ExitCondition,
+    winit::WinitPlugin,
+};
+use bevy_robin_render::{
+    RobinPlugins,
+    render::{
+        Render, RenderApp, RenderSystems, RobinRenderPlugin,
+        camera::CameraRenderGraph,
+        error_handler::RenderStatus,
+        gpu_readback::{Readback, ReadbackComplete, ReadbackOnce},
+        render_asset::RenderAssets,
+        render_graph::{
+            Node, NodeRunError, RenderGraph, RenderGraphContext, RenderGraphErrorPolicy,
+            RenderPipeline,
+        },
+        render_phase::DrawError,
+        render_resource::{
+            CommandEncoderDescriptor, Extent3d, LoadOp, Operations, RenderPassColorAttachment,
+            RenderPassDescriptor, StoreOp, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        settings::{RenderCreation, WgpuSettings},
+        texture::GpuImage,
+    },
+};
+
+/// An app rendering without windows, or `None` if there is no adapter, not even a software one.
+fn headless_app() -> Option<App> {
+    let default_plugins = DefaultPlugins
+        .build()
+        .disable::<RenderPlugin>()
+        .disable::<CorePipelinePlugin>()
+        .disable::<PostProcessPlugin>()
+        .disable::<AntiAliasPlugin>()
+        .disable::<SpriteRenderPlugin>()
+        .disable::<UiRenderPlugin>()
+        .disable::<PbrPlugin>()
+        .disable::<GizmoRenderPlugin>()
+        .disable::<GltfPlugin>()
+        .disable::<WinitPlugin>()
+        .disable::<LogPlugin>()
+        .set(WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            ..default()
+        });
+    let robin_plugins = RobinPlugins.build().set(RobinRenderPlugin {
+        render_creation: RenderCreation::headless(WgpuSettings::default()),
+        synchronous_pipeline_compilation: true,
+        ..default()
+    });
+
+    let mut app = App::new();
+    app.add_plugins((default_plugins, robin_plugins));
+    app.get_sub_app(RenderApp)?;
+    app.finish();
+    app.cleanup();
+    Some(app)
+}
+
+#[test]
+fn headless_renderer_is_ready() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+
+    #[derive(Resource, Default)]
+    struct RenderedFrames(u32);
+
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app.init_resource::<RenderedFrames>();
+    render_app.add_systems(
+        Render,
+        (|mut frames: ResMut<RenderedFrames>| frames.0 += 1).in_set(RenderSystems::Cleanup),
+    );
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(*app.world().resource::<RenderStatus>(), RenderStatus::Ready);
+    assert_eq!(
+        app.sub_app(RenderApp)
+            .world()
+            .resource::<RenderedFrames>()
+            .0,
+        3
+    );
+}
+
+/// Reads back `image` once the renderer got to it.
+fn read_back(app: &mut App, image: Handle<Image>) -> Vec<u8> {
+    let result = Arc::new(Mutex::new(None));
+    let sender = result.clone();
+    app.world_mut().spawn(Readback::texture(image)).observe(
+        move |readback: On<ReadbackComplete>| {
+            *sender.lock().unwrap() = Some(readback.data.clone());
+        },
+    );
+
+    for _ in 0..100 {
+        app.update();
+        if result.lock().unwrap().is_some() {
+            break;
+        }
+    }
+
+    result
+        .lock()
+        .unwrap()
+        .take()
+        .expect("the readback never completed")
+}
+
+#[test]
+fn one_shot_readbacks_despawn_their_entity() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 64,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[1, 2, 3, 4],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_descriptor.usage |= TextureUsages::COPY_SRC;
+    let image = app.world_mut().resource_mut::<Assets<Image>>().add(image);
+
+    let spawn = |app: &mut App, once: bool| {
+        let completions = Arc::new(AtomicU32::new(0));
+        let counter = completions.clone();
+        let mut entity = app.world_mut().spawn(Readback::texture(image.clone()));
+        if once {
+            entity.insert(ReadbackOnce);
+        }
+        entity.observe(move |readback: On<ReadbackComplete>| {
+            assert!(
+                readback
+                    .data
+                    .chunks_exact(4)
+                    .all(|texel| texel == [1, 2, 3, 4])
+            );
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        (entity.id(), completions)
+    };
+    let (once, once_completions) = spawn(&mut app, true);
+    let (continuous, continuous_completions) = spawn(&mut app, false);
+
+    for _ in 0..100 {
+        app.update();
+        if continuous_completions.load(Ordering::Relaxed) >= 3 {
+            break;
+        }
+    }
+
+    assert!(continuous_completions.load(Ordering::Relaxed) >= 3);
+    assert!(app.world().get_entity(continuous).is_ok());
+    assert_eq!(once_completions.load(Ordering::Relaxed), 1);
+    assert!(app.world().get_entity(once).is_err());
+}
+
+/// The image cleared by [`clear_target`].
+#[derive(Resource)]
+struct ClearTarget(Handle<Image>);
+
+fn clear_target(
+    target: Res<ClearTarget>,
+    images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let Some(image) = images.get(&target.0) else {
+        return;
+    };
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("clear_target"),
+    });
+    encoder.begin_render_pass(&RenderPassDescriptor {
+        label: Some("clear_target_pass"),
+        color_attachments: &[Some(RenderPassColorAttachment {
+            view: &image.texture_view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(LinearRgba::new(1.0, 0.0, 1.0, 1.0).into()),
+                store: StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+        multiview_mask: None,
+    });
+    render_queue.submit([encoder.finish()]);
+}
+
+#[test]
+fn headless_offscreen_render_reads_back() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+    let image = app.world_mut().resource_mut::<Assets<Image>>().add(image);
+
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app.insert_resource(ClearTarget(image.clone()));
+    // Submitted before the readback copies, which are encoded in `RenderSystems::Render`.
+    render_app.add_systems(Render, clear_target.in_set(RenderSystems::Queue));
+
+    let data = read_back(&mut app, image);
+    assert_eq!(data.len(), 64 * 64 * 4);
+    assert!(
+        data.chunks_exact(4)
+            .all(|texel| texel == [255, 0, 255, 255])
+    );
+}
+
+/// A render graph whose only node fails.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct FailingGraph;
+
+struct FailingNode;
+
+impl Node for FailingNode {
+    fn run(&self, _: &mut RenderGraphContext, _: &World) -> Result<(), NodeRunError> {
+        Err(NodeRunError::DrawError(DrawError::InvalidViewQuery))
+    }
+}
+
+#[test]
+fn failing_views_are_cleared_to_the_error_color() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app.insert_resource(RenderGraphErrorPolicy::clear_to_error());
+    let mut pipeline = RenderPipeline::empty();
+    pipeline.push(FailingNode);
+    render_app
+        .world_mut()
+        .resource_mut::<RenderGraph>()
+        .add(FailingGraph, pipeline);
+
+    let mut image = Image::new_target_texture(
+        64,
+        64,
+        TextureFormat::Rgba8Unorm,
+        Some(TextureFormat::Rgba8UnormSrgb),
+    );
+    image.texture_descriptor.usage |= TextureUsages::COPY_SRC;
+    let image = app.world_mut().resource_mut::<Assets<Image>>().add(image);
+    app.world_mut().spawn((
+        Camera2d,
+        CameraRenderGraph::new(FailingGraph),
+        RenderTarget::Image(image.clone().into()),
+    ));
+
+    let data = read_back(&mut app, image);
+    assert_eq!(data.len(), 64 * 64 * 4);
+    // `ERROR_COLOR` is magenta.
+    assert!(
+        data.chunks_exact(4)
+            .all(|texel| texel == [255, 0, 255, 255])
+    );
+}